@@ -0,0 +1,485 @@
+/* Streaming G-Code Interpreter */
+/* Bytes Come In One At A Time From Serial Or TCP, Each Line Ending In CR Or LF Is Run And Answered ok Or error */
+/* G0/G1 Rapid And Linear Moves With F Feedrate, G4 P Dwell In Seconds, G28 Home, G90/G91 Absolute/Relative, */
+/* G20/G21 Inches/mm, M17/M18/M84 Enable/Disable Drivers, M3/M4/M5 S Spindle */
+/* X Y Z A Are The Motors In MOTORS Order, X Y Z In The Motors' Units (mm), A Rotary So Never Scaled By G20 */
+/* Moves Are Steps From Where MotorControl Says The Motors Are, So Send The Next Line After The Last Move's ok */
+
+use core::fmt;
+use super::{interpolate, math, position, MotorControl, Motors, MOTORS};
+
+/* Longest Line Kept, Comments Included */
+pub const MAX_LINE:         usize = 96;
+
+/* Axes G20 Scales, A Is Rotary */
+const LINEAR_AXES:          usize = 3;
+const AXIS_LETTERS:         [u8; interpolate::AXES] = [b'X', b'Y', b'Z', b'A'];
+
+const MM_PER_INCH:          f32 = 25.4;
+const SECONDS_PER_MINUTE:   f32 = 60.0;
+
+/* What A Line Asks Of The Axes */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Command {
+    Move {
+        delta:      [i32; interpolate::AXES],   // Steps, For interpolate::LinearMove::plan
+        velocity:   f32                         // Steps/s Of The Dominant Axis
+    },
+    Dwell(f32),                                 // Seconds
+    Home(u32),                                  // Bit Per Motor In MOTORS Order
+    Enable,
+    Disable,
+    Spindle {
+        on:         bool,
+        clockwise:  bool,
+        speed:      f32                         // S Word, RPM
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GcodeError {
+    LineTooLong,
+    BadWord,                                    // Not A Letter Followed By A Number
+    BadNumber,
+    Unsupported,                                // G Or M Code Not Handled Here
+    NoFeedrate,                                 // G1 Before Any F
+    NoDwell,                                    // G4 Without P
+    Limit(Motors, position::PositionError)
+}
+
+/* Answer To One Line, Ok With Nothing For A Blank Line Or One That Only Sets Modes */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Reply {
+    Ok(Option<Command>),
+    Error(GcodeError)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Motion {
+    Rapid,
+    Linear
+}
+
+/* Words Of One Line */
+struct Words {
+    g:              [Option<u32>; 4],
+    m:              Option<u32>,
+    axes:           [Option<f32>; interpolate::AXES],
+    f:              Option<f32>,
+    p:              Option<f32>,
+    s:              Option<f32>
+}
+
+/* Modal State, A Line's Changes Only Stick When The Whole Line Is Accepted */
+#[derive(Clone, Copy)]
+struct Modes {
+    absolute:       bool,                       // G90
+    inches:         bool,                       // G20
+    motion:         Motion,                     // Modal G0/G1
+    feed:           f32                         // mm/min, 0 Until An F Word
+}
+
+pub struct Interpreter {
+    line:           [u8; MAX_LINE],
+    len:            usize,
+    overflow:       bool,                       // Line Ran Past MAX_LINE, Answered With An Error
+    modes:          Modes,
+    rapid:          f32                         // mm/min For G0
+}
+
+impl GcodeError {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            GcodeError::LineTooLong => "line too long",
+            GcodeError::BadWord => "bad word",
+            GcodeError::BadNumber => "bad number",
+            GcodeError::Unsupported => "unsupported command",
+            GcodeError::NoFeedrate => "no feedrate",
+            GcodeError::NoDwell => "no dwell time",
+            GcodeError::Limit(_, position::PositionError::BelowMin) => "below soft limit",
+            GcodeError::Limit(_, position::PositionError::AboveMax) => "above soft limit",
+            GcodeError::Limit(_, position::PositionError::Invalid) => "invalid position"
+        };
+    }
+}
+
+impl Reply {
+    /* ok Or error: Reason, One Line */
+    pub fn write<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        return match self {
+            Reply::Ok(_) => write!(w, "ok\r\n"),
+            Reply::Error(error @ GcodeError::Limit(motor, _)) => {
+                let axis = MOTORS.iter().position(|m| m == motor).unwrap_or(0);
+                write!(w, "error: {} {}\r\n", AXIS_LETTERS[axis] as char, error.get_name())
+            } Reply::Error(error) => write!(w, "error: {}\r\n", error.get_name())
+        };
+    }
+}
+
+impl Words {
+    const fn init() -> Words {
+        return Words {
+            g:      [None; 4],
+            m:      None,
+            axes:   [None; interpolate::AXES],
+            f:      None,
+            p:      None,
+            s:      None
+        };
+    }
+}
+
+impl Interpreter {
+    /* Absolute mm, G0 At rapid mm/min */
+    pub const fn init(rapid: f32) -> Interpreter {
+        return Interpreter {
+            line:       [0; MAX_LINE],
+            len:        0,
+            overflow:   false,
+            modes:      Modes {
+                absolute:   true,
+                inches:     false,
+                motion:     Motion::Rapid,
+                feed:       0.0
+            },
+            rapid
+        };
+    }
+
+    /* One Byte Of The Stream, The Reply Once It Ends A Line */
+    pub fn feed(&mut self, byte: u8, control: &MotorControl) -> Option<Reply> {
+        if byte == b'\r' || byte == b'\n' {
+            let overflow = self.overflow;
+            let len = self.len;
+            self.len = 0;
+            self.overflow = false;
+
+            /* CR LF Is One Line End, Not An Empty Line After It */
+            if len == 0 && !overflow {
+                return None;
+            }
+            if overflow {
+                return Some(Reply::Error(GcodeError::LineTooLong));
+            }
+            let line = self.line;
+            return Some(self.execute(&line[..len], control));
+        }
+
+        if self.len < MAX_LINE {
+            self.line[self.len] = byte;
+            self.len += 1;
+        } else {
+            self.overflow = true;
+        }
+        return None;
+    }
+
+    /* Run One Whole Line, Without Its Line End */
+    pub fn execute(&mut self, line: &[u8], control: &MotorControl) -> Reply {
+        return match self.parse(line).and_then(|words| self.run(&words, control)) {
+            Ok(command) => Reply::Ok(command),
+            Err(error) => Reply::Error(error)
+        };
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        return self.modes.absolute;
+    }
+
+    pub fn is_inches(&self) -> bool {
+        return self.modes.inches;
+    }
+
+    /* Feedrate In mm/min, 0 Until Set */
+    pub fn get_feed(&self) -> f32 {
+        return self.modes.feed;
+    }
+
+    /* Split Into Words, Dropping Spaces, % Markers, ( ) And ; Comments And Any N Line Number */
+    fn parse(&self, line: &[u8]) -> Result<Words, GcodeError> {
+        let mut words = Words::init();
+        let mut g = 0;
+        let mut i = 0;
+        while i < line.len() {
+            let letter = line[i].to_ascii_uppercase();
+            i += 1;
+            match letter {
+                b' ' | b'\t' | b'%' => {
+                    continue;
+                } b';' => {
+                    break;
+                } b'(' => {
+                    while i < line.len() && line[i] != b')' {
+                        i += 1;
+                    }
+                    i += 1;
+                    continue;
+                } b'A'..=b'Z' => {
+                } _ => {
+                    return Err(GcodeError::BadWord);
+                }
+            }
+
+            let start = i;
+            while i < line.len() && (line[i].is_ascii_digit() || line[i] == b'.' || line[i] == b'-' || line[i] == b'+') {
+                i += 1;
+            }
+            let value = parse_number(&line[start..i])?;
+
+            match letter {
+                b'G' => {
+                    if g == words.g.len() {
+                        return Err(GcodeError::BadWord);
+                    }
+                    words.g[g] = Some(parse_code(value)?);
+                    g += 1;
+                } b'M' => {
+                    words.m = Some(parse_code(value)?);
+                } b'F' => {
+                    words.f = Some(value);
+                } b'P' => {
+                    words.p = Some(value);
+                } b'S' => {
+                    words.s = Some(value);
+                } b'N' => {
+                } _ => {
+                    match AXIS_LETTERS.iter().position(|&axis| axis == letter) {
+                        Some(axis) => {
+                            words.axes[axis] = Some(value);
+                        } None => {
+                            return Err(GcodeError::BadWord);
+                        }
+                    }
+                }
+            }
+        }
+        return Ok(words);
+    }
+
+    /* Modes First, Then At Most One Command, G4 Or G28, An M Code, Or A Move, The Modes Kept Only If It Is Ok */
+    fn run(&mut self, words: &Words, control: &MotorControl) -> Result<Option<Command>, GcodeError> {
+        let mut modes = self.modes;
+        let command = self.command(words, &mut modes, control)?;
+        self.modes = modes;
+        return Ok(command);
+    }
+
+    fn command(&self, words: &Words, modes: &mut Modes, control: &MotorControl) -> Result<Option<Command>, GcodeError> {
+        let mut nonmodal = None;
+        for &code in words.g.iter().flatten() {
+            match code {
+                0 => {
+                    modes.motion = Motion::Rapid;
+                } 1 => {
+                    modes.motion = Motion::Linear;
+                } 4 => {
+                    nonmodal = Some(4);
+                } 20 => {
+                    modes.inches = true;
+                } 21 => {
+                    modes.inches = false;
+                } 28 => {
+                    nonmodal = Some(28);
+                } 90 => {
+                    modes.absolute = true;
+                } 91 => {
+                    modes.absolute = false;
+                } _ => {
+                    return Err(GcodeError::Unsupported);
+                }
+            }
+        }
+        if let Some(feed) = words.f {
+            if feed <= 0.0 {
+                return Err(GcodeError::BadNumber);
+            }
+            modes.feed = modes.get_mm(feed);
+        }
+
+        match nonmodal {
+            Some(4) => {
+                return match words.p {
+                    Some(seconds) if seconds >= 0.0 => Ok(Some(Command::Dwell(seconds))),
+                    Some(_) => Err(GcodeError::BadNumber),
+                    None => Err(GcodeError::NoDwell)
+                };
+            } Some(_) => {
+                /* Axis Words Pick The Motors, None Homes Them All */
+                let mut mask = 0;
+                for (axis, value) in words.axes.iter().enumerate() {
+                    if value.is_some() {
+                        mask |= 1 << axis;
+                    }
+                }
+                return Ok(Some(Command::Home(if mask != 0 { mask } else { (1 << interpolate::AXES) - 1 })));
+            } None => {
+            }
+        }
+
+        if let Some(code) = words.m {
+            let speed = words.s.unwrap_or(0.0);
+            return match code {
+                3 | 4 => Ok(Some(Command::Spindle { on: true, clockwise: code == 3, speed })),
+                5 => Ok(Some(Command::Spindle { on: false, clockwise: true, speed })),
+                17 => Ok(Some(Command::Enable)),
+                18 | 84 => Ok(Some(Command::Disable)),
+                _ => Err(GcodeError::Unsupported)
+            };
+        }
+
+        if words.axes.iter().any(|value| value.is_some()) {
+            return self.plan_move(words, modes, control).map(Some);
+        }
+        return Ok(None);
+    }
+
+    fn plan_move(&self, words: &Words, modes: &Modes, control: &MotorControl) -> Result<Command, GcodeError> {
+        let mut values = words.axes;
+        for value in values.iter_mut().take(LINEAR_AXES).flatten() {
+            *value = modes.get_mm(*value);
+        }
+
+        let delta = if modes.absolute {
+            control.move_to(values)
+        } else {
+            control.move_by(values)
+        }.map_err(|(motor, error)| GcodeError::Limit(motor, error))?;
+
+        let feed = match modes.motion {
+            Motion::Rapid => self.rapid,
+            Motion::Linear => modes.feed
+        };
+        if feed <= 0.0 {
+            return Err(GcodeError::NoFeedrate);
+        }
+
+        /* Path Length In mm Over The Feedrate, Covered In The Dominant Axis's Steps */
+        /* A Is In Degrees So Only Counts On Its Own, F Is Then Degrees/min */
+        let mut length = 0.0;
+        let mut dominant = 0;
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            let units = control.get_motor_units(motor).get_units(delta[axis]);
+            if axis < LINEAR_AXES {
+                length += units * units;
+            }
+            dominant = core::cmp::max(dominant, delta[axis].unsigned_abs());
+        }
+        let mut length = math::sqrt(length);
+        if length == 0.0 {
+            length = control.get_motor_units(MOTORS[LINEAR_AXES]).get_units(delta[LINEAR_AXES]).abs();
+        }
+        let velocity = if length > 0.0 { (dominant as f32 * feed) / (length * SECONDS_PER_MINUTE) } else { 0.0 };
+
+        return Ok(Command::Move {
+            delta,
+            velocity
+        });
+    }
+}
+
+impl Modes {
+    fn get_mm(&self, value: f32) -> f32 {
+        return if self.inches { value * MM_PER_INCH } else { value };
+    }
+}
+
+/* Decimal Number With Optional Sign And Point, No Exponent */
+fn parse_number(text: &[u8]) -> Result<f32, GcodeError> {
+    let text = core::str::from_utf8(text).map_err(|_| GcodeError::BadNumber)?;
+    return match text.parse::<f32>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(if text.is_empty() { GcodeError::BadWord } else { GcodeError::BadNumber })
+    };
+}
+
+/* G And M Numbers Are Whole, G1.5 Style Subcodes Are Not Handled */
+fn parse_code(value: f32) -> Result<u32, GcodeError> {
+    if value < 0.0 || value != (value as u32) as f32 {
+        return Err(GcodeError::Unsupported);
+    }
+    return Ok(value as u32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* X At 100 Steps/mm With Soft Limits 0 - 300 mm, The Rest Unlimited In Steps */
+    fn control() -> MotorControl {
+        let mut control = MotorControl::init();
+        let mut units = position::Units::init();
+        units.set_scale(100.0);
+        units.set_limits(0.0, 300.0, position::Limit::Reject);
+        control.set_motor_units(Motors::Motor1, units);
+        return control;
+    }
+
+    /* A Move Of delta At velocity Steps/s, To Within f32 Rounding */
+    fn moves(reply: Reply, delta: [i32; interpolate::AXES], velocity: f32) -> bool {
+        return match reply {
+            Reply::Ok(Some(Command::Move { delta: planned, velocity: rate })) => {
+                planned == delta && (rate - velocity).abs() <= velocity * 1e-5
+            } _ => false
+        };
+    }
+
+    #[test]
+    fn rejected_line_keeps_the_modes() {
+        let control = control();
+        let mut gcode = Interpreter::init(1000.0);
+        assert!(gcode.execute(b"G1 F600", &control) == Reply::Ok(None));
+
+        let reply = gcode.execute(b"G91 G20 F5 X200", &control);
+        assert!(reply == Reply::Error(GcodeError::Limit(Motors::Motor1, position::PositionError::AboveMax)));
+        assert!(gcode.is_absolute() && !gcode.is_inches());
+        assert!(gcode.get_feed() == 600.0);
+
+        /* Still Absolute mm At F600 */
+        let reply = gcode.execute(b"X2", &control);
+        assert!(moves(reply, [200, 0, 0, 0], 1000.0));
+    }
+
+    #[test]
+    fn unsupported_code_keeps_the_modes() {
+        let control = control();
+        let mut gcode = Interpreter::init(1000.0);
+        assert!(gcode.execute(b"G91 G20 G99", &control) == Reply::Error(GcodeError::Unsupported));
+        assert!(gcode.execute(b"G1 F-5", &control) == Reply::Error(GcodeError::BadNumber));
+        assert!(gcode.is_absolute() && !gcode.is_inches());
+
+        /* Still Rapid, So No Feedrate Is Needed */
+        assert!(moves(gcode.execute(b"X1", &control), [100, 0, 0, 0], 100.0 * 1000.0 / 60.0));
+    }
+
+    #[test]
+    fn accepted_line_sets_the_modes() {
+        let control = control();
+        let mut gcode = Interpreter::init(1000.0);
+        assert!(moves(gcode.execute(b"G91 G20 G1 F1 X1", &control), [2540, 0, 0, 0], 2540.0 * 25.4 / (25.4 * 60.0)));
+        assert!(!gcode.is_absolute() && gcode.is_inches());
+        assert!((gcode.get_feed() - 25.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotary_axis_keeps_out_of_the_path_length() {
+        let control = control();
+        let mut gcode = Interpreter::init(1000.0);
+
+        /* 10 mm Of X At 600 mm/min Takes 1 s Whatever A Does */
+        assert!(moves(gcode.execute(b"G1 F600 X10 A90", &control), [1000, 0, 0, 90], 1000.0));
+        assert!(moves(gcode.execute(b"X10 Y10 A90", &control), [1000, 10, 0, 90], 1000.0 / 2.0_f32.sqrt()));
+
+        /* A Alone Goes At F Degrees/min */
+        assert!(moves(gcode.execute(b"A90", &control), [0, 0, 0, 90], 10.0));
+    }
+
+    #[test]
+    fn relative_move_checks_only_the_axes_given() {
+        /* X Sits Below Its Soft Limit, A Y Move Leaves It Alone */
+        let mut control = control();
+        control.set_motor_position(Motors::Motor1, -100);
+        let mut gcode = Interpreter::init(1000.0);
+        assert!(moves(gcode.execute(b"G91 G1 F600 Y10", &control), [0, 10, 0, 0], 10.0));
+        assert!(gcode.execute(b"X-1", &control) == Reply::Error(GcodeError::Limit(Motors::Motor1, position::PositionError::BelowMin)));
+    }
+}
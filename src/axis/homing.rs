@@ -0,0 +1,327 @@
+/* Homing Against A Limit Switch */
+/* Seek The Switch Fast, Back Off Until It Releases And A Little More, Approach Again Slowly And Latch On The Switch */
+/* Edge, Or Carry On To The Next Index Pulse, The Axis Is Then At The Home Offset */
+/* It Is A StepProfile, Each Step's Interval Comes From next_interval And The Inputs Seen After It From set_inputs, */
+/* Elapsed Time Is The Sum Of Those Intervals */
+
+use super::{MotorDirection, StepProfile};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phase {
+    Seek,           // Fast, Toward The Switch
+    Release,        // Slow, Away Until The Switch Releases Plus backoff Steps
+    Approach,       // Slow, Toward The Switch Again
+    Index,          // Slow, On From The Switch To The Next Index Pulse
+    Done,
+    Faulted
+}
+
+/* What Ends The Approach */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Latch {
+    Switch,
+    Index
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HomingError {
+    SwitchNotFound,     // Nothing Within max_travel
+    SwitchStuck,        // Still Active After Backing Off max_travel
+    IndexNotFound,      // Nothing Within index_window After The Switch
+    Timeout
+}
+
+#[derive(Clone, Copy)]
+pub struct HomingConfig {
+    pub direction:      MotorDirection,     // Toward The Switch
+    pub fast:           f32,                // Seek Velocity, Steps/s
+    pub slow:           f32,                // Back Off And Approach Velocity, Steps/s
+    pub backoff:        u32,                // Steps Past The Switch Releasing
+    pub max_travel:     u32,                // Steps Any Phase May Take
+    pub index_window:   u32,                // Steps To Find The Index In, One Revolution Say
+    pub latch:          Latch,
+    pub offset:         i32,                // Position Of The Latch Point, Steps
+    pub timeout:        f32                 // Seconds For The Whole Sequence
+}
+
+pub struct Homing {
+    config:         HomingConfig,
+    phase:          Phase,
+    steps:          u32,                    // Steps Taken In This Phase
+    released:       u32,                    // Release Steps Taken Since The Switch Let Go
+    elapsed:        f32,                    // Seconds Of Steps Handed Out
+    switch:         bool,                   // Debounced, Active When Pressed
+    index:          bool,
+    error:          Option<HomingError>
+}
+
+impl Homing {
+    pub fn init(config: HomingConfig) -> Homing {
+        return Homing {
+            config,
+            phase:      Phase::Seek,
+            steps:      0,
+            released:   0,
+            elapsed:    0.0,
+            switch:     false,
+            index:      false,
+            error:      None
+        };
+    }
+
+    /* Inputs After The Last Step, Before Asking For The Next */
+    pub fn set_inputs(&mut self, switch: bool, index: bool) {
+        self.switch = switch;
+        self.index = index;
+    }
+
+    pub fn get_phase(&self) -> Phase {
+        return self.phase;
+    }
+
+    pub fn get_error(&self) -> Option<HomingError> {
+        return self.error;
+    }
+
+    pub fn is_done(&self) -> bool {
+        return self.phase == Phase::Done;
+    }
+
+    pub fn is_faulted(&self) -> bool {
+        return self.phase == Phase::Faulted;
+    }
+
+    /* Direction Of The Step next_interval Last Handed Out, For The DIR Output */
+    pub fn get_direction(&self) -> MotorDirection {
+        if self.phase == Phase::Release {
+            return match self.config.direction {
+                MotorDirection::Forward => MotorDirection::Reverse,
+                MotorDirection::Reverse => MotorDirection::Forward
+            };
+        }
+        return self.config.direction;
+    }
+
+    /* Position The Axis Is At Once Done */
+    pub fn get_home(&self) -> i32 {
+        return self.config.offset;
+    }
+
+    pub fn get_elapsed(&self) -> f32 {
+        return self.elapsed;
+    }
+
+    /* Start Over From Seek */
+    pub fn reset(&mut self) {
+        *self = Homing::init(self.config);
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.steps = 0;
+        self.released = 0;
+    }
+
+    fn fault(&mut self, error: HomingError) {
+        self.enter(Phase::Faulted);
+        self.error = Some(error);
+    }
+
+    /* Move On From The Current Phase If The Inputs Say So, May Pass Through More Than One */
+    fn update(&mut self) {
+        match self.phase {
+            Phase::Seek => {
+                if self.switch {
+                    self.enter(Phase::Release);
+                } else if self.steps >= self.config.max_travel {
+                    self.fault(HomingError::SwitchNotFound);
+                }
+            } Phase::Release => {
+                if !self.switch && self.released >= self.config.backoff {
+                    self.enter(Phase::Approach);
+                } else if self.switch && self.steps >= self.config.max_travel {
+                    self.fault(HomingError::SwitchStuck);
+                }
+            } Phase::Approach => {
+                if self.switch {
+                    match self.config.latch {
+                        Latch::Switch => {
+                            self.enter(Phase::Done);
+                        } Latch::Index => {
+                            self.enter(Phase::Index);
+                            self.update();
+                        }
+                    }
+                } else if self.steps >= self.config.max_travel {
+                    self.fault(HomingError::SwitchNotFound);
+                }
+            } Phase::Index => {
+                if self.index {
+                    self.enter(Phase::Done);
+                } else if self.steps >= self.config.index_window {
+                    self.fault(HomingError::IndexNotFound);
+                }
+            } Phase::Done | Phase::Faulted => {
+            }
+        }
+    }
+}
+
+impl StepProfile for Homing {
+    /* None Once Done Or Faulted, Check Which With is_done */
+    fn next_interval(&mut self) -> Option<f32> {
+        self.update();
+        let velocity = match self.phase {
+            Phase::Seek => self.config.fast,
+            Phase::Release | Phase::Approach | Phase::Index => self.config.slow,
+            Phase::Done | Phase::Faulted => return None
+        };
+
+        /* A Velocity Of 0 Would Never Get There */
+        if velocity.is_nan() || velocity <= 0.0 {
+            self.fault(HomingError::Timeout);
+            return None;
+        }
+
+        let interval = 1.0 / velocity;
+        if self.elapsed + interval > self.config.timeout {
+            self.fault(HomingError::Timeout);
+            return None;
+        }
+        self.elapsed += interval;
+        self.steps += 1;
+        if self.phase == Phase::Release && !self.switch {
+            self.released += 1;
+        }
+        return Some(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWITCH:           i32 = 1000;     // Switch Pressed From Here On
+    const REVOLUTION:       i32 = 256;      // Index Pulse Every Revolution
+
+    fn config(latch: Latch) -> HomingConfig {
+        return HomingConfig {
+            direction:      MotorDirection::Forward,
+            fast:           10000.0,
+            slow:           1000.0,
+            backoff:        20,
+            max_travel:     5000,
+            index_window:   REVOLUTION as u32,
+            latch,
+            offset:         -50,
+            timeout:        10.0
+        };
+    }
+
+    /* Step The Sequence Against An Axis Starting At 0, switch Says Whether It Is Pressed At A Position */
+    /* A Step Goes The Way get_direction Says Once next_interval Has Handed It Out */
+    /* Returns Where It Stopped And The Phases In The Order Seen */
+    fn run(homing: &mut Homing, switch: fn(i32) -> bool, index: fn(i32) -> bool) -> (i32, Vec<Phase>) {
+        let mut position = 0;
+        let mut phases = vec![homing.get_phase()];
+        loop {
+            if homing.next_interval().is_none() {
+                break;
+            }
+            position += if homing.get_direction() == MotorDirection::Forward { 1 } else { -1 };
+            homing.set_inputs(switch(position), index(position));
+            if *phases.last().unwrap() != homing.get_phase() {
+                phases.push(homing.get_phase());
+            }
+        }
+        if *phases.last().unwrap() != homing.get_phase() {
+            phases.push(homing.get_phase());
+        }
+        return (position, phases);
+    }
+
+    fn pressed(position: i32) -> bool {
+        return position >= SWITCH;
+    }
+
+    fn on_index(position: i32) -> bool {
+        return position % REVOLUTION == 0;
+    }
+
+    #[test]
+    fn latches_on_the_switch() {
+        let mut homing = Homing::init(config(Latch::Switch));
+        let (position, phases) = run(&mut homing, pressed, |_| false);
+        assert!(phases == [Phase::Seek, Phase::Release, Phase::Approach, Phase::Done]);
+        assert!(homing.is_done() && homing.get_error().is_none());
+        assert!(position == SWITCH);
+        assert!(homing.get_home() == -50);
+    }
+
+    #[test]
+    fn latches_on_the_index_past_the_switch() {
+        let mut homing = Homing::init(config(Latch::Index));
+        let (position, phases) = run(&mut homing, pressed, on_index);
+        assert!(phases == [Phase::Seek, Phase::Release, Phase::Approach, Phase::Index, Phase::Done]);
+        assert!(position == 4 * REVOLUTION);
+    }
+
+    #[test]
+    fn release_backs_off_past_the_switch() {
+        /* Seek Overruns By One Step, Release Goes backoff Steps Beyond Where The Switch Lets Go */
+        let mut homing = Homing::init(config(Latch::Switch));
+        let mut position = 0;
+        let mut lowest = i32::MAX;
+        while homing.next_interval().is_some() {
+            if homing.get_phase() == Phase::Approach {
+                break;
+            }
+            position += if homing.get_direction() == MotorDirection::Forward { 1 } else { -1 };
+            if homing.get_phase() == Phase::Release {
+                lowest = lowest.min(position);
+            }
+            homing.set_inputs(pressed(position), false);
+        }
+        assert!(lowest == SWITCH - 1 - 20);
+    }
+
+    #[test]
+    fn faults_without_the_switch() {
+        let mut homing = Homing::init(config(Latch::Switch));
+        let (position, phases) = run(&mut homing, |_| false, |_| false);
+        assert!(phases == [Phase::Seek, Phase::Faulted]);
+        assert!(homing.get_error() == Some(HomingError::SwitchNotFound));
+        assert!(position == 5000);
+    }
+
+    #[test]
+    fn faults_on_a_stuck_switch() {
+        let mut homing = Homing::init(config(Latch::Switch));
+        let (position, phases) = run(&mut homing, |_| true, |_| false);
+        assert!(phases == [Phase::Seek, Phase::Release, Phase::Faulted]);
+        assert!(homing.get_error() == Some(HomingError::SwitchStuck));
+        assert!(position == 1 - 5000);
+    }
+
+    #[test]
+    fn faults_without_the_index() {
+        let mut homing = Homing::init(config(Latch::Index));
+        let (_, phases) = run(&mut homing, pressed, |_| false);
+        assert!(phases == [Phase::Seek, Phase::Release, Phase::Approach, Phase::Index, Phase::Faulted]);
+        assert!(homing.get_error() == Some(HomingError::IndexNotFound));
+    }
+
+    #[test]
+    fn times_out() {
+        /* The Seek Alone Takes 0.1 s */
+        let mut homing = Homing::init(HomingConfig { timeout: 0.05, ..config(Latch::Switch) });
+        let (position, _) = run(&mut homing, pressed, |_| false);
+        assert!(homing.get_error() == Some(HomingError::Timeout));
+        assert!(homing.get_elapsed() <= 0.05);
+        assert!((position - 500).abs() <= 1);
+
+        /* reset Starts Over */
+        homing.reset();
+        assert!(homing.get_phase() == Phase::Seek && homing.get_error().is_none());
+    }
+}
@@ -0,0 +1,171 @@
+/* Coordinated Straight Line Moves Across Every Axis */
+/* The Axis With The Most Steps Is Dominant And Follows The Motion Profile, One Interrupt Per Dominant Step, */
+/* The Others Step On Some Of Those Interrupts By Bresenham (DDA) Error Terms, So All Start And Finish Together */
+
+use super::{MotorDirection, StepProfile};
+
+/* Axes A Move Covers, One Per Motor In MotorControl */
+pub const AXES:             usize = 4;
+
+/* What One Step Interrupt Does */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Steps {
+    pub mask:       u32,                    // Bit Per Axis That Steps
+    pub interval:   f32                     // Seconds Since The Previous Step, Or The Start
+}
+
+pub struct LinearMove<P: StepProfile> {
+    delta:          [u32; AXES],            // Steps Per Axis
+    direction:      [MotorDirection; AXES],
+    dominant:       u32,                    // Steps Of The Longest Axis
+    error:          [u32; AXES],            // Bresenham Error Per Axis
+    step:           u32,                    // Dominant Steps Taken
+    profile:        P                       // Timing Of The Dominant Axis
+}
+
+impl<P: StepProfile> LinearMove<P> {
+    /* delta Steps Per Axis, Negative Is Reverse, plan Builds The Profile For The Dominant Axis From Its Step Count: */
+    /*     LinearMove::plan([400, -120, 0, 35], |steps| Trapezoid::plan(steps, 2000.0, 8000.0)) */
+    pub fn plan<F: FnOnce(u32) -> P>(delta: [i32; AXES], plan: F) -> LinearMove<P> {
+        let mut steps = [0; AXES];
+        let mut direction = [MotorDirection::Forward; AXES];
+        for axis in 0..AXES {
+            steps[axis] = delta[axis].unsigned_abs();
+            if delta[axis] < 0 {
+                direction[axis] = MotorDirection::Reverse;
+            }
+        }
+        let dominant = steps.iter().copied().max().unwrap_or(0);
+
+        /* Starting Halfway Centres Each Axis's Steps Between The Dominant Ones */
+        return LinearMove {
+            delta:      steps,
+            direction,
+            dominant,
+            error:      [dominant / 2; AXES],
+            step:       0,
+            profile:    plan(dominant)
+        };
+    }
+
+    pub fn get_dominant(&self) -> u32 {
+        return self.dominant;
+    }
+
+    /* Steps axis Takes Over The Whole Move */
+    pub fn get_delta(&self, axis: usize) -> u32 {
+        return self.delta[axis];
+    }
+
+    pub fn get_direction(&self, axis: usize) -> MotorDirection {
+        return self.direction[axis];
+    }
+
+    /* Dominant Steps Left */
+    pub fn get_remaining(&self) -> u32 {
+        return self.dominant - self.step;
+    }
+
+    /* Axes That Move At All */
+    pub fn get_axes(&self) -> u32 {
+        let mut mask = 0;
+        for axis in 0..AXES {
+            if self.delta[axis] != 0 {
+                mask |= 1 << axis;
+            }
+        }
+        return mask;
+    }
+
+    pub fn get_profile(&self) -> &P {
+        return &self.profile;
+    }
+
+    /* Next Interrupt's Steps, None Once Every Axis Is There */
+    pub fn next(&mut self) -> Option<Steps> {
+        if self.step >= self.dominant {
+            return None;
+        }
+        let interval = self.profile.next_interval().unwrap_or(0.0);
+        self.step += 1;
+
+        let mut mask = 0;
+        for axis in 0..AXES {
+            self.error[axis] += self.delta[axis];
+            if self.error[axis] >= self.dominant {
+                self.error[axis] -= self.dominant;
+                mask |= 1 << axis;
+            }
+        }
+        return Some(Steps {
+            mask,
+            interval
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::trapezoid::Trapezoid;
+
+    fn plan(delta: [i32; AXES]) -> LinearMove<Trapezoid> {
+        return LinearMove::plan(delta, |steps| Trapezoid::plan(steps, 2000.0, 8000.0));
+    }
+
+    /* Steps Per Axis Over The Whole Move, Checking Every Tick On The Way */
+    fn run(linear: &mut LinearMove<Trapezoid>) -> [u32; AXES] {
+        let dominant = (0..AXES).max_by_key(|&axis| linear.get_delta(axis)).unwrap();
+        let mut total = [0; AXES];
+        let mut ticks = 0;
+        while let Some(steps) = linear.next() {
+            assert!((steps.mask & (1 << dominant)) != 0, "tick {} without the dominant axis", ticks);
+            assert!(steps.mask < (1 << AXES));
+            for (axis, count) in total.iter_mut().enumerate() {
+                *count += (steps.mask >> axis) & 1;
+            }
+            ticks += 1;
+        }
+        assert!(ticks == linear.get_dominant());
+        return total;
+    }
+
+    #[test]
+    fn every_axis_finishes_its_delta() {
+        for &delta in [[400, -120, 0, 35], [-7, 7, 6, -1], [1000, 999, 1, 500], [3, 0, 0, 0]].iter() {
+            let mut linear = plan(delta);
+            let total = run(&mut linear);
+            for axis in 0..AXES {
+                assert!(total[axis] == delta[axis].unsigned_abs(), "axis {} of {:?}", axis, delta);
+                let direction = if delta[axis] < 0 { MotorDirection::Reverse } else { MotorDirection::Forward };
+                assert!(linear.get_direction(axis) == direction);
+            }
+            assert!(linear.get_remaining() == 0);
+        }
+    }
+
+    #[test]
+    fn minor_axes_spread_their_steps() {
+        /* A Minor Axis Steps At Most Once A Tick, And Never Goes Longer Than dominant / delta Ticks Rounded Up Without One */
+        let mut linear = plan([90, 30, 45, 89]);
+        let mut since = [0; AXES];
+        while let Some(steps) = linear.next() {
+            for (axis, since) in since.iter_mut().enumerate().skip(1) {
+                *since += 1;
+                if (steps.mask & (1 << axis)) != 0 {
+                    assert!(*since <= 90_u32.div_ceil(linear.get_delta(axis)));
+                    *since = 0;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zero_length_move_stays_empty() {
+        let mut linear = plan([0; AXES]);
+        assert!(linear.get_dominant() == 0 && linear.get_axes() == 0);
+        assert!(linear.next().is_none());
+        assert!(linear.next().is_none());
+        assert!(linear.get_remaining() == 0);
+    }
+}
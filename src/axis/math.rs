@@ -0,0 +1,46 @@
+/* Float Functions core Leaves To libm, Accurate To A Few ULP Which Is Plenty For Motion Planning */
+
+/* Newton Steps After The Bit Level Estimate */
+const ITERATIONS:           u32 = 4;
+
+/* Square Root, 0 For Anything Not Above 0 */
+pub fn sqrt(x: f32) -> f32 {
+    if x <= 0.0 || x.is_nan() {
+        return 0.0;
+    }
+    if x.is_infinite() {
+        return x;
+    }
+
+    /* Halving The Exponent Gets Within A Few Percent */
+    let mut y = f32::from_bits((x.to_bits() >> 1) + 0x1FC00000);
+    for _ in 0..ITERATIONS {
+        y = 0.5 * (y + x / y);
+    }
+    return y;
+}
+
+/* Cube Root, Keeps The Sign */
+pub fn cbrt(x: f32) -> f32 {
+    if x == 0.0 || x.is_nan() || x.is_infinite() {
+        return x;
+    }
+
+    /* Dividing The Exponent By Three The Same Way */
+    let a = x.abs();
+    let mut y = f32::from_bits((a.to_bits() / 3) + 0x2A5137A0);
+    for _ in 0..ITERATIONS {
+        y = ((2.0 * y) + (a / (y * y))) / 3.0;
+    }
+    return if x < 0.0 { -y } else { y };
+}
+
+/* Nearest Whole Number, Halves Away From 0, Saturating At The i32 Range And 0 For NaN */
+pub fn round(x: f32) -> i32 {
+    return if x < 0.0 { (x - 0.5) as i32 } else { (x + 0.5) as i32 };
+}
+
+/* As round For f64, Where Step Positions Past 2^24 Are Still Whole */
+pub fn round_wide(x: f64) -> i32 {
+    return if x < 0.0 { (x - 0.5) as i32 } else { (x + 0.5) as i32 };
+}
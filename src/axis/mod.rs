@@ -0,0 +1,507 @@
+use crate::stm32hal::timer;
+
+/* Public Modules */
+pub mod math;
+pub mod trapezoid;
+pub mod scurve;
+pub mod interpolate;
+pub mod homing;
+pub mod switch;
+pub mod stepdir;
+pub mod position;
+pub mod gcode;
+pub mod state;
+pub mod pid;
+pub mod servo;
+#[cfg(any(test, feature = "sim"))]
+pub mod plant;
+
+/* Enumeration For Movement Of The Motor */
+#[derive(Clone, Copy, PartialEq)]
+pub enum MotorDirection {
+    Reverse,
+    Forward
+}
+
+/* Enumeration For States Of The Motor, Changed Only As state::check Allows */
+#[derive(Clone, Copy, PartialEq)]
+pub enum MotorState {
+    BootUp,
+    Stopped,
+    Operational,
+    PreOperational,
+    Faulted(state::Fault),
+    Homing
+}
+
+/* Enumeration For States Of The Motor */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Motors {
+    Motor1,
+    Motor2,
+    Motor3,
+    Motor4
+}
+
+
+/* Structure Of Individual Motors */
+struct MotorType {
+    direction:      MotorDirection,         // Direction To Move In
+    state:          MotorState,             // State Of The Motor
+    count:          u32,                    // Pulse Count To Count To
+    position:       i32,                    // Absolute, Steps From Home
+    units:          position::Units         // Scaling And Soft Limits
+}
+
+const ZERO:         u32 = 0;
+
+/* Slowest Step Rate pulse Sets, The Timer Cannot Count Below 1 Hz */
+const MIN_RATE:     u32 = 1;
+
+/* Motors In Axis Order Of interpolate::LinearMove */
+pub const MOTORS:   [Motors; interpolate::AXES] = [Motors::Motor1, Motors::Motor2, Motors::Motor3, Motors::Motor4];
+
+/* Motion Profile Timed Step By Step */
+pub trait StepProfile {
+    /* Seconds From The Previous Step (Or The Start) To The Next, None Once The Move Is Done */
+    fn next_interval(&mut self) -> Option<f32>;
+}
+
+impl MotorType {
+    pub const fn init() -> MotorType {
+        return MotorType {
+            direction:  MotorDirection::Reverse,
+            state:      MotorState::BootUp,
+            count:      ZERO,
+            position:   0,
+            units:      position::Units::init()
+        }
+    }
+}
+
+/* Structure Of Motor Control */
+pub struct MotorControl {
+    motor1:         MotorType,              // Motor 1 Structure
+    motor2:         MotorType,              // Motor 2 Structure
+    motor3:         MotorType,              // Motor 3 Structure
+    motor4:         MotorType,              // Motor 4 Structure
+    count:          u32,                    // Pulse Count
+    toggle:         bool                    // Toggle Bit To Indicate Heartbeat Of Interrupt
+}
+
+impl MotorControl {
+    pub const fn init() -> MotorControl {
+        return MotorControl {
+            motor1:     MotorType::init(),
+            motor2:     MotorType::init(),
+            motor3:     MotorType::init(),
+            motor4:     MotorType::init(),
+            count:      0,
+            toggle:     false
+        }
+    }
+
+    pub fn get_motor_direction(&self, motor: Motors) -> MotorDirection {
+        match motor {
+            Motors::Motor1 => {
+                return self.motor1.direction;
+            } Motors::Motor2 => {
+                return self.motor2.direction;
+            } Motors::Motor3 => {
+                return self.motor3.direction;
+            } Motors::Motor4 => {
+                return self.motor4.direction;
+            }
+        }
+    }
+
+    pub fn set_motor_direction(&mut self, motor: Motors, motor_dir: MotorDirection) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.direction = motor_dir;
+            } Motors::Motor2 => {
+                self.motor2.direction = motor_dir;
+            } Motors::Motor3 => {
+                self.motor3.direction = motor_dir;
+            } Motors::Motor4 => {
+                self.motor4.direction = motor_dir;   
+            }
+        }
+    }
+
+    pub fn get_motor_state(&self, motor: Motors) -> MotorState {
+        match motor {
+            Motors::Motor1 => {
+                return self.motor1.state;
+            } Motors::Motor2 => {
+                return self.motor2.state;
+            } Motors::Motor3 => {
+                return self.motor3.state;
+            } Motors::Motor4 => {
+                return self.motor4.state;
+            }
+        }
+    }
+
+    /* Only The Changes state::check Allows, Use ack_fault To Leave Faulted */
+    pub fn set_motor_state(&mut self, motor: Motors, motor_state: MotorState) -> Result<(), state::StateError> {
+        state::check(self.get_motor_state(motor), motor_state)?;
+        self.put_motor_state(motor, motor_state);
+        return Ok(());
+    }
+
+    /* Fault A Motor From Any State, A Motor Already Faulted Keeps Its First Cause */
+    pub fn set_motor_fault(&mut self, motor: Motors, fault: state::Fault) {
+        if self.get_motor_fault(motor).is_none() {
+            self.put_motor_state(motor, MotorState::Faulted(fault));
+        }
+    }
+
+    pub fn get_motor_fault(&self, motor: Motors) -> Option<state::Fault> {
+        return match self.get_motor_state(motor) {
+            MotorState::Faulted(fault) => Some(fault),
+            _ => None
+        };
+    }
+
+    /* Clear A Fault Once Its Cause Is Dealt With, The Motor Is Then Stopped */
+    pub fn ack_fault(&mut self, motor: Motors) -> Result<state::Fault, state::StateError> {
+        let fault = self.get_motor_fault(motor).ok_or(state::StateError::NotFaulted)?;
+        self.put_motor_state(motor, MotorState::Stopped);
+        return Ok(fault);
+    }
+
+    /* Emergency Stop, Faults Every Motor */
+    pub fn estop(&mut self) {
+        for &motor in MOTORS.iter() {
+            self.set_motor_fault(motor, state::Fault::EStop);
+        }
+    }
+
+    fn put_motor_state(&mut self, motor: Motors, motor_state: MotorState) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.state = motor_state;
+            } Motors::Motor2 => {
+                self.motor2.state = motor_state;
+            } Motors::Motor3 => {
+                self.motor3.state = motor_state;
+            } Motors::Motor4 => {
+                self.motor4.state = motor_state;   
+            }
+        }
+    }
+
+    pub fn get_motor_count(&self, motor: Motors) -> u32 {
+        match motor {
+            Motors::Motor1 => {
+                return self.motor1.count;
+            } Motors::Motor2 => {
+                return self.motor2.count;
+            } Motors::Motor3 => {
+                return self.motor3.count;
+            } Motors::Motor4 => {
+                return self.motor4.count;
+            }
+        }
+    }
+
+    pub fn clr_motor_count(&mut self, motor: Motors) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.count = ZERO;
+            } Motors::Motor2 => {
+                self.motor2.count = ZERO;
+            } Motors::Motor3 => {
+                self.motor3.count = ZERO;
+            } Motors::Motor4 => {
+                self.motor4.count = ZERO;   
+            }
+        }
+    }
+
+    pub fn set_motor_count(&mut self, motor: Motors, cnt: u32) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.count = cnt;
+            } Motors::Motor2 => {
+                self.motor2.count = cnt;
+            } Motors::Motor3 => {
+                self.motor3.count = cnt;
+            } Motors::Motor4 => {
+                self.motor4.count = cnt;   
+            }
+        }
+    }
+
+    pub fn get_motor_position(&self, motor: Motors) -> i32 {
+        match motor {
+            Motors::Motor1 => {
+                return self.motor1.position;
+            } Motors::Motor2 => {
+                return self.motor2.position;
+            } Motors::Motor3 => {
+                return self.motor3.position;
+            } Motors::Motor4 => {
+                return self.motor4.position;
+            }
+        }
+    }
+
+    pub fn set_motor_position(&mut self, motor: Motors, steps: i32) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.position = steps;
+            } Motors::Motor2 => {
+                self.motor2.position = steps;
+            } Motors::Motor3 => {
+                self.motor3.position = steps;
+            } Motors::Motor4 => {
+                self.motor4.position = steps;
+            }
+        }
+    }
+
+    pub fn get_motor_units(&self, motor: Motors) -> position::Units {
+        match motor {
+            Motors::Motor1 => {
+                return self.motor1.units;
+            } Motors::Motor2 => {
+                return self.motor2.units;
+            } Motors::Motor3 => {
+                return self.motor3.units;
+            } Motors::Motor4 => {
+                return self.motor4.units;
+            }
+        }
+    }
+
+    pub fn set_motor_units(&mut self, motor: Motors, units: position::Units) {
+        match motor {
+            Motors::Motor1 => {
+                self.motor1.units = units;
+            } Motors::Motor2 => {
+                self.motor2.units = units;
+            } Motors::Motor3 => {
+                self.motor3.units = units;
+            } Motors::Motor4 => {
+                self.motor4.units = units;
+            }
+        }
+    }
+
+    /* Position In The Motor's Units */
+    pub fn get_motor_location(&self, motor: Motors) -> f32 {
+        return self.get_motor_units(motor).get_units(self.get_motor_position(motor));
+    }
+
+    /* Count The Steps Of One Step Interrupt, Bit Per Motor In MOTORS Order, Each Way Its Direction Says */
+    pub fn add_steps(&mut self, mask: u32) {
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            if (mask & (1 << axis)) != 0 {
+                let position = self.get_motor_position(motor);
+                self.set_motor_position(motor, match self.get_motor_direction(motor) {
+                    MotorDirection::Forward => position.wrapping_add(1),
+                    MotorDirection::Reverse => position.wrapping_sub(1)
+                });
+            }
+        }
+    }
+
+    /* Steps Per Motor For interpolate::LinearMove::plan Taking Each Motor To An Absolute Position In Units, None Stays Put */
+    /* The First Motor Whose Target Fails Its Soft Limits Fails The Whole Move */
+    pub fn move_to(&self, targets: [Option<f32>; interpolate::AXES]) -> Result<[i32; interpolate::AXES], (Motors, position::PositionError)> {
+        let mut delta = [0; interpolate::AXES];
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            if let Some(units) = targets[axis] {
+                let target = self.get_motor_units(motor).get_target(units).map_err(|error| (motor, error))?;
+                delta[axis] = target.saturating_sub(self.get_motor_position(motor));
+            }
+        }
+        return Ok(delta);
+    }
+
+    /* As move_to But Each Motor Moves offsets Units From Where It Is, None Stays Put Unchecked */
+    pub fn move_by(&self, offsets: [Option<f32>; interpolate::AXES]) -> Result<[i32; interpolate::AXES], (Motors, position::PositionError)> {
+        let mut delta = [0; interpolate::AXES];
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            if let Some(units) = offsets[axis] {
+                let position = self.get_motor_position(motor);
+                let target = self.get_motor_units(motor).get_offset(position, units).map_err(|error| (motor, error))?;
+                delta[axis] = target.saturating_sub(position);
+            }
+        }
+        return Ok(delta);
+    }
+
+    /* State Of The Machine, From Every Motor's By state::combine */
+    pub fn get_state(&self) -> MotorState {
+        let mut states = [MotorState::BootUp; interpolate::AXES];
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            states[axis] = self.get_motor_state(motor);
+        }
+        return state::combine(&states);
+    }
+
+    pub fn get_count(&self) -> u32 {
+        return self.count;
+    }
+
+    pub fn clr_count(&mut self) {
+        self.count = ZERO;
+    }
+
+    pub fn add_count(&mut self) {
+        self.count += 1;
+    }
+
+    pub fn get_toggle(&self) -> bool {
+        return self.toggle;
+    }
+
+    pub fn clr_toggle(&mut self) {
+        self.toggle = false;
+    }
+
+    pub fn set_toggle(&mut self) {
+        self.toggle = true;
+    }
+
+    /* Set Every Motor Up For A Coordinated Move, Those Taking Part Are Operational Until finish_move */
+    /* Nothing Changes Unless Every Motor Taking Part May Become Operational */
+    pub fn start_move<P: StepProfile>(&mut self, linear: &interpolate::LinearMove<P>) -> Result<(), (Motors, state::StateError)> {
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            if linear.get_delta(axis) != ZERO {
+                state::check(self.get_motor_state(motor), MotorState::Operational).map_err(|error| (motor, error))?;
+            }
+        }
+
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            let steps = linear.get_delta(axis);
+            self.set_motor_count(motor, steps);
+            if steps != ZERO {
+                self.set_motor_direction(motor, linear.get_direction(axis));
+                self.put_motor_state(motor, MotorState::Operational);
+            }
+        }
+        self.clr_count();
+        return Ok(());
+    }
+
+    /* Every Axis Of A Coordinated Move Arrives On The Same Step, Any That Faulted On The Way Stay Faulted */
+    pub fn finish_move(&mut self) {
+        for &motor in MOTORS.iter() {
+            if self.get_motor_state(motor) == MotorState::Operational {
+                self.put_motor_state(motor, MotorState::Stopped);
+            }
+        }
+    }
+
+    /* Hand A Motor To A Homing Sequence */
+    pub fn start_homing(&mut self, motor: Motors) -> Result<(), state::StateError> {
+        self.set_motor_state(motor, MotorState::Homing)?;
+        self.clr_motor_count(motor);
+        return Ok(());
+    }
+
+    /* Once The Sequence Ends, Operational At Home Or Faulted */
+    pub fn finish_homing(&mut self, motor: Motors, homing: &homing::Homing) {
+        if self.get_motor_state(motor) != MotorState::Homing {
+            return;
+        }
+        if homing.is_done() {
+            self.set_motor_position(motor, homing.get_home());
+            self.put_motor_state(motor, MotorState::Operational);
+        } else if let Some(error) = homing.get_error() {
+            self.set_motor_fault(motor, state::Fault::Homing(error));
+        }
+    }
+
+    /* Put Each Motor's Direction On Its DIR Output, Before The First Step Of A Move Or Homing */
+    pub fn set_outputs(&self, outputs: &mut [stepdir::StepDir; interpolate::AXES]) {
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            outputs[axis].set_direction(self.get_motor_direction(motor));
+        }
+    }
+
+    /* One Period Of A Motor's Closed Loop From Its Control Timer, count From Its Encoder */
+    /* Sets The Motor's Direction And Returns The Duty Or Step Rate, Only Operational And Homing Motors Are Driven, */
+    /* Any Other Gets 0 With The Loops Reset, And A Following Error Faults It */
+    pub fn update_servo(&mut self, motor: Motors, servo: &mut servo::Servo, setpoint: servo::Setpoint, count: i32) -> u32 {
+        match self.get_motor_state(motor) {
+            MotorState::Operational | MotorState::Homing => {
+            } _ => {
+                servo.reset();
+                return ZERO;
+            }
+        }
+        match servo.update(setpoint, count) {
+            Ok(output) => {
+                let (direction, value) = servo.get_output(output);
+                self.set_motor_direction(motor, direction);
+                return value;
+            } Err(fault) => {
+                self.set_motor_fault(motor, fault);
+                return ZERO;
+            }
+        }
+    }
+
+    pub fn check_stopped(&self, motor_state: MotorState) -> bool {
+        return match motor_state {
+            MotorState::Stopped => true,
+            _ => false
+        }
+    }
+}
+
+/* From The Step Timer's Interrupt, Sets The Pulse Rate For The Next Step, False Once The Profile Is Done */
+/* The Timer Is Opened For PWM As Usual, Each Period Is One Step, So The Caller Stops It On False */
+pub fn pulse<P: StepProfile>(timer: &timer::Timer, freq: u32, profile: &mut P) -> bool {
+    return match profile.next_interval() {
+        Some(interval) => {
+            timer.set_scl(step_rate(interval), freq, freq);
+            true
+        } None => false
+    };
+}
+
+/* From The Step Timer's Interrupt During A Coordinated Move, One PWM Period Per Dominant Step */
+/* Channels 1 To 4 Carry Axes 1 To 4, An Axis Not Stepping This Period Gets A CCR Of 0 */
+/* Returns The Axes Stepping For MotorControl::add_steps, None Once The Move Is Done */
+pub fn pulse_move<P: StepProfile>(timer: &timer::Timer, freq: u32, duty: u32, linear: &mut interpolate::LinearMove<P>) -> Option<u32> {
+    let steps = linear.next();
+    let mask = steps.map_or(0, |steps| steps.mask);
+    let ccr = |axis: u32| if (mask & (1 << axis)) != 0 { duty } else { 0 };
+    timer.set_pwm_ccr1(ccr(0));
+    timer.set_pwm_ccr2(ccr(1));
+    timer.set_pwm_ccr3(ccr(2));
+    timer.set_pwm_ccr4(ccr(3));
+
+    return match steps {
+        Some(steps) => {
+            timer.set_scl(step_rate(steps.interval), freq, freq);
+            Some(steps.mask)
+        } None => None
+    };
+}
+
+/* As pulse_move But Through STEP/DIR Outputs, The Timer Only Paces The Interrupt, Directions Are Set By set_outputs */
+pub fn pulse_outputs<P: StepProfile>(timer: &timer::Timer, freq: u32, outputs: &[stepdir::StepDir; interpolate::AXES], linear: &mut interpolate::LinearMove<P>) -> Option<u32> {
+    return match linear.next() {
+        Some(steps) => {
+            stepdir::emit(outputs, steps.mask);
+            timer.set_scl(step_rate(steps.interval), freq, freq);
+            Some(steps.mask)
+        } None => {
+            stepdir::emit(outputs, 0);
+            None
+        }
+    };
+}
+
+/* Pulse Rate In Hz For A Step interval Seconds Long */
+fn step_rate(interval: f32) -> u32 {
+    let rate = if interval > 0.0 { ((1.0 / interval) + 0.5) as u32 } else { u32::MAX };
+    return core::cmp::max(rate, MIN_RATE);
+}
@@ -0,0 +1,169 @@
+/* PID Controller */
+/* Derivative Is Taken On The Measurement, So A Setpoint Jump Does Not Kick The Output, Through A First Order Low Pass */
+/* The Integral Is Kept Already Multiplied By ki And Stops Growing While The Output Is Pinned At A Limit In The Same */
+/* Direction (Conditional Integration), It Is Also Held Within The Limits So It Can Never Wind Up Past Them */
+
+#[derive(Clone, Copy)]
+pub struct Gains {
+    pub kp:             f32,
+    pub ki:             f32,                // Per Second
+    pub kd:             f32,                // Seconds
+    pub filter:         f32,                // Derivative Low Pass Time Constant, Seconds, 0 Unfiltered
+    pub min:            f32,                // Output Limits
+    pub max:            f32
+}
+
+pub struct Pid {
+    gains:          Gains,
+    integral:       f32,                    // Sum Of ki x error x dt
+    derivative:     f32,                    // Filtered Rate Of Change Of The Measurement, Negated
+    last:           Option<f32>,            // Previous Measurement
+    output:         f32
+}
+
+impl Gains {
+    /* No Filter And No Limits */
+    pub const fn init(kp: f32, ki: f32, kd: f32) -> Gains {
+        return Gains {
+            kp,
+            ki,
+            kd,
+            filter: 0.0,
+            min:    f32::NEG_INFINITY,
+            max:    f32::INFINITY
+        };
+    }
+}
+
+impl Pid {
+    pub fn init(gains: Gains) -> Pid {
+        let mut pid = Pid {
+            gains,
+            integral:   0.0,
+            derivative: 0.0,
+            last:       None,
+            output:     0.0
+        };
+        pid.set_gains(gains);
+        return pid;
+    }
+
+    /* New Gains Take Over Smoothly, The Integral Is Kept, Limits Either Way Round */
+    pub fn set_gains(&mut self, gains: Gains) {
+        self.gains = gains;
+        self.gains.min = gains.min.min(gains.max);
+        self.gains.max = gains.max.max(gains.min);
+        self.gains.filter = gains.filter.max(0.0);
+        self.integral = self.limit(self.integral);
+    }
+
+    pub fn get_gains(&self) -> Gains {
+        return self.gains;
+    }
+
+    /* Start Over, As After A Fault Or Enabling The Driver */
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.derivative = 0.0;
+        self.last = None;
+        self.output = 0.0;
+    }
+
+    /* One Update dt Seconds After The Last, feed_forward Is Added Straight To The Output */
+    pub fn update(&mut self, setpoint: f32, measured: f32, feed_forward: f32, dt: f32) -> f32 {
+        if dt.is_nan() || dt <= 0.0 || !setpoint.is_finite() || !measured.is_finite() {
+            return self.output;
+        }
+        let error = setpoint - measured;
+
+        if let Some(last) = self.last {
+            let rate = -(measured - last) / dt;
+            self.derivative += (rate - self.derivative) * (dt / (self.gains.filter + dt));
+        }
+        self.last = Some(measured);
+
+        /* Integrate Only When That Does Not Push Further Into A Limit */
+        let step = self.gains.ki * error * dt;
+        let proportional = (self.gains.kp * error) + (self.gains.kd * self.derivative) + feed_forward;
+        let unlimited = proportional + self.integral + step;
+        let pinned = (unlimited > self.gains.max && step > 0.0) || (unlimited < self.gains.min && step < 0.0);
+        if !pinned {
+            self.integral = self.limit(self.integral + step);
+        }
+
+        self.output = self.limit(proportional + self.integral);
+        return self.output;
+    }
+
+    pub fn get_output(&self) -> f32 {
+        return self.output;
+    }
+
+    pub fn get_integral(&self) -> f32 {
+        return self.integral;
+    }
+
+    /* At A Limit */
+    pub fn is_saturated(&self) -> bool {
+        return self.output <= self.gains.min || self.output >= self.gains.max;
+    }
+
+    fn limit(&self, value: f32) -> f32 {
+        return value.max(self.gains.min).min(self.gains.max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT:               f32 = 0.001;
+
+    #[test]
+    fn setpoint_jump_does_not_kick() {
+        let mut pid = Pid::init(Gains::init(0.0, 0.0, 1.0));
+        assert!(pid.update(0.0, 0.0, 0.0, DT) == 0.0);
+        assert!(pid.update(1000.0, 0.0, 0.0, DT) == 0.0);
+
+        /* The Measurement Moving Does, Against It */
+        assert!((pid.update(1000.0, 1.0, 0.0, DT) + (1.0 / DT)).abs() < 1e-2);
+    }
+
+    #[test]
+    fn integral_stops_while_pinned() {
+        let mut pid = Pid::init(Gains { min: -1.0, max: 1.0, ..Gains::init(1.0, 10.0, 0.0) });
+        for _ in 0..1000 {
+            assert!(pid.update(10.0, 0.0, 0.0, DT) == 1.0);
+        }
+        assert!(pid.is_saturated());
+        assert!(pid.get_integral() == 0.0);
+
+        /* Coming Off The Limit There Is Nothing To Unwind, And Integrating Toward It Again Is Allowed */
+        assert!(pid.update(0.5, 0.0, 0.0, DT) < 1.0);
+        assert!(pid.get_integral() > 0.0);
+        assert!(!pid.is_saturated());
+    }
+
+    #[test]
+    fn limits_either_way_round() {
+        let mut pid = Pid::init(Gains { min: 2.0, max: -2.0, ..Gains::init(1.0, 0.0, 0.0) });
+        assert!(pid.get_gains().min == -2.0 && pid.get_gains().max == 2.0);
+        assert!(pid.update(10.0, 0.0, 0.0, DT) == 2.0);
+        assert!(pid.update(-10.0, 0.0, 0.0, DT) == -2.0);
+        assert!(pid.update(1.5, 0.0, 0.0, DT) == 1.5);
+    }
+
+    #[test]
+    fn set_gains_clamps_the_integral() {
+        let mut pid = Pid::init(Gains::init(0.0, 1.0, 0.0));
+        for _ in 0..5 {
+            pid.update(1.0, 0.0, 0.0, 1.0);
+        }
+        assert!(pid.get_integral() == 5.0);
+
+        pid.set_gains(Gains { min: -2.0, max: 2.0, ..Gains::init(0.0, 1.0, 0.0) });
+        assert!(pid.get_integral() == 2.0);
+        pid.set_gains(Gains { min: 3.0, max: 4.0, ..Gains::init(0.0, 1.0, 0.0) });
+        assert!(pid.get_integral() == 3.0);
+    }
+}
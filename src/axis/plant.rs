@@ -0,0 +1,207 @@
+/* Motor Model For Tuning A Servo Offline */
+/* First Order, The Velocity Settles Toward gain x Output With time_constant, Less A Steady Load, Position Is Its Sum */
+/* A DC Motor On PWM Duty Has Its Mechanical Time Constant, A Stepper Given A Step Rate Follows At Once (0) */
+/* The Encoder Reads Whole Counts, Velocity Is Integrated Backward Euler So Any dt Stays Stable */
+/* Only Built For Tests Or With --features sim, Nothing Here Is Used On The Target */
+
+use super::math;
+use super::servo::{Servo, Setpoint};
+use super::state::Fault;
+
+pub struct Plant {
+    gain:           f32,                    // Steady State Counts/s Per Unit Of Output
+    time_constant:  f32,                    // Seconds
+    load:           f32,                    // Counts/s Lost To A Steady Load
+    position:       f32,                    // Counts
+    velocity:       f32                     // Counts/s
+}
+
+/* How Well A Simulated Run Tracked Its Setpoints, Counts And Output Magnitudes */
+#[derive(Clone, Copy)]
+pub struct Tracking {
+    pub max_error:      f32,                // Largest Following Error
+    pub final_error:    f32,                // Following Error At The End
+    pub max_output:     f32                 // Largest Output, To Check Against The Limits
+}
+
+impl Plant {
+    /* At Rest At Count 0 With No Load */
+    pub fn init(gain: f32, time_constant: f32) -> Plant {
+        return Plant {
+            gain,
+            time_constant:  time_constant.max(0.0),
+            load:           0.0,
+            position:       0.0,
+            velocity:       0.0
+        };
+    }
+
+    /* Steady Load, Positive Holds The Axis Back Going Forward */
+    pub fn set_load(&mut self, load: f32) {
+        self.load = load;
+    }
+
+    /* dt Seconds With output Applied, Returns The Encoder Count */
+    pub fn step(&mut self, output: f32, dt: f32) -> i32 {
+        let target = (self.gain * output) - self.load;
+        self.velocity = ((self.velocity * self.time_constant) + (target * dt)) / (self.time_constant + dt);
+        self.position += self.velocity * dt;
+        return self.get_count();
+    }
+
+    pub fn get_count(&self) -> i32 {
+        return math::round(self.position);
+    }
+
+    pub fn get_position(&self) -> f32 {
+        return self.position;
+    }
+
+    pub fn get_velocity(&self) -> f32 {
+        return self.velocity;
+    }
+}
+
+/* Close servo's Loop Around plant For ticks Control Periods, setpoint Gives The Setpoint At Each Time (Seconds) */
+pub fn simulate<F: FnMut(f32) -> Setpoint>(servo: &mut Servo, plant: &mut Plant, ticks: u32, mut setpoint: F) -> Result<Tracking, Fault> {
+    let mut tracking = Tracking {
+        max_error:      0.0,
+        final_error:    0.0,
+        max_output:     0.0
+    };
+    let dt = servo.get_period();
+    let mut count = plant.get_count();
+
+    for tick in 0..ticks {
+        let output = servo.update(setpoint(tick as f32 * dt), count)?;
+        count = plant.step(output, dt);
+
+        tracking.max_error = tracking.max_error.max(servo.get_error().abs());
+        tracking.max_output = tracking.max_output.max(output.abs());
+        tracking.final_error = servo.get_error();
+    }
+    return Ok(tracking);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::{MotorControl, MotorState, Motors, ZERO};
+    use crate::axis::pid::Gains;
+    use crate::axis::servo::{Output, ServoConfig};
+
+    /* DC Motor, Full Duty Runs At 20000 Counts/s With A 20 ms Time Constant, 1 kHz Loop */
+    fn config() -> ServoConfig {
+        return ServoConfig {
+            rate:           1000,
+            position:       Gains { kp: 60.0, ki: 0.0, kd: 0.0, filter: 0.0, min: -15000.0, max: 15000.0 },
+            velocity:       Gains { kp: 0.002, ki: 0.05, kd: 0.0, filter: 0.002, min: -1.0, max: 1.0 },
+            velocity_ff:    1.0,
+            accel_ff:       0.02 / 20000.0,
+            output_ff:      1.0 / 20000.0,
+            filter:         0.002,
+            following:      2000.0,
+            output:         Output::Duty(1000)
+        };
+    }
+
+    /* Accelerate At 50000 Counts/s^2 To 10000 Counts/s, Cruise And Stop At 5000 Counts After 0.7 s */
+    fn profile(time: f32) -> Setpoint {
+        let (accel, velocity) = (50000.0, 10000.0);
+        let ramp = velocity / accel;
+        let cruise = 0.5 - ramp;
+        let start = 0.5 * accel * ramp * ramp;
+        if time < ramp {
+            return Setpoint { position: 0.5 * accel * time * time, velocity: accel * time, accel };
+        } else if time < 0.5 {
+            return Setpoint { position: start + (velocity * (time - ramp)), velocity, accel: 0.0 };
+        }
+        let t = (time - 0.5).min(ramp);
+        return Setpoint {
+            position:   start + (velocity * cruise) + (velocity * t) - (0.5 * accel * t * t),
+            velocity:   velocity - (accel * t),
+            accel:      if t < ramp { -accel } else { 0.0 }
+        };
+    }
+
+    #[test]
+    fn tracks_a_move_and_settles() {
+        let mut servo = Servo::init(config());
+        let mut plant = Plant::init(20000.0, 0.02);
+        let tracking = simulate(&mut servo, &mut plant, 1000, profile).ok().unwrap();
+        assert!(tracking.max_error < 50.0, "max error {}", tracking.max_error);
+        assert!(tracking.final_error.abs() <= 1.0, "final error {}", tracking.final_error);
+        assert!(tracking.max_output <= 1.0);
+        assert!(plant.get_count() == 5000);
+    }
+
+    #[test]
+    fn integral_holds_against_a_load() {
+        let mut servo = Servo::init(config());
+        let mut plant = Plant::init(20000.0, 0.02);
+        plant.set_load(2000.0);
+        let tracking = simulate(&mut servo, &mut plant, 2000, |_| Setpoint::hold(1000.0)).ok().unwrap();
+        assert!(tracking.final_error.abs() <= 1.0, "final error {}", tracking.final_error);
+        assert!((plant.get_position() - 1000.0).abs() <= 1.5);
+    }
+
+    #[test]
+    fn saturated_step_does_not_wind_up() {
+        /* The Load Holds Full Duty To 10000 Counts/s, The Position Loop Asks For 15000, So The Output Sits At Its Limit */
+        let mut servo = Servo::init(config());
+        let mut plant = Plant::init(20000.0, 0.02);
+        plant.set_load(10000.0);
+        let target = 1900.0;
+        let dt = servo.get_period();
+        let mut count = 0;
+        let mut saturated = 0;
+        let mut overshoot: f32 = 0.0;
+        for _ in 0..3000 {
+            let output = servo.update(Setpoint::hold(target), count).ok().unwrap();
+            assert!(output.abs() <= 1.0);
+            if output >= 1.0 {
+                saturated += 1;
+            }
+            count = plant.step(output, dt);
+            overshoot = overshoot.max(plant.get_position() - target);
+        }
+        assert!(saturated > 100, "saturated for {} ticks", saturated);
+        assert!(overshoot < 5.0, "overshoot {}", overshoot);
+        assert!(servo.get_error().abs() <= 2.0, "final error {}", servo.get_error());
+    }
+
+    #[test]
+    fn following_error_faults() {
+        let mut servo = Servo::init(config());
+        let mut plant = Plant::init(20000.0, 0.02);
+        assert!(simulate(&mut servo, &mut plant, 10, |_| Setpoint::hold(5000.0)).err() == Some(Fault::FollowingError));
+        assert!(servo.get_error() == 0.0);
+    }
+
+    #[test]
+    fn only_operational_and_homing_motors_are_driven() {
+        let mut control = MotorControl::init();
+        let mut servo = Servo::init(config());
+        let held = Setpoint::hold(100.0);
+        assert!(control.update_servo(Motors::Motor1, &mut servo, held, 0) == ZERO);
+
+        for &state in [MotorState::PreOperational, MotorState::Stopped].iter() {
+            assert!(control.set_motor_state(Motors::Motor1, state).is_ok());
+            assert!(control.update_servo(Motors::Motor1, &mut servo, held, 0) == ZERO);
+        }
+
+        for &state in [MotorState::Homing, MotorState::Operational].iter() {
+            assert!(control.set_motor_state(Motors::Motor1, state).is_ok());
+            assert!(control.update_servo(Motors::Motor1, &mut servo, held, 0) > ZERO);
+            assert!(servo.get_error() == 100.0);
+        }
+
+        /* Stopping Resets The Loops */
+        assert!(control.set_motor_state(Motors::Motor1, MotorState::Stopped).is_ok());
+        assert!(control.update_servo(Motors::Motor1, &mut servo, held, 0) == ZERO);
+        assert!(servo.get_error() == 0.0 && servo.get_command() == 0.0);
+
+        control.set_motor_fault(Motors::Motor1, Fault::Driver);
+        assert!(control.update_servo(Motors::Motor1, &mut servo, held, 0) == ZERO);
+    }
+}
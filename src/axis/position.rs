@@ -0,0 +1,151 @@
+/* Engineering Units And Soft Limits Of One Axis */
+/* Positions Are Kept As Signed Steps From Home, Commands Come In Units (mm, Degrees) Scaled By steps_per_unit */
+/* A Command Past A Soft Limit Is Rejected Or Clamped To It, Whole Steps Nearest The Target Are Used */
+
+use super::math;
+
+/* What A Command Past A Soft Limit Does */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Limit {
+    Reject,
+    Clamp
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PositionError {
+    BelowMin,
+    AboveMax,
+    Invalid             // NaN Or Infinite
+}
+
+#[derive(Clone, Copy)]
+pub struct Units {
+    steps_per_unit: f32,
+    min:            i32,                    // Soft Limits, Steps
+    max:            i32,
+    limit:          Limit
+}
+
+impl Units {
+    /* Steps Per Unit Of 1 And No Soft Limits, Commands Are In Steps */
+    pub const fn init() -> Units {
+        return Units {
+            steps_per_unit: 1.0,
+            min:            i32::MIN,
+            max:            i32::MAX,
+            limit:          Limit::Reject
+        };
+    }
+
+    /* 0 Or Not Finite Is Ignored, Negative Turns The Axis Around */
+    pub fn set_scale(&mut self, steps_per_unit: f32) {
+        if steps_per_unit.is_finite() && steps_per_unit != 0.0 {
+            self.steps_per_unit = steps_per_unit;
+        }
+    }
+
+    pub fn get_scale(&self) -> f32 {
+        return self.steps_per_unit;
+    }
+
+    /* Soft Limits In Units, Either Way Round, Kept In Steps So Set The Scale First */
+    pub fn set_limits(&mut self, min: f32, max: f32, limit: Limit) {
+        let (min, max) = (self.get_steps(min), self.get_steps(max));
+        self.min = core::cmp::min(min, max);
+        self.max = core::cmp::max(min, max);
+        self.limit = limit;
+    }
+
+    pub fn clr_limits(&mut self) {
+        self.min = i32::MIN;
+        self.max = i32::MAX;
+    }
+
+    /* Soft Limits In Units, Lowest First */
+    pub fn get_limits(&self) -> (f32, f32) {
+        let (min, max) = (self.get_units(self.min), self.get_units(self.max));
+        return (min.min(max), min.max(max));
+    }
+
+    pub fn get_steps(&self, units: f32) -> i32 {
+        return math::round(units * self.steps_per_unit);
+    }
+
+    pub fn get_units(&self, steps: i32) -> f32 {
+        return steps as f32 / self.steps_per_unit;
+    }
+
+    /* Step Position For An Absolute Command In Units */
+    pub fn get_target(&self, units: f32) -> Result<i32, PositionError> {
+        if !units.is_finite() {
+            return Err(PositionError::Invalid);
+        }
+        return self.check(units as f64 * self.steps_per_unit as f64);
+    }
+
+    /* Step Position For A Command Relative To position Steps */
+    pub fn get_offset(&self, position: i32, units: f32) -> Result<i32, PositionError> {
+        if !units.is_finite() {
+            return Err(PositionError::Invalid);
+        }
+        return self.check(position as f64 + (units as f64 * self.steps_per_unit as f64));
+    }
+
+    /* Against The Soft Limits, steps Is Exact Before Rounding So A Target Just Past A Limit Is Caught */
+    /* f64 Since An f32 Cannot Hold Every Step Position Past 2^24 */
+    fn check(&self, steps: f64) -> Result<i32, PositionError> {
+        let error = if steps < self.min as f64 {
+            PositionError::BelowMin
+        } else if steps > self.max as f64 {
+            PositionError::AboveMax
+        } else {
+            return Ok(math::round_wide(steps).clamp(self.min, self.max));
+        };
+
+        return match self.limit {
+            Limit::Reject => Err(error),
+            Limit::Clamp => Ok(if error == PositionError::BelowMin { self.min } else { self.max })
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_stay_exact_past_2_24() {
+        let units = Units::init();
+        let position = (1 << 24) + 1;
+        assert!(units.get_offset(position, 0.0) == Ok(position));
+        assert!(units.get_offset(position, 1.0) == Ok(position + 1));
+        assert!(units.get_offset(position, -1.0) == Ok(position - 1));
+        assert!(units.get_offset(-position, 0.0) == Ok(-position));
+        assert!(units.get_offset(i32::MAX, 0.0) == Ok(i32::MAX));
+    }
+
+    #[test]
+    fn offset_past_the_limits() {
+        let mut units = Units::init();
+        units.set_limits(-100_000_000.0, 100_000_000.0, Limit::Reject);
+        assert!(units.get_offset(99_999_999, 1.0) == Ok(100_000_000));
+        assert!(units.get_offset(100_000_000, 0.25) == Err(PositionError::AboveMax));
+        assert!(units.get_offset(-100_000_000, -0.25) == Err(PositionError::BelowMin));
+        assert!(units.get_offset(0, f32::NAN) == Err(PositionError::Invalid));
+
+        units.set_limits(-100_000_000.0, 100_000_000.0, Limit::Clamp);
+        assert!(units.get_offset(99_999_999, 5.0) == Ok(100_000_000));
+        assert!(units.get_offset(i32::MIN, -1.0) == Ok(-100_000_000));
+    }
+
+    #[test]
+    fn scaled_target_just_past_a_limit() {
+        let mut units = Units::init();
+        units.set_scale(100.0);
+        units.set_limits(0.0, 300.0, Limit::Reject);
+        assert!(units.get_target(300.0) == Ok(30000));
+        assert!(units.get_target(300.004) == Err(PositionError::AboveMax));
+        assert!(units.get_target(-0.001) == Err(PositionError::BelowMin));
+        assert!(units.get_target(12.345) == Ok(1235));
+    }
+}
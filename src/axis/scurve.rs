@@ -0,0 +1,469 @@
+/* Jerk Limited Seven Segment S-Curve Motion Profile */
+/* Jerk Up, Constant Acceleration, Jerk Down, Cruise, Then The Mirror Image Down To Rest */
+/* Segment Times Are Closed Form, A Move Too Short For All Seven Drops Cruise, Then Constant Acceleration, */
+/* Lowering The Peak Velocity And Acceleration So The Remaining Segments Still Meet Exactly */
+/* The Override Scales Profile Time Against Real Time, So Velocity Changes By k, Acceleration k^2 And Jerk k^3, */
+/* And The Path Itself Never Changes, Above 1 It Goes Past The Planned Limits So Leave Margin For It */
+/* Units Are Steps And Seconds */
+
+use super::math;
+use super::StepProfile;
+
+/* Override Range, It Never Reaches 0 So Steps Stay Finite */
+pub const MIN_OVERRIDE:     f32 = 0.01;
+pub const MAX_OVERRIDE:     f32 = 2.0;
+
+/* Halvings In get_time And solve, Enough For A Fraction Of A Step On Long Moves */
+const SEARCH_STEPS:         u32 = 32;
+
+/* Slack On The Time Left In A Segment When Stepping, Rounding Adds Up In elapsed Over Its Steps */
+const SLACK:                f32 = 1e-4;
+
+/* Jerk Of Each Segment, In Units Of The Jerk Limit */
+const JERK_SIGN:            [f32; 7] = [1.0, 0.0, -1.0, 0.0, -1.0, 0.0, 1.0];
+
+/* Position, Velocity And Acceleration At One Moment */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Point {
+    pub position:   f32,                    // Steps
+    pub velocity:   f32,                    // Steps/s
+    pub accel:      f32                     // Steps/s^2
+}
+
+pub struct SCurve {
+    distance:       u32,                    // Steps
+    jerk:           f32,                    // Steps/s^3
+    velocity:       f32,                    // Peak Velocity Reached
+    accel:          f32,                    // Peak Acceleration Reached
+    starts:         [f32; 8],               // Segment Start Times, The Last Is The End Of The Move
+    points:         [Point; 8],             // State At Each Start
+    durations:      [f32; 7],               // Seconds In Each Segment
+    lengths:        [f32; 7],               // Steps In Each Segment
+    time:           f32,                    // Profile Time Reached By advance Or next_interval
+    step:           u32,                    // Steps Already Handed Out By next_interval
+    segment:        usize,                  // Segment next_interval Is In
+    at:             Point,                  // State There, Position Unused
+    elapsed:        f32,                    // Seconds Into That Segment
+    left:           f32,                    // Steps To The End Of That Segment
+    scale:          f32,                    // Override In Effect
+    target:         f32,                    // Override Being Slewed To
+    slew:           f32                     // Override Change Per Second
+}
+
+impl Point {
+    const fn init() -> Point {
+        return Point {
+            position:   0.0,
+            velocity:   0.0,
+            accel:      0.0
+        };
+    }
+
+    /* Where Constant jerk Takes This Point After time */
+    fn advance(&self, jerk: f32, time: f32) -> Point {
+        let t2 = time * time;
+        return Point {
+            position:   self.position + (self.velocity * time) + (0.5 * self.accel * t2) + (jerk * t2 * time / 6.0),
+            velocity:   self.velocity + (self.accel * time) + (0.5 * jerk * t2),
+            accel:      self.accel + (jerk * time)
+        };
+    }
+}
+
+impl SCurve {
+    /* A Move Of distance Steps From Rest To Rest, A Limit Not Above 0 Plans No Steps */
+    pub fn plan(distance: u32, max_velocity: f32, max_accel: f32, jerk: f32) -> SCurve {
+        let mut profile = SCurve {
+            distance:   0,
+            jerk:       0.0,
+            velocity:   0.0,
+            accel:      0.0,
+            starts:     [0.0; 8],
+            points:     [Point::init(); 8],
+            durations:  [0.0; 7],
+            lengths:    [0.0; 7],
+            time:       0.0,
+            step:       0,
+            segment:    0,
+            at:         Point::init(),
+            elapsed:    0.0,
+            left:       0.0,
+            scale:      1.0,
+            target:     1.0,
+            slew:       0.0
+        };
+        for &limit in [max_velocity, max_accel, jerk].iter() {
+            if limit.is_nan() || limit <= 0.0 {
+                return profile;
+            }
+        }
+        if distance == 0 {
+            return profile;
+        }
+
+        /* Peak Velocity, Lowered Until Both Ramps Fit In The Distance */
+        let d = distance as f32;
+        let mut velocity = max_velocity;
+        if (velocity * ramp_time(velocity, max_accel, jerk)) > d {
+            /* With The Acceleration Limit Reached: v^2 + (A^2 / J) v - A d = 0 */
+            let b = (max_accel * max_accel) / jerk;
+            velocity = 0.5 * (math::sqrt((b * b) + (4.0 * max_accel * d)) - b);
+            if velocity < b {
+                /* Too Short For That Too, Pure Jerk Ramps: 2 v sqrt(v / J) = d */
+                velocity = math::cbrt((d * d * jerk) / 4.0);
+            }
+        }
+
+        /* Jerk Phase And Constant Acceleration Phase Of One Ramp */
+        let (jerk_time, accel_time) = if (velocity * jerk) >= (max_accel * max_accel) {
+            (max_accel / jerk, (velocity / max_accel) - (max_accel / jerk))
+        } else {
+            (math::sqrt(velocity / jerk), 0.0)
+        };
+        let ramp = velocity * ((2.0 * jerk_time) + accel_time);
+        let cruise_time = f32::max(d - ramp, 0.0) / velocity;
+
+        /* Lengths Are Taken From Each Segment's Own Start So They Keep Their Precision On Long Moves */
+        profile.durations = [jerk_time, accel_time, jerk_time, cruise_time, jerk_time, accel_time, jerk_time];
+        for (i, &sign) in JERK_SIGN.iter().enumerate() {
+            let start = Point { position: 0.0, ..profile.points[i] };
+            let end = start.advance(sign * jerk, profile.durations[i]);
+            profile.lengths[i] = end.position;
+            profile.starts[i + 1] = profile.starts[i] + profile.durations[i];
+            profile.points[i + 1] = Point { position: profile.points[i].position + end.position, ..end };
+        }
+
+        profile.distance = distance;
+        profile.jerk = jerk;
+        profile.velocity = velocity;
+        profile.accel = jerk * jerk_time;
+        profile.left = profile.lengths[0];
+        return profile;
+    }
+
+    pub fn get_distance(&self) -> u32 {
+        return self.distance;
+    }
+
+    pub fn get_duration(&self) -> f32 {
+        return self.starts[7];
+    }
+
+    /* Highest Velocity Reached, Below max_velocity When The Move Is Too Short */
+    pub fn get_peak_velocity(&self) -> f32 {
+        return self.velocity;
+    }
+
+    /* Highest Acceleration Reached, Below max_accel When The Ramps Are Too Short */
+    pub fn get_peak_accel(&self) -> f32 {
+        return self.accel;
+    }
+
+    /* Length Of Each Of The Seven Segments, 0 For Those Dropped */
+    pub fn get_segments(&self) -> [f32; 7] {
+        return self.durations;
+    }
+
+    /* State At time Seconds Of Profile Time, Held At The Ends */
+    pub fn get_point(&self, time: f32) -> Point {
+        if self.distance == 0 || time <= 0.0 {
+            return Point::init();
+        }
+        if time >= self.starts[7] {
+            return Point {
+                position:   self.distance as f32,
+                velocity:   0.0,
+                accel:      0.0
+            };
+        }
+
+        let mut segment = 0;
+        while segment < 6 && time >= self.starts[segment + 1] {
+            segment += 1;
+        }
+        return self.points[segment].advance(JERK_SIGN[segment] * self.jerk, time - self.starts[segment]);
+    }
+
+    pub fn get_position(&self, time: f32) -> f32 {
+        return self.get_point(time).position;
+    }
+
+    pub fn get_velocity(&self, time: f32) -> f32 {
+        return self.get_point(time).velocity;
+    }
+
+    pub fn get_accel(&self, time: f32) -> f32 {
+        return self.get_point(time).accel;
+    }
+
+    /* Profile Time At Which position Is Reached, Position Never Falls So A Bisection Finds It */
+    pub fn get_time(&self, position: f32) -> f32 {
+        if position <= 0.0 {
+            return 0.0;
+        }
+        if position >= self.distance as f32 {
+            return self.starts[7];
+        }
+
+        let mut low = 0.0;
+        let mut high = self.starts[7];
+        for _ in 0..SEARCH_STEPS {
+            let middle = 0.5 * (low + high);
+            if self.get_position(middle) < position {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        return high;
+    }
+
+    /* Move The Override To scale (1 As Planned), Changing By At Most slew Per Second, 0 Jumps At Once */
+    pub fn set_override(&mut self, scale: f32, slew: f32) {
+        self.target = scale.clamp(MIN_OVERRIDE, MAX_OVERRIDE);
+        self.slew = slew.max(0.0);
+        if self.slew == 0.0 {
+            self.scale = self.target;
+        }
+    }
+
+    pub fn get_override(&self) -> f32 {
+        return self.scale;
+    }
+
+    /* Real Time Step Of dt Seconds Under The Override, Returns The New State With Velocity And Acceleration In Real Time */
+    pub fn advance(&mut self, dt: f32) -> Point {
+        self.slew_override(dt);
+        self.time = (self.time + (self.scale * dt)).min(self.starts[7]);
+        let point = self.get_point(self.time);
+        return Point {
+            position:   point.position,
+            velocity:   point.velocity * self.scale,
+            accel:      point.accel * self.scale * self.scale
+        };
+    }
+
+    /* Profile Time Reached So Far */
+    pub fn get_progress(&self) -> f32 {
+        return self.time;
+    }
+
+    pub fn is_done(&self) -> bool {
+        return self.time >= self.starts[7];
+    }
+
+    /* Start Over From Rest, The Override Is Kept */
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+        self.step = 0;
+        self.segment = 0;
+        self.at = Point::init();
+        self.elapsed = 0.0;
+        self.left = self.lengths[0];
+    }
+
+    fn slew_override(&mut self, dt: f32) {
+        let change = self.slew * dt;
+        if self.scale < self.target {
+            self.scale = (self.scale + change).min(self.target);
+        } else if self.scale > self.target {
+            self.scale = (self.scale - change).max(self.target);
+        }
+    }
+}
+
+impl StepProfile for SCurve {
+    /* The Override Is Taken As Constant Over One Step */
+    /* Each Step Is Solved From The State At The Last One, Never From Absolute Times Or Positions, */
+    /* Which Would Cancel To Nothing Once A Long Move Is Seconds And Millions Of Steps In */
+    fn next_interval(&mut self) -> Option<f32> {
+        if self.step >= self.distance {
+            return None;
+        }
+        self.step += 1;
+
+        let last = self.step == self.distance;
+        let mut need = 1.0;
+        let mut time = 0.0;
+        loop {
+            let segment = self.segment;
+            let jerk = JERK_SIGN[segment] * self.jerk;
+            let left = self.left.max(0.0);
+            let remaining = (self.durations[segment] * (1.0 + SLACK)) - self.elapsed;
+            if last && segment == 6 {
+                /* The Last Step Lands On The End Of The Move, Whatever Rounding Is Left Over */
+                time += (self.durations[segment] - self.elapsed).max(0.0);
+                break;
+            }
+            if (need <= left && !last) || segment == 6 {
+                let length = need.min(left);
+                let dt = solve(&self.at, jerk, length, remaining);
+                self.at = Point { position: 0.0, ..self.at.advance(jerk, dt) };
+                self.elapsed += dt;
+                self.left -= length;
+                time += dt;
+                break;
+            }
+
+            /* The Rest Of This Segment, Then On From The Planned Start Of The Next */
+            time += solve(&self.at, jerk, left, remaining);
+            need -= left;
+            self.segment += 1;
+            self.at = Point { position: 0.0, ..self.points[self.segment] };
+            self.elapsed = 0.0;
+            self.left = self.lengths[self.segment];
+        }
+
+        self.time = if last { self.starts[7] } else { (self.time + time).min(self.starts[7]) };
+        let interval = time / self.scale;
+        self.slew_override(interval);
+        return Some(interval);
+    }
+}
+
+/* Seconds To Ramp From Rest To velocity Under The Acceleration And Jerk Limits */
+fn ramp_time(velocity: f32, accel: f32, jerk: f32) -> f32 {
+    if (velocity * jerk) >= (accel * accel) {
+        return (velocity / accel) + (accel / jerk);
+    }
+    return 2.0 * math::sqrt(velocity / jerk);
+}
+
+/* Seconds For at, At Position 0, To Cover length Under Constant jerk, Bisected Up To limit Where Position Never Falls */
+/* Cruise Is Closed Form, Its limit Adds Up Over Too Many Steps To Hold It To */
+fn solve(at: &Point, jerk: f32, length: f32, limit: f32) -> f32 {
+    if jerk == 0.0 && at.accel == 0.0 && at.velocity > 0.0 {
+        return length / at.velocity;
+    }
+
+    let mut low = 0.0;
+    let mut high = limit;
+    for _ in 0..SEARCH_STEPS {
+        let middle = 0.5 * (low + high);
+        if at.advance(jerk, middle).position < length {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    return high;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intervals(profile: &mut SCurve) -> Vec<f32> {
+        return core::iter::from_fn(|| profile.next_interval()).collect();
+    }
+
+    fn close(a: f32, b: f32, tolerance: f32) -> bool {
+        return (a - b).abs() <= tolerance * b.abs().max(1.0);
+    }
+
+    #[test]
+    fn seven_segment_phase_boundaries() {
+        let profile = SCurve::plan(100000, 10000.0, 50000.0, 1000000.0);
+        let expected = [0.05, 0.15, 0.05, 9.75, 0.05, 0.15, 0.05];
+        for (segment, &length) in profile.get_segments().iter().zip(expected.iter()) {
+            assert!(close(*segment, length, 1e-4), "{} != {}", segment, length);
+        }
+        assert!(close(profile.get_duration(), 10.25, 1e-5));
+
+        /* Jerk Up To Full Acceleration, Held, Jerk Down To Cruise, Then The Mirror Image */
+        let starts = [0.0, 0.05, 0.2, 0.25, 10.0, 10.05, 10.2, 10.25];
+        let accel = [0.0, 50000.0, 50000.0, 0.0, 0.0, -50000.0, -50000.0, 0.0];
+        let velocity = [0.0, 1250.0, 8750.0, 10000.0, 10000.0, 8750.0, 1250.0, 0.0];
+        for i in 1..7 {
+            let point = profile.get_point(starts[i]);
+            assert!(close(point.accel, accel[i], 1e-2), "segment {} accel {}", i, point.accel);
+            assert!(close(point.velocity, velocity[i], 1e-3), "segment {} velocity {}", i, point.velocity);
+        }
+        assert!(close(profile.get_position(0.25), 1250.0, 1e-3));
+        assert!(profile.get_position(10.25) == 100000.0);
+    }
+
+    #[test]
+    fn limits_hold_throughout() {
+        let profile = SCurve::plan(20000, 10000.0, 50000.0, 1000000.0);
+        let dt = 1e-4;
+        let mut last = profile.get_point(0.0);
+        let mut time = dt;
+        while time <= profile.get_duration() {
+            let point = profile.get_point(time);
+            assert!(point.velocity <= 10000.0 * 1.0001 && point.velocity >= -1e-2, "velocity {} at {}", point.velocity, time);
+            assert!(point.accel.abs() <= 50000.0 * 1.0001, "accel {} at {}", point.accel, time);
+            assert!((point.accel - last.accel).abs() / dt <= 1000000.0 * 1.01, "jerk at {}", time);
+            assert!(point.position >= last.position - 1e-3, "backwards at {}", time);
+            last = point;
+            time += dt;
+        }
+    }
+
+    #[test]
+    fn long_moves_hold_the_cruise_rate() {
+        for &distance in [10_000, 100_000, 1_000_000].iter() {
+            let mut profile = SCurve::plan(distance, 50000.0, 1000000.0, 50000000.0);
+            let steps = intervals(&mut profile);
+            assert_eq!(steps.len(), distance as usize);
+            assert!(profile.is_done());
+
+            let fastest = steps.iter().cloned().fold(f32::INFINITY, f32::min);
+            assert!(1.0 / fastest <= 50000.0 * 1.001, "{} steps at {} steps/s", distance, 1.0 / fastest);
+
+            let cruise = steps.iter().filter(|&&interval| ((1.0 / interval) - 50000.0).abs() < 0.05).count() as f32;
+            let planned = profile.get_segments()[3] * 50000.0;
+            assert!((cruise - planned).abs() <= 4.0, "{} steps, {} cruise of {}", distance, cruise, planned);
+
+            let total: f64 = steps.iter().map(|&interval| interval as f64).sum();
+            assert!((total / profile.get_duration() as f64 - 1.0).abs() < 1e-4, "{} steps", distance);
+        }
+    }
+
+    #[test]
+    fn short_moves_never_cruise() {
+        /* Acceleration Limited, No Cruise */
+        let mut profile = SCurve::plan(1000, 10000.0, 50000.0, 1000000.0);
+        let segments = profile.get_segments();
+        assert!(segments[3] < 1e-6 && segments[1] > 0.0);
+        assert!(profile.get_peak_velocity() < 10000.0);
+        assert!(close(profile.get_peak_accel(), 50000.0, 1e-4));
+        assert!(close(profile.get_position(profile.get_duration() / 2.0), 500.0, 1e-3));
+
+        let steps = intervals(&mut profile);
+        assert_eq!(steps.len(), 1000);
+        assert!(1.0 / steps.iter().cloned().fold(f32::INFINITY, f32::min) <= profile.get_peak_velocity() * 1.001);
+        assert!(close(steps.iter().sum(), profile.get_duration(), 1e-4));
+
+        /* Too Short To Reach Full Acceleration Either, Pure Jerk */
+        let mut profile = SCurve::plan(50, 10000.0, 50000.0, 1000000.0);
+        let segments = profile.get_segments();
+        assert!(segments[1] == 0.0 && segments[3] == 0.0 && segments[5] == 0.0);
+        assert!(profile.get_peak_accel() < 50000.0);
+        assert!(close(profile.get_position(profile.get_duration()), 50.0, 1e-6));
+
+        let steps = intervals(&mut profile);
+        assert_eq!(steps.len(), 50);
+        assert!(close(steps.iter().sum(), profile.get_duration(), 1e-4));
+    }
+
+    #[test]
+    fn override_scales_the_steps() {
+        let mut planned = SCurve::plan(2000, 10000.0, 50000.0, 1000000.0);
+        let mut halved = SCurve::plan(2000, 10000.0, 50000.0, 1000000.0);
+        halved.set_override(0.5, 0.0);
+        for (a, b) in intervals(&mut planned).iter().zip(intervals(&mut halved).iter()) {
+            assert!(close(*b, 2.0 * a, 1e-5));
+        }
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut profile = SCurve::plan(300, 10000.0, 50000.0, 1000000.0);
+        let first = intervals(&mut profile);
+        assert!(profile.next_interval().is_none());
+        profile.reset();
+        assert!(intervals(&mut profile) == first);
+        assert!(SCurve::plan(0, 1.0, 1.0, 1.0).next_interval().is_none());
+    }
+}
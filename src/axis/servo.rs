@@ -0,0 +1,176 @@
+/* Closed Loop Position Control Of One Axis */
+/* Cascaded, The Position Loop Turns The Position Error Into A Velocity Command And The Velocity Loop Turns The */
+/* Velocity Error Into The Output, PWM Duty For A DC Motor Or A Step Rate For A Stepper With An Encoder */
+/* Update At A Fixed Rate From A Timer Interrupt, Positions Are Encoder Counts And Velocities Counts/s */
+/* Feed Forward Of The Setpoint's Velocity And Acceleration Leaves The PIDs Only The Error To Correct */
+/* See plant For Tuning Against A Model */
+
+use super::{math, MotorDirection};
+use super::pid::{Gains, Pid};
+use super::state::Fault;
+
+/* What The Velocity Loop Drives */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Output {
+    Duty(u32),          // PWM, An Output Of 1.0 Is This Many Timer Counts, Keep The Output Limits Within 1.0
+    StepRate            // Steps/s, Limited By The Output Limits
+}
+
+#[derive(Clone, Copy)]
+pub struct ServoConfig {
+    pub rate:           u32,                // Updates Per Second
+    pub position:       Gains,              // Counts In, Counts/s Out, Its Limits Cap The Velocity Command
+    pub velocity:       Gains,              // Counts/s In, Output Out, Its Limits Cap The Output
+    pub velocity_ff:    f32,                // Setpoint Velocity Into The Velocity Command, 1.0 Usually
+    pub accel_ff:       f32,                // Setpoint Acceleration Into The Output
+    pub output_ff:      f32,                // Setpoint Velocity Into The Output, Back EMF Or Steps Per Count
+    pub filter:         f32,                // Velocity Estimate Low Pass Time Constant, Seconds
+    pub following:      f32,                // Position Error That Faults The Axis, Counts
+    pub output:         Output
+}
+
+/* Where The Axis Should Be Now, From The Motion Profile */
+#[derive(Clone, Copy)]
+pub struct Setpoint {
+    pub position:       f32,                // Counts
+    pub velocity:       f32,                // Counts/s
+    pub accel:          f32                 // Counts/s^2
+}
+
+/* Extends A 16 Bit Timer In Encoder Mode To A Signed 32 Bit Count */
+#[derive(Clone, Copy)]
+pub struct Encoder {
+    raw:            u16,                    // Timer Count Last Read
+    count:          i32
+}
+
+pub struct Servo {
+    config:         ServoConfig,
+    position:       Pid,
+    velocity:       Pid,
+    period:         f32,                    // Seconds Between Updates
+    speed:          f32,                    // Filtered Velocity Estimate, Counts/s
+    last:           Option<i32>,            // Count At The Last Update
+    error:          f32                     // Position Error At The Last Update, Counts
+}
+
+impl Setpoint {
+    /* Standing Still At position */
+    pub const fn hold(position: f32) -> Setpoint {
+        return Setpoint {
+            position,
+            velocity:   0.0,
+            accel:      0.0
+        };
+    }
+}
+
+impl Encoder {
+    /* Starting From The Timer's Count Now, Counted As 0 */
+    pub const fn init(raw: u16) -> Encoder {
+        return Encoder {
+            raw,
+            count:  0
+        };
+    }
+
+    /* Read Often Enough That The Timer Moves Less Than Half Its Range In Between */
+    pub fn update(&mut self, raw: u16) -> i32 {
+        self.count = self.count.wrapping_add(raw.wrapping_sub(self.raw) as i16 as i32);
+        self.raw = raw;
+        return self.count;
+    }
+
+    pub fn get_count(&self) -> i32 {
+        return self.count;
+    }
+
+    pub fn set_count(&mut self, count: i32) {
+        self.count = count;
+    }
+}
+
+impl Servo {
+    /* A Rate Of 0 Is Taken As 1 Hz */
+    pub fn init(config: ServoConfig) -> Servo {
+        return Servo {
+            config,
+            position:   Pid::init(config.position),
+            velocity:   Pid::init(config.velocity),
+            period:     1.0 / core::cmp::max(config.rate, 1) as f32,
+            speed:      0.0,
+            last:       None,
+            error:      0.0
+        };
+    }
+
+    pub fn get_config(&self) -> ServoConfig {
+        return self.config;
+    }
+
+    /* Retune While Running, The Rate Stays As It Was */
+    pub fn set_config(&mut self, config: ServoConfig) {
+        self.config = ServoConfig { rate: self.config.rate, ..config };
+        self.position.set_gains(config.position);
+        self.velocity.set_gains(config.velocity);
+    }
+
+    /* Start Over, The Next Update Takes Its Count Without Seeing A Jump In Velocity */
+    pub fn reset(&mut self) {
+        self.position.reset();
+        self.velocity.reset();
+        self.speed = 0.0;
+        self.last = None;
+        self.error = 0.0;
+    }
+
+    /* One Control Period, The Output Or A Following Error, Which Also Resets The Loops */
+    pub fn update(&mut self, setpoint: Setpoint, count: i32) -> Result<f32, Fault> {
+        let measured = count as f32;
+        if let Some(last) = self.last {
+            let raw = count.wrapping_sub(last) as f32 / self.period;
+            self.speed += (raw - self.speed) * (self.period / (self.config.filter.max(0.0) + self.period));
+        }
+        self.last = Some(count);
+
+        self.error = setpoint.position - measured;
+        if self.error.abs() > self.config.following {
+            self.reset();
+            return Err(Fault::FollowingError);
+        }
+
+        let command = self.position.update(setpoint.position, measured, self.config.velocity_ff * setpoint.velocity, self.period);
+        let feed_forward = (self.config.accel_ff * setpoint.accel) + (self.config.output_ff * setpoint.velocity);
+        return Ok(self.velocity.update(command, self.speed, feed_forward, self.period));
+    }
+
+    /* Direction And Magnitude For The Hardware, Duty In Timer Counts Or A Step Rate In Hz */
+    pub fn get_output(&self, output: f32) -> (MotorDirection, u32) {
+        let direction = if output < 0.0 { MotorDirection::Reverse } else { MotorDirection::Forward };
+        let value = match self.config.output {
+            Output::Duty(period) => core::cmp::min(math::round(output.abs() * period as f32) as u32, period),
+            Output::StepRate => math::round(output.abs()) as u32
+        };
+        return (direction, value);
+    }
+
+    /* Seconds Between Updates */
+    pub fn get_period(&self) -> f32 {
+        return self.period;
+    }
+
+    /* Velocity Estimate From The Encoder, Counts/s */
+    pub fn get_velocity(&self) -> f32 {
+        return self.speed;
+    }
+
+    /* Setpoint Less Measured Position At The Last Update, Counts */
+    pub fn get_error(&self) -> f32 {
+        return self.error;
+    }
+
+    /* Velocity Command Of The Position Loop At The Last Update */
+    pub fn get_command(&self) -> f32 {
+        return self.position.get_output();
+    }
+}
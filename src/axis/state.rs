@@ -0,0 +1,168 @@
+/* Motor State Machine */
+/* BootUp Leads Only To PreOperational, From There Stopped, Operational And Homing Move Between Each Other */
+/* Any State Can Fault, Faulted Then Holds Its First Cause Until Acknowledged, Which Leaves The Motor Stopped */
+/* The Machine State Is Not Set, It Is The Most Urgent Of The Motor States */
+
+use super::MotorState;
+use super::homing::HomingError;
+
+/* Why A Motor Faulted */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Fault {
+    LimitHit,
+    FollowingError,
+    Driver,
+    Watchdog,
+    EStop,
+    Homing(HomingError)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum StateError {
+    Illegal(MotorState, MotorState),        // From, To
+    Faulted(Fault),                         // Acknowledge It First
+    NotFaulted                              // Nothing To Acknowledge
+}
+
+impl Fault {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            Fault::LimitHit => "LimitHit",
+            Fault::FollowingError => "FollowingError",
+            Fault::Driver => "Driver",
+            Fault::Watchdog => "Watchdog",
+            Fault::EStop => "EStop",
+            Fault::Homing(_) => "Homing"
+        };
+    }
+}
+
+/* Whether A Motor May Go From from To to, Nothing Goes Back To BootUp And Nothing Leaves Faulted Here */
+pub fn check(from: MotorState, to: MotorState) -> Result<(), StateError> {
+    if let MotorState::Faulted(fault) = from {
+        return Err(StateError::Faulted(fault));
+    }
+
+    /* Operational Stops Before Homing, Homing Ends Stopped Or Straight Into A Move */
+    let allowed = match (from, to) {
+        (_, MotorState::Faulted(_)) => true,
+        (_, MotorState::BootUp) => false,
+        (MotorState::BootUp, to) => to == MotorState::PreOperational,
+        (MotorState::PreOperational, _) | (MotorState::Stopped, _) => true,
+        (MotorState::Operational, to) => to != MotorState::Homing,
+        (MotorState::Homing, to) => to != MotorState::PreOperational,
+        (MotorState::Faulted(_), _) => false
+    };
+
+    if !allowed {
+        return Err(StateError::Illegal(from, to));
+    }
+    return Ok(());
+}
+
+/* Machine State From The Motor States, A Fault (The First In Order) Outranks Everything, Then BootUp, */
+/* Homing, Operational, PreOperational, And Only With Every Motor Stopped Is The Machine Stopped */
+pub fn combine(states: &[MotorState]) -> MotorState {
+    let mut machine = MotorState::Stopped;
+    for &state in states.iter() {
+        if rank(state) > rank(machine) {
+            machine = state;
+        }
+    }
+    return machine;
+}
+
+fn rank(state: MotorState) -> u32 {
+    return match state {
+        MotorState::Stopped => 0,
+        MotorState::PreOperational => 1,
+        MotorState::Operational => 2,
+        MotorState::Homing => 3,
+        MotorState::BootUp => 4,
+        MotorState::Faulted(_) => 5
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::axis::{MotorControl, Motors, MOTORS};
+
+    const STATES: [MotorState; 6] = [
+        MotorState::BootUp,
+        MotorState::PreOperational,
+        MotorState::Stopped,
+        MotorState::Operational,
+        MotorState::Homing,
+        MotorState::Faulted(Fault::Driver)
+    ];
+
+    /* Rows From, Columns To, In STATES Order */
+    const ALLOWED: [[bool; 6]; 6] = [
+        [false, true,  false, false, false, true],
+        [false, true,  true,  true,  true,  true],
+        [false, true,  true,  true,  true,  true],
+        [false, true,  true,  true,  false, true],
+        [false, false, true,  true,  true,  true],
+        [false, false, false, false, false, false]
+    ];
+
+    #[test]
+    fn transition_table() {
+        for (row, &from) in STATES.iter().enumerate() {
+            for (column, &to) in STATES.iter().enumerate() {
+                let result = check(from, to);
+                if ALLOWED[row][column] {
+                    assert!(result.is_ok(), "{} -> {}", row, column);
+                } else if from == MotorState::Faulted(Fault::Driver) {
+                    assert!(result.err() == Some(StateError::Faulted(Fault::Driver)), "{} -> {}", row, column);
+                } else {
+                    assert!(result.err() == Some(StateError::Illegal(from, to)), "{} -> {}", row, column);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fault_precedence() {
+        use MotorState::*;
+        assert!(combine(&[]) == Stopped);
+        assert!(combine(&[Stopped, Stopped]) == Stopped);
+        assert!(combine(&[Stopped, PreOperational]) == PreOperational);
+        assert!(combine(&[PreOperational, Operational, Stopped]) == Operational);
+        assert!(combine(&[Operational, Homing]) == Homing);
+        assert!(combine(&[Homing, BootUp, Operational]) == BootUp);
+        assert!(combine(&[BootUp, Faulted(Fault::Watchdog), Homing]) == Faulted(Fault::Watchdog));
+
+        /* The First Fault In Order Wins */
+        assert!(combine(&[Faulted(Fault::LimitHit), Faulted(Fault::EStop)]) == Faulted(Fault::LimitHit));
+        assert!(combine(&[Stopped, Faulted(Fault::EStop), Faulted(Fault::LimitHit)]) == Faulted(Fault::EStop));
+    }
+
+    #[test]
+    fn ack_fault_leaves_the_motor_stopped() {
+        let mut control = MotorControl::init();
+        assert!(control.ack_fault(Motors::Motor1).err() == Some(StateError::NotFaulted));
+
+        /* The First Cause Sticks */
+        control.set_motor_fault(Motors::Motor1, Fault::FollowingError);
+        control.set_motor_fault(Motors::Motor1, Fault::Driver);
+        assert!(control.set_motor_state(Motors::Motor1, MotorState::Stopped).err() == Some(StateError::Faulted(Fault::FollowingError)));
+
+        assert!(control.ack_fault(Motors::Motor1).ok() == Some(Fault::FollowingError));
+        assert!(control.get_motor_state(Motors::Motor1) == MotorState::Stopped);
+        assert!(control.ack_fault(Motors::Motor1).err() == Some(StateError::NotFaulted));
+    }
+
+    #[test]
+    fn estop_faults_every_motor_keeping_earlier_causes() {
+        let mut control = MotorControl::init();
+        control.set_motor_fault(Motors::Motor2, Fault::LimitHit);
+        control.estop();
+        for &motor in MOTORS.iter() {
+            let cause = if motor == Motors::Motor2 { Fault::LimitHit } else { Fault::EStop };
+            assert!(control.get_motor_fault(motor) == Some(cause));
+        }
+        assert!(control.get_state() == MotorState::Faulted(Fault::EStop));
+    }
+}
@@ -0,0 +1,216 @@
+/* STEP/DIR Outputs For Standard Stepper Drivers */
+/* STEP Is A Timer PWM Channel, One Pulse Per Period Armed Through Its CCR, Or A GPIO Pulsed From The Step Interrupt */
+/* DIR And The Optional ENABLE Are GPIO, Any Of The Three Can Be Inverted For Drivers That Want Active Low */
+/* Setup And Pulse Width Are Busy Waits Of At Least The Given Time, Sized From The Core Clock */
+
+use core::ptr;
+use crate::stm32hal::{gpio, timer};
+use super::MotorDirection;
+
+/* Timer Capture/Compare Enable Register, Output Polarity Bit Of Channel 1, The Others Are Every 4 Bits */
+const TIM_CCER:             u32 = 0x20;
+const TIM_CCER_CC1P:        u32 = 1 << 1;
+const CCER_CHANNEL_WIDTH:   u32 = 4;
+const TIM_CHANNELS:         u32 = 4;
+
+const NS_PER_S:             u64 = 1_000_000_000;
+
+#[derive(Clone, Copy)]
+pub struct Timing {
+    pub dir_setup:      u32,                // ns From A DIR Change To The Next STEP Edge
+    pub pulse_width:    u32,                // ns STEP Stays Active
+    pub core_clock:     u32,                // Hz, For The Busy Waits
+    pub timer_clock:    u32                 // Hz The Step Timer Counts At, For Its CCR
+}
+
+/* One GPIO Output Pin */
+pub struct Output {
+    gpio:           gpio::Gpio,
+    pin:            u32,                    // Pin Bit, As For set_pin
+    inverted:       bool                    // Active Low
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum StepDirError {
+    Channel                                 // Not A Timer Channel 1 To 4
+}
+
+pub enum StepOutput {
+    Gpio(Output),
+    Timer {
+        timer:      timer::Timer,
+        channel:    u32                     // 1 To 4
+    }
+}
+
+pub struct StepDir {
+    step:           StepOutput,
+    dir:            Output,
+    enable:         Option<Output>,
+    timing:         Timing,
+    ccr:            u32,                    // Pulse Width In Timer Counts
+    direction:      Option<MotorDirection>  // On The DIR Pin, None Until First Set
+}
+
+impl Output {
+    /* pin Is The Pin Bit (common::BIT_n), Set Up As An Output With gpio.otype First */
+    pub fn init(gpio_base: u32, pin: u32, inverted: bool) -> Output {
+        return Output {
+            gpio:       gpio::Gpio::init(gpio_base),
+            pin,
+            inverted
+        };
+    }
+
+    pub fn set(&self, active: bool) {
+        if active != self.inverted {
+            self.gpio.set_pin(self.pin);
+        } else {
+            self.gpio.clr_pin(self.pin);
+        }
+    }
+}
+
+impl StepDir {
+    /* STEP On A GPIO Pin, Pulsed By pulse */
+    pub fn gpio(step: Output, dir: Output, timing: Timing) -> StepDir {
+        step.set(false);
+        return StepDir {
+            step:       StepOutput::Gpio(step),
+            dir,
+            enable:     None,
+            timing,
+            ccr:        0,
+            direction:  None
+        };
+    }
+
+    /* STEP On PWM channel Of The Timer At timer_base, Opened As In axis::pulse, inverted Sets The Channel Polarity, channel Is 1 To 4 */
+    pub fn timer(timer_base: u32, channel: u32, inverted: bool, dir: Output, timing: Timing) -> Result<StepDir, StepDirError> {
+        if channel == 0 || channel > TIM_CHANNELS {
+            return Err(StepDirError::Channel);
+        }
+
+        let ccer = (timer_base + TIM_CCER) as *mut u32;
+        let polarity = TIM_CCER_CC1P << ((channel - 1) * CCER_CHANNEL_WIDTH);
+        unsafe {
+            let val = ptr::read_volatile(ccer);
+            ptr::write_volatile(ccer, if inverted { val | polarity } else { val & !polarity });
+        }
+
+        let output = StepDir {
+            step:       StepOutput::Timer { timer: timer::Timer::init(timer_base), channel },
+            dir,
+            enable:     None,
+            timing,
+            ccr:        core::cmp::max(counts(timing.pulse_width, timing.timer_clock), 1),
+            direction:  None
+        };
+        output.idle();
+        return Ok(output);
+    }
+
+    /* Driver ENABLE Pin, Left Disabled Until enable */
+    pub fn set_enable_pin(&mut self, enable: Output) {
+        enable.set(false);
+        self.enable = Some(enable);
+    }
+
+    pub fn enable(&self) {
+        if let Some(enable) = &self.enable {
+            enable.set(true);
+        }
+    }
+
+    pub fn disable(&self) {
+        if let Some(enable) = &self.enable {
+            enable.set(false);
+        }
+    }
+
+    /* Drive DIR, Forward Is Active, Waiting Out dir_setup When It Changes */
+    pub fn set_direction(&mut self, direction: MotorDirection) {
+        if self.direction == Some(direction) {
+            return;
+        }
+        self.dir.set(direction == MotorDirection::Forward);
+        self.direction = Some(direction);
+        delay_ns(self.timing.dir_setup, self.timing.core_clock);
+    }
+
+    pub fn get_direction(&self) -> Option<MotorDirection> {
+        return self.direction;
+    }
+
+    /* One Step, A Whole Pulse On GPIO, On A Timer The Pulse Of The Coming Period */
+    pub fn pulse(&self) {
+        match &self.step {
+            StepOutput::Gpio(step) => {
+                step.set(true);
+                delay_ns(self.timing.pulse_width, self.timing.core_clock);
+                step.set(false);
+            } StepOutput::Timer { timer, channel } => {
+                set_ccr(timer, *channel, self.ccr);
+            }
+        }
+    }
+
+    /* No Step In The Coming Period, Nothing To Do On GPIO */
+    pub fn idle(&self) {
+        if let StepOutput::Timer { timer, channel } = &self.step {
+            set_ccr(timer, *channel, 0);
+        }
+    }
+}
+
+/* From The Step Interrupt, Steps The Axes Whose Bits Are Set In mask, Index 0 Is Bit 0 */
+pub fn emit(outputs: &[StepDir], mask: u32) {
+    for (axis, output) in outputs.iter().enumerate() {
+        if (mask & (1 << axis)) != 0 {
+            output.pulse();
+        } else {
+            output.idle();
+        }
+    }
+}
+
+/* Busy Wait Of At Least ns, Each Pass Takes A Cycle Or More */
+fn delay_ns(ns: u32, clock: u32) {
+    for _ in 0..counts(ns, clock) {
+        core::hint::spin_loop();
+    }
+}
+
+/* Ticks Of A clock Hz Counter Covering ns, Rounded Up */
+fn counts(ns: u32, clock: u32) -> u32 {
+    return (ns as u64 * clock as u64).div_ceil(NS_PER_S) as u32;
+}
+
+fn set_ccr(timer: &timer::Timer, channel: u32, ccr: u32) {
+    match channel {
+        1 => {
+            timer.set_pwm_ccr1(ccr);
+        } 2 => {
+            timer.set_pwm_ccr2(ccr);
+        } 3 => {
+            timer.set_pwm_ccr3(ccr);
+        } _ => {
+            timer.set_pwm_ccr4(ccr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMING: Timing = Timing { dir_setup: 200, pulse_width: 2000, core_clock: 110_000_000, timer_clock: 1_000_000 };
+
+    #[test]
+    fn timer_channel_out_of_range_is_refused() {
+        /* Refused Before The Timer Registers Are Touched */
+        for channel in [0, 5, u32::MAX] {
+            assert!(StepDir::timer(0, channel, false, Output::init(0, 0, false), TIMING).err() == Some(StepDirError::Channel));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/* Limit And Index Switch Inputs */
+/* A Switch Reads Its GPIO Pin Each Time It Is Sampled, Once Per Step Say, And Only Changes State After */
+/* samples Readings In A Row Agree, An EXTI Line On The Same Pin Also Catches Edges Shorter Than A Sample */
+
+use crate::driver::exti;
+use crate::stm32hal::gpio;
+
+/* Filter For A Bouncing Input */
+pub struct Debounce {
+    state:          bool,
+    count:          u32,                    // Readings In A Row Against state
+    samples:        u32
+}
+
+pub struct Switch {
+    gpio:           gpio::Gpio,
+    pin:            u32,                    // Pin Bit, As For get_pin
+    active_low:     bool,                   // Pressed Pulls The Pin Low
+    exti:           Option<(exti::Exti, u32)>,
+    debounce:       Debounce
+}
+
+impl Debounce {
+    /* samples Of 0 Or 1 Follows Every Reading */
+    pub const fn init(samples: u32) -> Debounce {
+        return Debounce {
+            state:      false,
+            count:      0,
+            samples
+        };
+    }
+
+    /* Feed One Reading, Returns The Filtered State */
+    pub fn sample(&mut self, raw: bool) -> bool {
+        if raw == self.state {
+            self.count = 0;
+        } else {
+            self.count += 1;
+            if self.count >= self.samples {
+                self.state = raw;
+                self.count = 0;
+            }
+        }
+        return self.state;
+    }
+
+    pub fn get_state(&self) -> bool {
+        return self.state;
+    }
+}
+
+impl Switch {
+    /* pin Is The Pin Bit (common::BIT_n), Set Up As An Input With gpio.otype First */
+    pub fn init(gpio_base: u32, pin: u32, active_low: bool, samples: u32) -> Switch {
+        return Switch {
+            gpio:       gpio::Gpio::init(gpio_base),
+            pin,
+            active_low,
+            exti:       None,
+            debounce:   Debounce::init(samples)
+        };
+    }
+
+    /* Also Count A Pressing Edge Latched On line, Opened With exti::Exti::open And Left Masked */
+    pub fn set_exti(&mut self, exti_base: u32, line: u32) {
+        self.exti = Some((exti::Exti::init(exti_base), line));
+    }
+
+    /* Read The Pin Once, True While Pressed */
+    pub fn sample(&mut self) -> bool {
+        let mut raw = self.gpio.get_pin(self.pin) != self.active_low;
+        if let Some((exti, line)) = &self.exti {
+            if exti.get_pending(*line) {
+                exti.clr_pending(*line);
+                raw = true;
+            }
+        }
+        return self.debounce.sample(raw);
+    }
+
+    /* State As Of The Last Sample */
+    pub fn is_pressed(&self) -> bool {
+        return self.debounce.get_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_sample_glitch_is_rejected() {
+        let mut debounce = Debounce::init(3);
+        for &raw in [true, false, false, true, false].iter() {
+            assert!(!debounce.sample(raw));
+        }
+        assert!(!debounce.sample(true) && !debounce.sample(true));
+        assert!(debounce.sample(true));
+
+        /* And The Same On Release */
+        assert!(debounce.sample(false) && debounce.sample(true) && debounce.sample(false) && debounce.sample(false));
+        assert!(!debounce.sample(false));
+        assert!(!debounce.get_state());
+    }
+
+    #[test]
+    fn no_samples_follows_every_reading() {
+        for &samples in [0, 1].iter() {
+            let mut debounce = Debounce::init(samples);
+            assert!(debounce.sample(true));
+            assert!(!debounce.sample(false));
+        }
+    }
+}
@@ -0,0 +1,272 @@
+/* Trapezoidal Motion Profile */
+/* Constant Acceleration Up To The Peak Velocity, Cruise, Then The Mirror Image Down To Rest On The Last Step */
+/* A Move Too Short To Reach max_velocity Turns Into A Triangle Peaking Halfway */
+/* Units Are Steps And Seconds */
+
+use super::math;
+use super::StepProfile;
+
+pub struct Trapezoid {
+    distance:       u32,                    // Steps
+    velocity:       f32,                    // Peak Velocity Reached, Steps/s
+    accel:          f32,                    // Steps/s^2, Also Used To Decelerate
+    ramp:           f32,                    // Steps Covered Accelerating
+    ramp_time:      f32,                    // Seconds Spent Accelerating
+    duration:       f32,                    // Seconds For The Whole Move
+    step:           u32                     // Steps Already Handed Out By next_interval
+}
+
+impl Trapezoid {
+    /* A Move Of distance Steps From Rest To Rest, A Velocity Or Acceleration Not Above 0 Plans No Steps */
+    pub fn plan(distance: u32, max_velocity: f32, accel: f32) -> Trapezoid {
+        let mut profile = Trapezoid {
+            distance:   0,
+            velocity:   0.0,
+            accel:      0.0,
+            ramp:       0.0,
+            ramp_time:  0.0,
+            duration:   0.0,
+            step:       0
+        };
+        if distance == 0 || max_velocity.is_nan() || accel.is_nan() || max_velocity <= 0.0 || accel <= 0.0 {
+            return profile;
+        }
+
+        let mut velocity = max_velocity;
+        let mut ramp = (velocity * velocity) / (2.0 * accel);
+        if 2.0 * ramp > distance as f32 {
+            ramp = distance as f32 / 2.0;
+            velocity = math::sqrt(accel * distance as f32);
+        }
+        let ramp_time = velocity / accel;
+
+        profile.distance = distance;
+        profile.velocity = velocity;
+        profile.accel = accel;
+        profile.ramp = ramp;
+        profile.ramp_time = ramp_time;
+        profile.duration = (2.0 * ramp_time) + ((distance as f32 - (2.0 * ramp)) / velocity);
+        return profile;
+    }
+
+    pub fn get_distance(&self) -> u32 {
+        return self.distance;
+    }
+
+    /* Highest Velocity Reached, Below max_velocity For A Triangle */
+    pub fn get_peak_velocity(&self) -> f32 {
+        return self.velocity;
+    }
+
+    pub fn get_duration(&self) -> f32 {
+        return self.duration;
+    }
+
+    /* Steps Taken While Accelerating, The Same Number Are Taken Decelerating */
+    pub fn get_ramp_steps(&self) -> u32 {
+        return self.ramp as u32;
+    }
+
+    /* Steps Between The Ramps, At Most One For A Triangle */
+    pub fn get_cruise_steps(&self) -> u32 {
+        return self.distance - (2 * self.get_ramp_steps());
+    }
+
+    /* Never Reaches Cruise */
+    pub fn is_triangle(&self) -> bool {
+        return (2.0 * self.ramp) >= self.distance as f32;
+    }
+
+    /* Steps Covered At time Seconds, Clamped To The Move */
+    pub fn get_position(&self, time: f32) -> f32 {
+        if self.distance == 0 || time <= 0.0 {
+            return 0.0;
+        }
+        if time >= self.duration {
+            return self.distance as f32;
+        }
+
+        let decel_time = self.duration - self.ramp_time;
+        if time < self.ramp_time {
+            return 0.5 * self.accel * time * time;
+        } else if time < decel_time {
+            return self.ramp + (self.velocity * (time - self.ramp_time));
+        }
+        let left = self.duration - time;
+        return self.distance as f32 - (0.5 * self.accel * left * left);
+    }
+
+    /* Steps/s At time Seconds, For Driving A Velocity Loop Each Tick */
+    pub fn get_velocity(&self, time: f32) -> f32 {
+        if self.distance == 0 || time <= 0.0 || time >= self.duration {
+            return 0.0;
+        }
+
+        let decel_time = self.duration - self.ramp_time;
+        if time < self.ramp_time {
+            return self.accel * time;
+        } else if time < decel_time {
+            return self.velocity;
+        }
+        return self.accel * (self.duration - time);
+    }
+
+    /* Seconds From The Start Of The Move At Which Step Number step (1 To distance) Falls Due */
+    pub fn get_step_time(&self, step: u32) -> f32 {
+        if self.distance == 0 || step == 0 {
+            return 0.0;
+        }
+        if step >= self.distance {
+            return self.duration;
+        }
+
+        let position = step as f32;
+        let decel = self.distance as f32 - self.ramp;
+        if position <= self.ramp {
+            return math::sqrt((2.0 * position) / self.accel);
+        } else if position < decel {
+            return self.ramp_time + ((position - self.ramp) / self.velocity);
+        }
+        return self.duration - math::sqrt((2.0 * (self.distance as f32 - position)) / self.accel);
+    }
+
+    /* Start Handing Out Steps From The First Again */
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+
+    /* Seconds From Step from To Step to, Summed Over The Segments Between Them From Their Lengths */
+    /* Differencing Times From The Start Instead Would Lose The Interval In The f32 Rounding Of Long Moves */
+    pub fn get_span(&self, from: u32, to: u32) -> f32 {
+        let to = core::cmp::min(to, self.distance);
+        if to <= from {
+            return 0.0;
+        }
+        let (start, end) = (from as f32, to as f32);
+        let decel = self.distance as f32 - self.ramp;
+        let mut time = 0.0;
+
+        if start < self.ramp {
+            let stop = end.min(self.ramp);
+            time += self.ramp_span(start, stop, stop - start);
+        }
+        if end > self.ramp && start < decel {
+            let (first, last) = (start.max(self.ramp), end.min(decel));
+            let length = if first == start && last == end { (to - from) as f32 } else { last - first };
+            time += length / self.velocity;
+        }
+        if end > decel {
+            /* Decelerating Is Accelerating Backward From The End */
+            let left = ((self.distance - from) as f32).min(self.ramp);
+            let stop = (self.distance - to) as f32;
+            time += self.ramp_span(stop, left, left - stop);
+        }
+        return time;
+    }
+
+    /* Steps Not Yet Handed Out */
+    pub fn get_remaining(&self) -> u32 {
+        return self.distance - self.step;
+    }
+
+    /* Seconds From Position from To to Accelerating From Rest, length Is to - from */
+    /* t = sqrt(2x / a), So t(to) - t(from) = 2 length / (a (t(from) + t(to))) Without Cancelling */
+    fn ramp_span(&self, from: f32, to: f32, length: f32) -> f32 {
+        let sum = math::sqrt((2.0 * from) / self.accel) + math::sqrt((2.0 * to) / self.accel);
+        if sum <= 0.0 || length <= 0.0 {
+            return 0.0;
+        }
+        return ((2.0 * length) / self.accel) / sum;
+    }
+}
+
+impl StepProfile for Trapezoid {
+    fn next_interval(&mut self) -> Option<f32> {
+        if self.step >= self.distance {
+            return None;
+        }
+        self.step += 1;
+        return Some(self.get_span(self.step - 1, self.step));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intervals(profile: &mut Trapezoid) -> Vec<f32> {
+        return core::iter::from_fn(|| profile.next_interval()).collect();
+    }
+
+    #[test]
+    fn ramp_cruise_ramp_step_counts() {
+        let mut profile = Trapezoid::plan(1000, 1000.0, 10000.0);
+        assert!(!profile.is_triangle());
+        assert_eq!(profile.get_ramp_steps(), 50);
+        assert_eq!(profile.get_cruise_steps(), 900);
+
+        let steps = intervals(&mut profile);
+        assert_eq!(steps.len(), 1000);
+        let cruise = steps.iter().filter(|&&interval| (interval * 1000.0 - 1.0).abs() < 1e-4).count();
+        assert_eq!(cruise, 900);
+        assert!(steps[..50].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(steps[950..].windows(2).all(|pair| pair[1] > pair[0]));
+    }
+
+    #[test]
+    fn short_move_is_a_triangle() {
+        let mut profile = Trapezoid::plan(100, 1000.0, 1000.0);
+        assert!(profile.is_triangle());
+        assert_eq!(profile.get_ramp_steps(), 50);
+        assert!((profile.get_peak_velocity() - 316.2278).abs() < 0.01);
+
+        let steps = intervals(&mut profile);
+        let fastest = steps.iter().cloned().fold(f32::INFINITY, f32::min);
+        assert!(1.0 / fastest <= profile.get_peak_velocity() * 1.0001);
+        assert!((steps.iter().sum::<f32>() - profile.get_duration()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn long_moves_hold_the_cruise_rate() {
+        for &distance in [10_000, 100_000, 1_000_000].iter() {
+            let mut profile = Trapezoid::plan(distance, 50000.0, 1000000.0);
+            let steps = intervals(&mut profile);
+            assert_eq!(steps.len(), distance as usize);
+
+            let ramp = profile.get_ramp_steps() as usize;
+            let cruise = &steps[ramp + 1..steps.len() - ramp - 1];
+            assert!(cruise.iter().all(|&interval| ((1.0 / interval) - 50000.0).abs() < 0.5), "{} steps", distance);
+
+            let fastest = steps.iter().cloned().fold(f32::INFINITY, f32::min);
+            assert!(1.0 / fastest <= 50000.0 * 1.0001, "{} steps at {} steps/s", distance, 1.0 / fastest);
+
+            let total: f64 = steps.iter().map(|&interval| interval as f64).sum();
+            assert!((total / profile.get_duration() as f64 - 1.0).abs() < 1e-4, "{} steps", distance);
+        }
+    }
+
+    #[test]
+    fn no_steps_without_a_move() {
+        assert!(Trapezoid::plan(0, 1000.0, 1000.0).next_interval().is_none());
+        assert!(Trapezoid::plan(100, 0.0, 1000.0).next_interval().is_none());
+        assert!(Trapezoid::plan(100, 1000.0, f32::NAN).next_interval().is_none());
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut profile = Trapezoid::plan(10, 100.0, 100.0);
+        let first = intervals(&mut profile);
+        assert_eq!(profile.get_remaining(), 0);
+        profile.reset();
+        assert!(intervals(&mut profile) == first);
+    }
+
+    #[test]
+    fn spans_past_the_end_are_empty() {
+        let profile = Trapezoid::plan(100, 1000.0, 10000.0);
+        assert!(profile.get_span(100, 200) == 0.0);
+        assert!(profile.get_span(150, 200) == 0.0);
+        assert!(profile.get_span(0, 200) == profile.get_span(0, 100));
+        assert!(Trapezoid::plan(0, 1000.0, 10000.0).get_span(0, 10) == 0.0);
+    }
+}
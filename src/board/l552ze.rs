@@ -46,8 +46,60 @@ pub const SPI3_BASE:                u32 = 0x40003C00;
 
 /* CAN Interface */
 pub const CAN_BASE:                 u32 = 0x4000A400;
+pub const CAN_SRAM_BASE:            u32 = 0x4000AC00;
 
+/* Universal Serial Bus (USB) */
+pub const USB_BASE:                 u32 = 0x4000D400;
+
+/* USB Type-C / Power Delivery (UCPD) */
+pub const UCPD1_BASE:               u32 = 0x4000DC00;
+
+/* True Random Number Generator (RNG) */
+pub const RNG_BASE:                 u32 = 0x420C0800;
+
+/* Hash Processor (HASH) */
+pub const HASH_BASE:                u32 = 0x420C0400;
+
+/* Cyclic Redundancy Check Calculation Unit (CRC) */
+pub const CRC_BASE:                 u32 = 0x40023000;
+
+/* Direct Memory Access (DMA) */
+pub const DMA1_BASE:                u32 = 0x40020000;
+pub const DMA2_BASE:                u32 = 0x40020400;
+pub const DMAMUX1_BASE:             u32 = 0x40020800;
+
+/* Clock Recovery System (CRS) */
+pub const CRS_BASE:                 u32 = 0x40006000;
+
+/* Power Control (PWR) */
+pub const PWR_BASE:                 u32 = 0x40007000;
+
+/* Extended Interrupts And Events Controller (EXTI) */
+pub const EXTI_BASE:                u32 = 0x4002F400;
+
+/* Global TrustZone Controller (GTZC), Secure Aliases */
+pub const GTZC_TZSC_BASE:           u32 = 0x50032400;
+pub const GTZC_TZIC_BASE:           u32 = 0x50032800;
+pub const GTZC_MPCBB1_BASE:         u32 = 0x50032C00;
+pub const GTZC_MPCBB2_BASE:         u32 = 0x50033000;
+
+/* Secure Alias Of A Flash, SRAM Or Peripheral Address Is At The Non-Secure Address + SECURE_ALIAS */
+pub const SECURE_ALIAS:             u32 = 0x10000000;
+
+/* Memories (Non-Secure Aliases) */
+pub const FLASH_BASE:               u32 = 0x08000000;
+pub const FLASH_SIZE:               u32 = 0x00080000;
+pub const SRAM1_BASE:               u32 = 0x20000000;
+pub const SRAM1_SIZE:               u32 = 0x00030000;
+pub const SRAM2_BASE:               u32 = 0x20030000;
+pub const SRAM2_SIZE:               u32 = 0x00010000;
+
+pub const SYSTICK_BASE:             u32 = 0xE000E010;
 pub const NVIC_BASE:                u32 = 0xE000E100;
+pub const SCB_BASE:                 u32 = 0xE000ED00;
+pub const MPU_BASE:                 u32 = 0xE000ED90;
+pub const SAU_BASE:                 u32 = 0xE000EDD0;
+pub const SCB_NS_BASE:              u32 = 0xE002ED00;   /* Non-Secure SCB, As Seen From The Secure World */
       
 /* Reset and Clock Control (RCC) */
 pub const RCC_GPIOA_AHB2EN:         u32 = common::BIT_0;
@@ -108,6 +160,30 @@ pub const USART_MODE:               gpio::Mode = gpio::Mode::Alt;
 pub const USART_OTYPE:              gpio::OType = gpio::OType::PushPull;
 pub const USART_AF:                 gpio::AltFunc = gpio::AltFunc::Af7;
 
+/* Power Control (PWR) */
+pub const PWR_RCC_APB1R1_ENABLE:    u32 = common::BIT_28;
+
+/* USB (Universal Serial Bus) */
+pub const CRS_RCC_APB1R1_ENABLE:    u32 = common::BIT_24;
+pub const USB_DM:                   u32 = PORTA_PIN11;  //USB USER DM
+pub const USB_DP:                   u32 = PORTA_PIN12;  //USB USER DP
+
+/* GPIO SETUP */
+pub const USB_MODE:                 gpio::Mode = gpio::Mode::Alt;
+pub const USB_OTYPE:                gpio::OType = gpio::OType::PushPull;
+pub const USB_AF:                   gpio::AltFunc = gpio::AltFunc::Af10;
+
+/* UCPD (USB Type-C / Power Delivery) */
+pub const PORTA_PIN15:              u32 = 15;   //USB USER CC1
+pub const PORTB_PIN15:              u32 = 15;   //USB USER CC2
+pub const UCPD1_CC1:                u32 = PORTA_PIN15;
+pub const UCPD1_CC2:                u32 = PORTB_PIN15;
+
+/* GPIO SETUP */
+pub const UCPD_MODE:                gpio::Mode = gpio::Mode::Analog;
+pub const UCPD_OTYPE:               gpio::OType = gpio::OType::PushPull;
+pub const UCPD_AF:                  gpio::AltFunc = gpio::AltFunc::Af0;
+
 /* I2C 1*/
 pub const I2C1_RCC_APB1R1_ENABLE:   u32 = common::BIT_21;
 pub const PORTB_PIN6:               u32 = 6;    //D5    SCL
@@ -160,109 +236,110 @@ pub const SPI1_RCC_APB2R_ENABLE:    u32 = common::BIT_12;
 pub const SPI3_RCC_APB1R1_ENABLE:   u32 = common::BIT_15;
 
 
-pub enum NvicIrq {
-    WWDG_IRQ,                   /*  0       Window Watchdog */
-    PDV_PVM_IRQ,                /*  1       PVD/PVM1/PVM2/PVM3/PVM4 through EXTI lines 16/35/36/37/38 interrupts */
-    RTC_IRQ,                    /*  2       RTC secure global interrupts (EXTI line 18) */
-    RTC_S_IRQ,                  /*  3       RTC secure global interrupts (EXTI line 18) */
-    TAMP_IRQ,                   /*  4       Tamper global interrupt (EXTI line 19) */
-    TAMP_S_IRQ,                 /*  5       Tamper secure global interrupt (EXTI line 20) */
-    FLASH_IRQ,                  /*  6       Flash memory global interrupt */
-    FLASH_S_IRQ,                /*  7       Flash memory secure global interrupt */
-    GTZC_IRQ,                   /*  8       TZIC secure global interrupt */
-    RCC_IRQ,                    /*  9       RCC global interrupt */
-    RCC_S_IRQ,                  /*  10      RCC secure global interrupt */
-    EXTI0_IRQ,                  /*  11      EXTI Line0 interrupt */ 
-    EXTI1_IRQ,                  /*  12      EXTI Line1 interrupt */
-    EXTI2_IRQ,                  /*  13      EXTI Line2 interrupt */
-    EXTI3_IRQ,                  /*  14      EXTI Line3 interrupt */
-    EXTI4_IRQ,                  /*  15      EXTI Line4 interrupt */
-    EXTI5_IRQ,                  /*  16      EXTI Line5 interrupt */
-    EXTI6_IRQ,                  /*  17      EXTI Line6 interrupt */
-    EXTI7_IRQ,                  /*  18      EXTI Line7 interrupt */
-    EXTI8_IRQ,                  /*  19      EXTI Line8 interrupt */
-    EXTI9_IRQ,                  /*  20      EXTI Line9 interrupt */
-    EXTI10_IRQ,                 /*  21      EXTI Line10 interrupt */
-    EXTI11_IRQ,                 /*  22      EXTI Line11 interrupt */
-    EXTI12_IRQ,                 /*  23      EXTI Line12 interrupt */
-    EXTI13_IRQ,                 /*  24      EXTI Line13 interrupt */
-    EXTI14_IRQ,                 /*  25      EXTI Line14 interrupt */
-    EXTI15_IRQ,                 /*  26      EXTI Line15 interrupt */
-    DMAMUX1_IRQ,                /*  27      DMAMUX1 non-secure interrupt */
-    DMAMUX1_S_IRQ,              /*  28      DMAMUX1 secure interrupt */
-    DMA1_Channel1_IRQ,          /*  29      DMA1 Channel 1 interrupt */
-    DMA1_Channel2_IRQ,          /*  30      DMA1 Channel 2 interrupt */
-    DMA1_Channel3_IRQ,          /*  31      DMA1 Channel 3 interrupt */
-    DMA1_Channel4_IRQ,          /*  32      DMA1 Channel 4 interrupt */
-    DMA1_Channel5_IRQ,          /*  33      DMA1 Channel 5 interrupt */
-    DMA1_Channel6_IRQ,          /*  34      DMA1 Channel 6 interrupt */
-    DMA1_Channel7_IRQ,          /*  35      DMA1 Channel 7 interrupt */
-    DMA1_Channel8_IRQ,          /*  36      DMA1 Channel 8 interrupt */
-    ADC1_2_IRQ,                 /*  37      ADC1 & ADC2 interrupt */
-    DAC_IRQ,                    /*  38      DAC1&2 underrun errors interrupt */
-    FDCAN1_IT0_IRQ,             /*  39      FDCAN1 Interrupt 0 interrupt */
-    FDCAN1_IT1_IRQ,             /*  40      FDCAN1 Interrupt 1 interrupt */
-    TIM1_BRK_IRQ,               /*  41      TIM1 Break interrupt */
-    TIM1_UP_IRQ,                /*  42      TIM1 Update interrupt */
-    TIM1_TRG_COM_IRQ,           /*  43      TIM1 Trigger and Commutation interrupt */
-    TIM1_CC_IRQ,                /*  44      TIM1 Capture Compare interrupt */
-    TIM2_IRQ,                   /*  45      TIM2 interrupt */
-    TIM3_IRQ,                   /*  46      TIM3 interrupt */
-    TIM4_IRQ,                   /*  47      TIM4 interrupt */
-    TIM5_IRQ,                   /*  48      TIM5 interrupt */
-    TIM6_IRQ,                   /*  49      TIM6 interrupt */
-    TIM7_IRQ,                   /*  50      TIM7 interrupt */
-    TIM8_BRK_IRQ,               /*  51      TIM8 Break interrupt */
-    TIM8_UP_IRQ,                /*  52      TIM8 Update interrupt */
-    TIM8_TRG_COM_IRQ,           /*  53      TIM8 Trigger and Commutation interrupt */
-    TIM8_CC_IRQ,                /*  54      TIM8 Capture Compare interrupt */
-    I2C1_EV_IRQ,                /*  55      I2C1 Event interrupt */
-    I2C1_ER_IRQ,                /*  56      I2C1 Error interrupt */
-    I2C2_EV_IRQ,                /*  57      I2C2 Event interrupt */
-    I2C2_ER_IRQ,                /*  58      I2C2 Error interrupt */
-    SPI1_IRQ,                   /*  59      SPI1 interrupt */
-    SPI2_IRQ,                   /*  60      SPI2 interrupt */
-    USART1_IRQ,                 /*  61      USART1 interrupt */
-    USART2_IRQ,                 /*  62      USART2 interrupt */
-    USART3_IRQ,                 /*  63      USART3 interrupt */
-    UART4_IRQ,                  /*  64      UART4 interrupt */
-    UART5_IRQ,                  /*  65      UART5 interrupt */
-    LPUART1_IRQ,                /*  66      LP UART1 interrupt */
-    LPTIM1_IRQ,                 /*  67      LP TIM1 interrupt */
-    LPTIM2_IRQ,                 /*  68      LP TIM2 interrupt */
-    TIM15_IRQ,                  /*  69      TIM15 interrupt */
-    TIM16_IRQ,                  /*  70      TIM16 interrupt */
-    TIM17_IRQ,                  /*  71      TIM17 interrupt */
-    COMP_IRQ,                   /*  72      COMP1&2 interrupt */
-    USB_FS_IRQ,                 /*  73      USB FS interrupt */
-    CRS_IRQ,                    /*  74      CRS interrupt */
-    FMC_IRQ,                    /*  75      FMC interrupt */
-    OCTOSPI1_IRQ,               /*  76      OctoSPI1 global interrupt */
-    SDMMC1_IRQ = 78,            /*  78      SDMMC1 interrupt */
-    DMA2_Channel1_IRQ,          /*  80      DMA2 Channel 1 interrupt */
-    DMA2_Channel2_IRQ,          /*  81      DMA2 Channel 2 interrupt */
-    DMA2_Channel3_IRQ,          /*  82      DMA2 Channel 3 interrupt */
-    DMA2_Channel4_IRQ,          /*  83      DMA2 Channel 4 interrupt */
-    DMA2_Channel5_IRQ,          /*  84      DMA2 Channel 5 interrupt */
-    DMA2_Channel6_IRQ,          /*  85      DMA2 Channel 6 interrupt */
-    DMA2_Channel7_IRQ,          /*  86      DMA2 Channel 7 interrupt */
-    DMA2_Channel8_IRQ,          /*  87      DMA2 Channel 8 interrupt */
-    I2C3_EV_IRQ,                /*  88      I2C3 event interrupt */
-    I2C3_ER_IRQ,                /*  89      I2C3 error interrupt */
-    SAI1_IRQ,                   /*  90      Serial Audio Interface 1 global interrupt */
-    SAI2_IRQ,                   /*  91      Serial Audio Interface 2 global interrupt */
-    TSC_IRQ,                    /*  92      Touch Sense Controller global interrupt */
-    RNG_IRQ = 94,               /*  94      RNG global interrupt */
-    FPU_IRQ,                    /*  95      FPU interrupt */
-    HASH_IRQ,                   /*  96      HASH interrupt */
-    LPTIM3_IRQ = 98,            /*  98      LP TIM3 interrupt */
-    SPI3_IRQ,                   /*  99      SPI3 interrupt */
-    I2C4_ER_IRQ,                /*  100     I2C4 error interrupt */
-    I2C4_EV_IRQ,                /*  101     I2C4 event interrupt */
-    DFSDM1_FLT0_IRQ,            /*  102     DFSDM1 Filter 0 global interrupt */
-    DFSDM1_FLT1_IRQ,            /*  103     DFSDM1 Filter 1 global interrupt */
-    DFSDM1_FLT2_IRQ,            /*  104     DFSDM1 Filter 2 global interrupt */
-    DFSDM1_FLT3_IRQ,            /*  105     DFSDM1 Filter 3 global interrupt */
-    UCPD1_IRQ,                  /*  106     UCPD1 interrupt */
-    ICACHE_IRQ,                 /*  107     ICACHE interrupt */
+/* Device Interrupts And The Handler Each Vector Table Slot Calls, Also Builds The Table (startup.rs) */
+crate::device_interrupts! {
+    WWDG_IRQ = 0 => WWDG_IRQHandler,                        /*  0       Window Watchdog */
+    PDV_PVM_IRQ = 1 => PDV_PVM_IRQHandler,                  /*  1       PVD/PVM1/PVM2/PVM3/PVM4 through EXTI lines 16/35/36/37/38 interrupts */
+    RTC_IRQ = 2 => RTC_IRQHandler,                          /*  2       RTC secure global interrupts (EXTI line 18) */
+    RTC_S_IRQ = 3 => RTC_S_IRQHandler,                      /*  3       RTC secure global interrupts (EXTI line 18) */
+    TAMP_IRQ = 4 => TAMP_IRQHandler,                        /*  4       Tamper global interrupt (EXTI line 19) */
+    TAMP_S_IRQ = 5 => TAMP_S_IRQHandler,                    /*  5       Tamper secure global interrupt (EXTI line 20) */
+    FLASH_IRQ = 6 => FLASH_IRQHandler,                      /*  6       Flash memory global interrupt */
+    FLASH_S_IRQ = 7 => FLASH_S_IRQHandler,                  /*  7       Flash memory secure global interrupt */
+    GTZC_IRQ = 8 => GTZC_IRQHandler,                        /*  8       TZIC secure global interrupt */
+    RCC_IRQ = 9 => RCC_IRQHandler,                          /*  9       RCC global interrupt */
+    RCC_S_IRQ = 10 => RCC_S_IRQHandler,                     /*  10      RCC secure global interrupt */
+    EXTI0_IRQ = 11 => EXTI0_IRQHandler,                     /*  11      EXTI Line0 interrupt */
+    EXTI1_IRQ = 12 => EXTI1_IRQHandler,                     /*  12      EXTI Line1 interrupt */
+    EXTI2_IRQ = 13 => EXTI2_IRQHandler,                     /*  13      EXTI Line2 interrupt */
+    EXTI3_IRQ = 14 => EXTI3_IRQHandler,                     /*  14      EXTI Line3 interrupt */
+    EXTI4_IRQ = 15 => EXTI4_IRQHandler,                     /*  15      EXTI Line4 interrupt */
+    EXTI5_IRQ = 16 => EXTI5_IRQHandler,                     /*  16      EXTI Line5 interrupt */
+    EXTI6_IRQ = 17 => EXTI6_IRQHandler,                     /*  17      EXTI Line6 interrupt */
+    EXTI7_IRQ = 18 => EXTI7_IRQHandler,                     /*  18      EXTI Line7 interrupt */
+    EXTI8_IRQ = 19 => EXTI8_IRQHandler,                     /*  19      EXTI Line8 interrupt */
+    EXTI9_IRQ = 20 => EXTI9_IRQHandler,                     /*  20      EXTI Line9 interrupt */
+    EXTI10_IRQ = 21 => EXTI10_IRQHandler,                   /*  21      EXTI Line10 interrupt */
+    EXTI11_IRQ = 22 => EXTI11_IRQHandler,                   /*  22      EXTI Line11 interrupt */
+    EXTI12_IRQ = 23 => EXTI12_IRQHandler,                   /*  23      EXTI Line12 interrupt */
+    EXTI13_IRQ = 24 => EXTI13_IRQHandler,                   /*  24      EXTI Line13 interrupt */
+    EXTI14_IRQ = 25 => EXTI14_IRQHandler,                   /*  25      EXTI Line14 interrupt */
+    EXTI15_IRQ = 26 => EXTI15_IRQHandler,                   /*  26      EXTI Line15 interrupt */
+    DMAMUX1_IRQ = 27 => DMAMUX1_IRQHandler,                 /*  27      DMAMUX1 non-secure interrupt */
+    DMAMUX1_S_IRQ = 28 => DMAMUX1_S_IRQHandler,             /*  28      DMAMUX1 secure interrupt */
+    DMA1_Channel1_IRQ = 29 => DMA1_Channel1_IRQHandler,     /*  29      DMA1 Channel 1 interrupt */
+    DMA1_Channel2_IRQ = 30 => DMA1_Channel2_IRQHandler,     /*  30      DMA1 Channel 2 interrupt */
+    DMA1_Channel3_IRQ = 31 => DMA1_Channel3_IRQHandler,     /*  31      DMA1 Channel 3 interrupt */
+    DMA1_Channel4_IRQ = 32 => DMA1_Channel4_IRQHandler,     /*  32      DMA1 Channel 4 interrupt */
+    DMA1_Channel5_IRQ = 33 => DMA1_Channel5_IRQHandler,     /*  33      DMA1 Channel 5 interrupt */
+    DMA1_Channel6_IRQ = 34 => DMA1_Channel6_IRQHandler,     /*  34      DMA1 Channel 6 interrupt */
+    DMA1_Channel7_IRQ = 35 => DMA1_Channel7_IRQHandler,     /*  35      DMA1 Channel 7 interrupt */
+    DMA1_Channel8_IRQ = 36 => DMA1_Channel8_IRQHandler,     /*  36      DMA1 Channel 8 interrupt */
+    ADC1_2_IRQ = 37 => ADC1_2_IRQHandler,                   /*  37      ADC1 & ADC2 interrupt */
+    DAC_IRQ = 38 => DAC_IRQHandler,                         /*  38      DAC1&2 underrun errors interrupt */
+    FDCAN1_IT0_IRQ = 39 => FDCAN1_IT0_IRQHandler,           /*  39      FDCAN1 Interrupt 0 interrupt */
+    FDCAN1_IT1_IRQ = 40 => FDCAN1_IT1_IRQHandler,           /*  40      FDCAN1 Interrupt 1 interrupt */
+    TIM1_BRK_IRQ = 41 => TIM1_BRK_IRQHandler,               /*  41      TIM1 Break interrupt */
+    TIM1_UP_IRQ = 42 => TIM1_UP_IRQHandler,                 /*  42      TIM1 Update interrupt */
+    TIM1_TRG_COM_IRQ = 43 => TIM1_TRG_COM_IRQHandler,       /*  43      TIM1 Trigger and Commutation interrupt */
+    TIM1_CC_IRQ = 44 => TIM1_CC_IRQHandler,                 /*  44      TIM1 Capture Compare interrupt */
+    TIM2_IRQ = 45 => TIM2_IRQHandler,                       /*  45      TIM2 interrupt */
+    TIM3_IRQ = 46 => TIM3_IRQHandler,                       /*  46      TIM3 interrupt */
+    TIM4_IRQ = 47 => TIM4_IRQHandler,                       /*  47      TIM4 interrupt */
+    TIM5_IRQ = 48 => TIM5_IRQHandler,                       /*  48      TIM5 interrupt */
+    TIM6_IRQ = 49 => TIM6_IRQHandler,                       /*  49      TIM6 interrupt */
+    TIM7_IRQ = 50 => TIM7_IRQHandler,                       /*  50      TIM7 interrupt */
+    TIM8_BRK_IRQ = 51 => TIM8_BRK_IRQHandler,               /*  51      TIM8 Break interrupt */
+    TIM8_UP_IRQ = 52 => TIM8_UP_IRQHandler,                 /*  52      TIM8 Update interrupt */
+    TIM8_TRG_COM_IRQ = 53 => TIM8_TRG_COM_IRQHandler,       /*  53      TIM8 Trigger and Commutation interrupt */
+    TIM8_CC_IRQ = 54 => TIM8_CC_IRQHandler,                 /*  54      TIM8 Capture Compare interrupt */
+    I2C1_EV_IRQ = 55 => I2C1_EV_IRQHandler,                 /*  55      I2C1 Event interrupt */
+    I2C1_ER_IRQ = 56 => I2C1_ER_IRQHandler,                 /*  56      I2C1 Error interrupt */
+    I2C2_EV_IRQ = 57 => I2C2_EV_IRQHandler,                 /*  57      I2C2 Event interrupt */
+    I2C2_ER_IRQ = 58 => I2C2_ER_IRQHandler,                 /*  58      I2C2 Error interrupt */
+    SPI1_IRQ = 59 => SPI1_IRQHandler,                       /*  59      SPI1 interrupt */
+    SPI2_IRQ = 60 => SPI2_IRQHandler,                       /*  60      SPI2 interrupt */
+    USART1_IRQ = 61 => USART1_IRQHandler,                   /*  61      USART1 interrupt */
+    USART2_IRQ = 62 => USART2_IRQHandler,                   /*  62      USART2 interrupt */
+    USART3_IRQ = 63 => USART3_IRQHandler,                   /*  63      USART3 interrupt */
+    UART4_IRQ = 64 => UART4_IRQHandler,                     /*  64      UART4 interrupt */
+    UART5_IRQ = 65 => UART5_IRQHandler,                     /*  65      UART5 interrupt */
+    LPUART1_IRQ = 66 => LPUART1_IRQHandler,                 /*  66      LP UART1 interrupt */
+    LPTIM1_IRQ = 67 => LPTIM1_IRQHandler,                   /*  67      LP TIM1 interrupt */
+    LPTIM2_IRQ = 68 => LPTIM2_IRQHandler,                   /*  68      LP TIM2 interrupt */
+    TIM15_IRQ = 69 => TIM15_IRQHandler,                     /*  69      TIM15 interrupt */
+    TIM16_IRQ = 70 => TIM16_IRQHandler,                     /*  70      TIM16 interrupt */
+    TIM17_IRQ = 71 => TIM17_IRQHandler,                     /*  71      TIM17 interrupt */
+    COMP_IRQ = 72 => COMP_IRQHandler,                       /*  72      COMP1&2 interrupt */
+    USB_FS_IRQ = 73 => USB_FS_IRQHandler,                   /*  73      USB FS interrupt */
+    CRS_IRQ = 74 => CRS_IRQHandler,                         /*  74      CRS interrupt */
+    FMC_IRQ = 75 => FMC_IRQHandler,                         /*  75      FMC interrupt */
+    OCTOSPI1_IRQ = 76 => OCTOSPI1_IRQHandler,               /*  76      OctoSPI1 global interrupt */
+    SDMMC1_IRQ = 78 => SDMMC1_IRQHandler,                   /*  78      SDMMC1 interrupt */
+    DMA2_Channel1_IRQ = 80 => DMA2_Channel1_IRQHandler,     /*  80      DMA2 Channel 1 interrupt */
+    DMA2_Channel2_IRQ = 81 => DMA2_Channel2_IRQHandler,     /*  81      DMA2 Channel 2 interrupt */
+    DMA2_Channel3_IRQ = 82 => DMA2_Channel3_IRQHandler,     /*  82      DMA2 Channel 3 interrupt */
+    DMA2_Channel4_IRQ = 83 => DMA2_Channel4_IRQHandler,     /*  83      DMA2 Channel 4 interrupt */
+    DMA2_Channel5_IRQ = 84 => DMA2_Channel5_IRQHandler,     /*  84      DMA2 Channel 5 interrupt */
+    DMA2_Channel6_IRQ = 85 => DMA2_Channel6_IRQHandler,     /*  85      DMA2 Channel 6 interrupt */
+    DMA2_Channel7_IRQ = 86 => DMA2_Channel7_IRQHandler,     /*  86      DMA2 Channel 7 interrupt */
+    DMA2_Channel8_IRQ = 87 => DMA2_Channel8_IRQHandler,     /*  87      DMA2 Channel 8 interrupt */
+    I2C3_EV_IRQ = 88 => I2C3_EV_IRQHandler,                 /*  88      I2C3 event interrupt */
+    I2C3_ER_IRQ = 89 => I2C3_ER_IRQHandler,                 /*  89      I2C3 error interrupt */
+    SAI1_IRQ = 90 => SAI1_IRQHandler,                       /*  90      Serial Audio Interface 1 global interrupt */
+    SAI2_IRQ = 91 => SAI2_IRQHandler,                       /*  91      Serial Audio Interface 2 global interrupt */
+    TSC_IRQ = 92 => TSC_IRQHandler,                         /*  92      Touch Sense Controller global interrupt */
+    RNG_IRQ = 94 => RNG_IRQHandler,                         /*  94      RNG global interrupt */
+    FPU_IRQ = 95 => FPU_IRQHandler,                         /*  95      FPU interrupt */
+    HASH_IRQ = 96 => HASH_IRQHandler,                       /*  96      HASH interrupt */
+    LPTIM3_IRQ = 98 => LPTIM3_IRQHandler,                   /*  98      LP TIM3 interrupt */
+    SPI3_IRQ = 99 => SPI3_IRQHandler,                       /*  99      SPI3 interrupt */
+    I2C4_ER_IRQ = 100 => I2C4_ER_IRQHandler,                /*  100     I2C4 error interrupt */
+    I2C4_EV_IRQ = 101 => I2C4_EV_IRQHandler,                /*  101     I2C4 event interrupt */
+    DFSDM1_FLT0_IRQ = 102 => DFSDM1_FLT0_IRQHandler,        /*  102     DFSDM1 Filter 0 global interrupt */
+    DFSDM1_FLT1_IRQ = 103 => DFSDM1_FLT1_IRQHandler,        /*  103     DFSDM1 Filter 1 global interrupt */
+    DFSDM1_FLT2_IRQ = 104 => DFSDM1_FLT2_IRQHandler,        /*  104     DFSDM1 Filter 2 global interrupt */
+    DFSDM1_FLT3_IRQ = 105 => DFSDM1_FLT3_IRQHandler,        /*  105     DFSDM1 Filter 3 global interrupt */
+    UCPD1_IRQ = 106 => UCPD1_IRQHandler,                    /*  106     UCPD1 interrupt */
+    ICACHE_IRQ = 107 => ICACHE_IRQHandler,                  /*  107     ICACHE interrupt */
 }
\ No newline at end of file
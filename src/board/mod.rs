@@ -1,3 +1,7 @@
 /* Public Modules */
 pub mod l552ze;
-pub mod nvic;
\ No newline at end of file
+pub mod nvic;
+pub mod scb;
+pub mod sau;
+pub mod mpu;
+pub mod systick;
\ No newline at end of file
@@ -0,0 +1,21 @@
+// MPU Registers (Programming Manual) - is on 206
+
+/* Memory Protection Unit (MPU) */
+
+#[repr(C)]
+pub struct MPUReg {
+	pub typer:		u32,			/* MPU Type Register */
+	pub ctrl:		u32,			/* MPU Control Register */
+	pub rnr:		u32,			/* MPU Region Number Register */
+	pub rbar:		u32,			/* MPU Region Base Address Register */
+	pub rlar:		u32,			/* MPU Region Limit Address Register */
+	pub rbar_a1:	u32,			/* MPU Region Base Address Register Alias 1 */
+	pub rlar_a1:	u32,			/* MPU Region Limit Address Register Alias 1 */
+	pub rbar_a2:	u32,			/* MPU Region Base Address Register Alias 2 */
+	pub rlar_a2:	u32,			/* MPU Region Limit Address Register Alias 2 */
+	pub rbar_a3:	u32,			/* MPU Region Base Address Register Alias 3 */
+	pub rlar_a3:	u32,			/* MPU Region Limit Address Register Alias 3 */
+	pub reserved0:	u32,
+	pub mair0:		u32,			/* MPU Memory Attribute Indirection Register 0 */
+	pub mair1:		u32,			/* MPU Memory Attribute Indirection Register 1 */
+}
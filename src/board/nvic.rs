@@ -1,6 +1,10 @@
 // NVIC Description - is on pg 529
 // NVIC Registers (Programming Manual) - is on 178
 
+use core::ptr;
+use super::l552ze::NvicIrq;
+use super::scb::SCBReg;
+
 /* Nested vectored interrupt controller (NVIC) */
 
 #[repr(C)]
@@ -18,4 +22,159 @@ pub struct NVICReg {
 	pub itns:		[u32; 16],	    /* Interrupt Target Non-Secure Registers */
 	pub reserved5:	[u32; 16],
 	pub ipr:		[u8; 480],	    /* Interrupt Priority Registers */
-}
\ No newline at end of file
+}
+
+/* Typed Access, Every Call Takes An NvicIrq So Only Interrupts That Exist Can Be Named */
+pub struct Nvic {
+    nvic:       *mut NVICReg,
+    scb:        *mut SCBReg,
+    stir:       *mut u32        // Software Trigger Interrupt Register
+}
+
+/* Split Of The Priority Bits Between Preemption Priority And Sub-Priority (AIRCR PRIGROUP) */
+/* Only The Upper PRIO_BITS Of Each Priority Are Implemented, So Groups Below Preempt3Sub0 Behave Like It */
+#[derive(Clone, Copy, PartialEq)]
+pub enum PriorityGroup {
+    Preempt3Sub0 = 4,
+    Preempt2Sub1 = 5,
+    Preempt1Sub2 = 6,
+    Preempt0Sub3 = 7
+}
+
+/* Priority Bits Implemented On The STM32L5, Priorities Run 0 (Highest) To 7 */
+pub const PRIO_BITS:        u32 = 3;
+pub const PRIO_LOWEST:      u8 = (1 << PRIO_BITS) - 1;
+
+const STIR_OFFSET:          u32 = 0xE00;    // From The ISER Base
+
+/* Application Interrupt And Reset Control Register */
+const AIRCR_VECTKEY:        u32 = 0x05FA << 16;
+const AIRCR_VECTKEY_MASK:   u32 = 0xFFFF << 16;
+const AIRCR_PRIGROUP_SHIFT: u32 = 8;
+const AIRCR_PRIGROUP:       u32 = 0x7 << AIRCR_PRIGROUP_SHIFT;
+
+impl Nvic {
+    pub fn init(base: u32, scb_base: u32) -> Nvic {
+        return Nvic {
+            nvic:       base as *mut NVICReg,
+            scb:        scb_base as *mut SCBReg,
+            stir:       (base + STIR_OFFSET) as *mut u32
+        };
+    }
+
+    pub fn enable(&self, irq: NvicIrq) {
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*self.nvic).iser[index(irq)]), mask(irq)) };
+    }
+
+    /* Barriers So No Interrupt Arrives From The Source Once This Returns */
+    pub fn disable(&self, irq: NvicIrq) {
+        unsafe {
+            ptr::write_volatile(ptr::addr_of_mut!((*self.nvic).icer[index(irq)]), mask(irq));
+            core::arch::asm!("dsb", "isb");
+        }
+    }
+
+    pub fn get_enabled(&self, irq: NvicIrq) -> bool {
+        return (unsafe { ptr::read_volatile(ptr::addr_of!((*self.nvic).iser[index(irq)])) } & mask(irq)) != 0;
+    }
+
+    pub fn set_pending(&self, irq: NvicIrq) {
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*self.nvic).ispr[index(irq)]), mask(irq)) };
+    }
+
+    pub fn clr_pending(&self, irq: NvicIrq) {
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*self.nvic).icpr[index(irq)]), mask(irq)) };
+    }
+
+    pub fn get_pending(&self, irq: NvicIrq) -> bool {
+        return (unsafe { ptr::read_volatile(ptr::addr_of!((*self.nvic).ispr[index(irq)])) } & mask(irq)) != 0;
+    }
+
+    /* Handler Running Or Preempted */
+    pub fn get_active(&self, irq: NvicIrq) -> bool {
+        return (unsafe { ptr::read_volatile(ptr::addr_of!((*self.nvic).iabr[index(irq)])) } & mask(irq)) != 0;
+    }
+
+    /* Priority 0 (Highest) To PRIO_LOWEST, Larger Values Are Capped */
+    pub fn set_priority(&self, irq: NvicIrq, priority: u8) {
+        let priority = core::cmp::min(priority, PRIO_LOWEST) << (8 - PRIO_BITS);
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*self.nvic).ipr[irq as usize]), priority) };
+    }
+
+    pub fn get_priority(&self, irq: NvicIrq) -> u8 {
+        return unsafe { ptr::read_volatile(ptr::addr_of!((*self.nvic).ipr[irq as usize])) } >> (8 - PRIO_BITS);
+    }
+
+    /* Bits Really Implemented In The Priority Registers, Found By Writing All Ones And Reading Back */
+    pub fn get_priority_bits(&self, irq: NvicIrq) -> u32 {
+        unsafe {
+            let ipr = ptr::addr_of_mut!((*self.nvic).ipr[irq as usize]);
+            let saved = ptr::read_volatile(ipr);
+            ptr::write_volatile(ipr, 0xFF);
+            let bits = ptr::read_volatile(ipr).count_ones();
+            ptr::write_volatile(ipr, saved);
+            return bits;
+        }
+    }
+
+    pub fn set_grouping(&self, group: PriorityGroup) {
+        unsafe {
+            let aircr = ptr::read_volatile(ptr::addr_of!((*self.scb).aircr)) & !(AIRCR_VECTKEY_MASK | AIRCR_PRIGROUP);
+            let aircr = aircr | AIRCR_VECTKEY | ((group as u32) << AIRCR_PRIGROUP_SHIFT);
+            ptr::write_volatile(ptr::addr_of_mut!((*self.scb).aircr), aircr);
+        }
+    }
+
+    pub fn get_grouping(&self) -> PriorityGroup {
+        let prigroup = (unsafe { ptr::read_volatile(ptr::addr_of!((*self.scb).aircr)) } & AIRCR_PRIGROUP) >> AIRCR_PRIGROUP_SHIFT;
+        return match prigroup {
+            5 => PriorityGroup::Preempt2Sub1,
+            6 => PriorityGroup::Preempt1Sub2,
+            7 => PriorityGroup::Preempt0Sub3,
+            _ => PriorityGroup::Preempt3Sub0
+        };
+    }
+
+    /* Priority For set_priority From A Preemption Priority And Sub-Priority Under The Current Grouping */
+    pub fn encode_priority(&self, preempt: u8, sub: u8) -> u8 {
+        return encode_priority(self.get_grouping(), preempt, sub);
+    }
+
+    /* Pend An Interrupt From Software, Also Allowed From Unprivileged Code When CCR.USERSETMPEND Is Set */
+    pub fn trigger(&self, irq: NvicIrq) {
+        unsafe { ptr::write_volatile(self.stir, irq as u32) };
+    }
+
+    /* Deliver An Interrupt To The Non-Secure World, Only Writable From The Secure World */
+    pub fn set_target_ns(&self, irq: NvicIrq, non_secure: bool) {
+        unsafe {
+            let itns = ptr::addr_of_mut!((*self.nvic).itns[index(irq)]);
+            if non_secure {
+                ptr::write_volatile(itns, ptr::read_volatile(itns) | mask(irq));
+            } else {
+                ptr::write_volatile(itns, ptr::read_volatile(itns) & !mask(irq));
+            }
+        }
+    }
+
+    pub fn get_target_ns(&self, irq: NvicIrq) -> bool {
+        return (unsafe { ptr::read_volatile(ptr::addr_of!((*self.nvic).itns[index(irq)])) } & mask(irq)) != 0;
+    }
+}
+
+/* Preemption Priority In The Upper Bits, Sub-Priority Below, Each Capped To The Bits It Has */
+pub fn encode_priority(group: PriorityGroup, preempt: u8, sub: u8) -> u8 {
+    let sub_bits = (group as u32) - (PriorityGroup::Preempt3Sub0 as u32);
+    let preempt_bits = PRIO_BITS - sub_bits;
+    let preempt = core::cmp::min(preempt as u32, (1 << preempt_bits) - 1);
+    let sub = core::cmp::min(sub as u32, (1 << sub_bits) - 1);
+    return ((preempt << sub_bits) | sub) as u8;
+}
+
+fn index(irq: NvicIrq) -> usize {
+    return (irq as usize) / 32;
+}
+
+fn mask(irq: NvicIrq) -> u32 {
+    return 1 << ((irq as u32) % 32);
+}
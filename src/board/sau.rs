@@ -0,0 +1,14 @@
+// SAU Registers (Programming Manual) - is on 218
+
+/* Security Attribution Unit (SAU) */
+
+#[repr(C)]
+pub struct SAUReg {
+	pub ctrl:		u32,			/* SAU Control Register */
+	pub typer:		u32,			/* SAU Type Register */
+	pub rnr:		u32,			/* SAU Region Number Register */
+	pub rbar:		u32,			/* SAU Region Base Address Register */
+	pub rlar:		u32,			/* SAU Region Limit Address Register */
+	pub sfsr:		u32,			/* Secure Fault Status Register */
+	pub sfar:		u32,			/* Secure Fault Address Register */
+}
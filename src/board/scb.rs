@@ -0,0 +1,32 @@
+// SCB Registers (Programming Manual) - is on 192
+
+/* System Control Block (SCB) */
+
+#[repr(C)]
+pub struct SCBReg {
+	pub cpuid:		u32,			/* CPUID Base Register */
+	pub icsr:		u32,			/* Interrupt Control and State Register */
+	pub vtor:		u32,			/* Vector Table Offset Register */
+	pub aircr:		u32,			/* Application Interrupt and Reset Control Register */
+	pub scr:		u32,			/* System Control Register */
+	pub ccr:		u32,			/* Configuration and Control Register */
+	pub shpr:		[u8; 12],		/* System Handler Priority Registers */
+	pub shcsr:		u32,			/* System Handler Control and State Register */
+	pub cfsr:		u32,			/* Configurable Fault Status Register */
+	pub hfsr:		u32,			/* HardFault Status Register */
+	pub dfsr:		u32,			/* Debug Fault Status Register */
+	pub mmfar:		u32,			/* MemManage Fault Address Register */
+	pub bfar:		u32,			/* BusFault Address Register */
+	pub afsr:		u32,			/* Auxiliary Fault Status Register */
+	pub id_pfr:		[u32; 2],		/* Processor Feature Registers */
+	pub id_dfr:		u32,			/* Debug Feature Register */
+	pub id_afr:		u32,			/* Auxiliary Feature Register */
+	pub id_mmfr:	[u32; 4],		/* Memory Model Feature Registers */
+	pub id_isar:	[u32; 6],		/* Instruction Set Attributes Registers */
+	pub clidr:		u32,			/* Cache Level ID Register */
+	pub ctr:		u32,			/* Cache Type Register */
+	pub ccsidr:		u32,			/* Cache Size ID Register */
+	pub csselr:		u32,			/* Cache Size Selection Register */
+	pub cpacr:		u32,			/* Coprocessor Access Control Register */
+	pub nsacr:		u32,			/* Non-secure Access Control Register */
+}
@@ -0,0 +1,11 @@
+// SysTick Registers (Programming Manual) - is on 183
+
+/* System Timer (SysTick) */
+
+#[repr(C)]
+pub struct SysTickReg {
+	pub ctrl:		u32,			/* SysTick Control and Status Register */
+	pub load:		u32,			/* SysTick Reload Value Register */
+	pub val:		u32,			/* SysTick Current Value Register */
+	pub calib:		u32,			/* SysTick Calibration Value Register */
+}
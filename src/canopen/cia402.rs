@@ -0,0 +1,233 @@
+/* CiA 402 Drive Profile, Profile Position Mode */
+/* Each Axis Has Its Own Controlword/Statusword Objects, Axis n At n x 0x800 Above The Base Index */
+/* The Drive State Machine Runs On Top Of The Motor's State, A Faulted Motor Is Always In Fault Here */
+/* Positions, Velocities And Accelerations Are In Steps (Increments) */
+
+use crate::axis::MotorState;
+use crate::axis::state::Fault;
+use super::od::{self, Abort, Access, Entry, Kind};
+
+/* Objects Of Axis 0 */
+pub const AXIS_OFFSET:      u16 = 0x800;
+pub const ERROR_CODE:       u16 = 0x603F;
+pub const CONTROLWORD:      u16 = 0x6040;
+pub const STATUSWORD:       u16 = 0x6041;
+pub const MODES:            u16 = 0x6060;
+pub const MODES_DISPLAY:    u16 = 0x6061;
+pub const POSITION_ACTUAL:  u16 = 0x6064;
+pub const TARGET_POSITION:  u16 = 0x607A;
+pub const PROFILE_VELOCITY: u16 = 0x6081;
+pub const PROFILE_ACCEL:    u16 = 0x6083;
+
+/* Modes Of Operation, Only Profile Position Is Handled */
+pub const MODE_PROFILE_POSITION: u32 = 1;
+
+pub const OBJECTS:          [Entry; 9] = [
+    od::entry(ERROR_CODE, 0, Kind::U16, Access::Ro, true, 0),
+    od::entry(CONTROLWORD, 0, Kind::U16, Access::Rw, true, 0),
+    od::entry(STATUSWORD, 0, Kind::U16, Access::Ro, true, 0),
+    od::entry(MODES, 0, Kind::I8, Access::Rw, true, MODE_PROFILE_POSITION),
+    od::entry(MODES_DISPLAY, 0, Kind::I8, Access::Ro, true, MODE_PROFILE_POSITION),
+    od::entry(POSITION_ACTUAL, 0, Kind::I32, Access::Ro, true, 0),
+    od::entry(TARGET_POSITION, 0, Kind::I32, Access::Rw, true, 0),
+    od::entry(PROFILE_VELOCITY, 0, Kind::U32, Access::Rw, true, 1000),
+    od::entry(PROFILE_ACCEL, 0, Kind::U32, Access::Rw, true, 10000)
+];
+
+/* Controlword */
+const CW_SWITCH_ON:         u16 = 1 << 0;
+const CW_ENABLE_VOLTAGE:    u16 = 1 << 1;
+const CW_QUICK_STOP:        u16 = 1 << 2;   // Active Low
+const CW_ENABLE_OPERATION:  u16 = 1 << 3;
+const CW_NEW_SETPOINT:      u16 = 1 << 4;
+const CW_RELATIVE:          u16 = 1 << 6;
+const CW_FAULT_RESET:       u16 = 1 << 7;
+
+/* Statusword */
+const SW_READY:             u16 = 1 << 0;   // Ready To Switch On
+const SW_SWITCHED_ON:       u16 = 1 << 1;
+const SW_OPERATION:         u16 = 1 << 2;   // Operation Enabled
+const SW_FAULT:             u16 = 1 << 3;
+const SW_VOLTAGE:           u16 = 1 << 4;   // Voltage Enabled
+const SW_QUICK_STOP:        u16 = 1 << 5;   // Active Low
+const SW_DISABLED:          u16 = 1 << 6;   // Switch On Disabled
+const SW_REMOTE:            u16 = 1 << 9;
+const SW_TARGET_REACHED:    u16 = 1 << 10;
+const SW_SETPOINT_ACK:      u16 = 1 << 12;
+const SW_FOLLOWING_ERROR:   u16 = 1 << 13;
+
+/* What The Node Does For A Controlword, Several Can Come Together */
+pub const ACT_ENABLE:       u32 = 1 << 0;   // Enable The Driver
+pub const ACT_DISABLE:      u32 = 1 << 1;   // Disable The Driver, Stopping Any Move
+pub const ACT_QUICK_STOP:   u32 = 1 << 2;   // Stop Any Move, Driver Stays Enabled
+pub const ACT_FAULT_RESET:  u32 = 1 << 3;   // Acknowledge The Motor's Fault
+pub const ACT_SETPOINT:     u32 = 1 << 4;   // Move To TARGET_POSITION
+pub const ACT_RELATIVE:     u32 = 1 << 5;   // With ACT_SETPOINT, Target Is Relative
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DriveState {
+    NotReady,
+    SwitchOnDisabled,
+    ReadyToSwitchOn,
+    SwitchedOn,
+    OperationEnabled,
+    QuickStopActive,
+    Fault
+}
+
+/* Device Control Commands Of The Controlword */
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Shutdown,
+    SwitchOn,
+    EnableOperation,
+    DisableVoltage,
+    QuickStop
+}
+
+#[derive(Clone, Copy)]
+pub struct Drive {
+    state:          DriveState,
+    controlword:    u16                     // Last One Acted On
+}
+
+impl Drive {
+    pub const fn init() -> Drive {
+        return Drive {
+            state:          DriveState::NotReady,
+            controlword:    0
+        };
+    }
+
+    pub fn get_state(&self) -> DriveState {
+        return self.state;
+    }
+
+    pub fn get_controlword(&self) -> u16 {
+        return self.controlword;
+    }
+
+    /* Follow The Motor, Into Fault When It Faults And Out When Its Fault Is Acknowledged Elsewhere */
+    pub fn update(&mut self, motor: MotorState) -> u32 {
+        match motor {
+            MotorState::Faulted(_) => {
+                let powered = is_powered(self.state);
+                self.state = DriveState::Fault;
+                return if powered { ACT_DISABLE } else { 0 };
+            } MotorState::BootUp => {
+            } _ => {
+                if self.state == DriveState::Fault || self.state == DriveState::NotReady {
+                    self.state = DriveState::SwitchOnDisabled;
+                }
+            }
+        }
+        return 0;
+    }
+
+    /* A Controlword Written By SDO Or RPDO, Returns The ACT_ Flags For The Node */
+    pub fn control(&mut self, word: u16) -> u32 {
+        let last = self.controlword;
+        self.controlword = word;
+        let rising = word & !last;
+
+        if self.state == DriveState::Fault || self.state == DriveState::NotReady {
+            return if (rising & CW_FAULT_RESET) != 0 && self.state == DriveState::Fault { ACT_FAULT_RESET } else { 0 };
+        }
+
+        let before = self.state;
+        self.state = match (self.state, decode(word)) {
+            (DriveState::SwitchOnDisabled, Command::Shutdown) => DriveState::ReadyToSwitchOn,
+            (DriveState::ReadyToSwitchOn, Command::SwitchOn) => DriveState::SwitchedOn,
+            (DriveState::ReadyToSwitchOn, Command::EnableOperation) => DriveState::OperationEnabled,
+            (DriveState::SwitchedOn, Command::EnableOperation) => DriveState::OperationEnabled,
+            (DriveState::SwitchedOn, Command::Shutdown) => DriveState::ReadyToSwitchOn,
+            (DriveState::OperationEnabled, Command::SwitchOn) => DriveState::SwitchedOn,
+            (DriveState::OperationEnabled, Command::Shutdown) => DriveState::ReadyToSwitchOn,
+            (DriveState::OperationEnabled, Command::QuickStop) => DriveState::QuickStopActive,
+            (DriveState::QuickStopActive, Command::EnableOperation) => DriveState::OperationEnabled,
+            (DriveState::QuickStopActive, Command::QuickStop) => DriveState::QuickStopActive,
+            (_, Command::DisableVoltage) | (_, Command::QuickStop) => DriveState::SwitchOnDisabled,
+            (state, _) => state
+        };
+
+        /* The Driver Stays Powered Through A Quick Stop */
+        let mut actions = 0;
+        if !is_powered(before) && is_powered(self.state) {
+            actions |= ACT_ENABLE;
+        } else if is_powered(before) && !is_powered(self.state) {
+            actions |= ACT_DISABLE;
+        }
+        if before == DriveState::OperationEnabled && self.state == DriveState::QuickStopActive {
+            actions |= ACT_QUICK_STOP;
+        }
+        if self.state == DriveState::OperationEnabled && (rising & CW_NEW_SETPOINT) != 0 {
+            actions |= ACT_SETPOINT;
+            if (word & CW_RELATIVE) != 0 {
+                actions |= ACT_RELATIVE;
+            }
+        }
+        return actions;
+    }
+
+    /* Statusword For The Current State, moving While A Move Is Under Way */
+    pub fn get_statusword(&self, motor: MotorState, moving: bool) -> u16 {
+        let mut word = SW_REMOTE | match self.state {
+            DriveState::NotReady => 0,
+            DriveState::SwitchOnDisabled => SW_DISABLED,
+            DriveState::ReadyToSwitchOn => SW_QUICK_STOP | SW_READY,
+            DriveState::SwitchedOn => SW_QUICK_STOP | SW_VOLTAGE | SW_SWITCHED_ON | SW_READY,
+            DriveState::OperationEnabled => SW_QUICK_STOP | SW_VOLTAGE | SW_OPERATION | SW_SWITCHED_ON | SW_READY,
+            DriveState::QuickStopActive => SW_VOLTAGE | SW_OPERATION | SW_SWITCHED_ON | SW_READY,
+            DriveState::Fault => SW_FAULT
+        };
+
+        if !moving {
+            word |= SW_TARGET_REACHED;
+        }
+        if (self.controlword & CW_NEW_SETPOINT) != 0 && self.state == DriveState::OperationEnabled {
+            word |= SW_SETPOINT_ACK;
+        }
+        if motor == MotorState::Faulted(Fault::FollowingError) {
+            word |= SW_FOLLOWING_ERROR;
+        }
+        return word;
+    }
+}
+
+/* A Write From The Bus To A Drive Object, Only Profile Position Mode Can Be Chosen */
+pub fn check(index: u16, value: u32) -> Result<(), Abort> {
+    if index >= MODES && (index - MODES).is_multiple_of(AXIS_OFFSET) && value != MODE_PROFILE_POSITION {
+        return Err(Abort::Range);
+    }
+    return Ok(());
+}
+
+fn is_powered(state: DriveState) -> bool {
+    return state == DriveState::OperationEnabled || state == DriveState::QuickStopActive;
+}
+
+/* Device Control Command, Fault Reset Is Taken Separately On Its Edge */
+fn decode(word: u16) -> Command {
+    if (word & CW_ENABLE_VOLTAGE) == 0 {
+        return Command::DisableVoltage;
+    } else if (word & CW_QUICK_STOP) == 0 {
+        return Command::QuickStop;
+    } else if (word & CW_SWITCH_ON) == 0 {
+        return Command::Shutdown;
+    } else if (word & CW_ENABLE_OPERATION) == 0 {
+        return Command::SwitchOn;
+    }
+    return Command::EnableOperation;
+}
+
+/* EMCY And 0x603F Error Code Of A Motor Fault (CiA 301 And 402) */
+pub fn get_error_code(fault: Fault) -> u16 {
+    return match fault {
+        Fault::LimitHit => 0x8612,          // Reference Limit
+        Fault::FollowingError => 0x8611,    // Following Error
+        Fault::Driver => 0x5400,            // Power Section
+        Fault::Watchdog => 0x6010,          // Software Reset (Watchdog)
+        Fault::EStop => 0x9000,             // External Error
+        Fault::Homing(_) => 0x8600          // Positioning Controller
+    };
+}
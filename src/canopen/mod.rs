@@ -0,0 +1,725 @@
+/* CANopen Device (CiA 301) With A CiA 402 Drive For Each Axis */
+/* The Node Only Turns Frames Into Frames And Events, Frames Received From The Bus Go To receive, Those To Send */
+/* Come Out Of take_frame, And What The Motion Side Has To Do (Moves, Enabling Drivers, Stops) Out Of take_event */
+/* NMT States Follow The Motors, Starting The Node Readies Every Motor, Pre-Operational Holds Them Back */
+/* SDOs Work In Pre-Operational And Operational, PDOs Only In Operational, Stopped Leaves NMT And Heartbeat */
+
+use crate::axis::{MotorControl, MotorState, Motors, MOTORS};
+use crate::axis::interpolate::AXES;
+use crate::axis::state::Fault;
+use crate::driver::fdcan::Frame;
+use od::{Abort, Dictionary, PDOS};
+
+/* Public Modules */
+pub mod od;
+pub mod sdo;
+pub mod pdo;
+pub mod cia402;
+
+/* Function Codes, The Node ID Is Added To All But NMT */
+const COB_NMT:              u16 = 0x000;
+const COB_SDO_TX:           u16 = 0x580;
+const COB_SDO_RX:           u16 = 0x600;
+const COB_HEARTBEAT:        u16 = 0x700;
+const COB_ID:               u32 = 0x7FF;
+
+/* NMT Commands, Node 0 Addresses Every Node */
+const NMT_START:            u8 = 0x01;
+const NMT_STOP:             u8 = 0x02;
+const NMT_PRE_OPERATIONAL:  u8 = 0x80;
+const NMT_RESET_NODE:       u8 = 0x81;
+const NMT_RESET_COMM:       u8 = 0x82;
+const NMT_ALL:              u8 = 0;
+
+/* Error Register (0x1001) */
+const ERR_GENERIC:          u8 = 1 << 0;
+const ERR_COMMUNICATION:    u8 = 1 << 4;
+
+/* EMCY Error Codes Besides The Motor Faults */
+const EMCY_RESET:           u16 = 0x0000;   // Error Reset Or No Error
+const EMCY_PDO_LENGTH:      u16 = 0x8210;   // PDO Not Processed Due To Length Error
+
+/* Frames And Events Waiting To Be Taken */
+const FRAMES:               usize = 16;
+const EVENTS:               usize = 8;
+
+/* Network Management State, Sent In The Heartbeat */
+#[derive(Clone, Copy, PartialEq)]
+pub enum NmtState {
+    BootUp,
+    PreOperational,
+    Operational,
+    Stopped
+}
+
+/* What The Motion Side Has To Do For The Bus */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event {
+    Move { motor: Motors, delta: i32, velocity: u32, accel: u32 },  // Steps, Steps/s, Steps/s^2
+    Enable(Motors),                                                 // Enable The Driver
+    Disable(Motors),                                                // Stop Any Move, Disable The Driver
+    QuickStop(Motors),                                              // Stop Any Move, Driver Stays Enabled
+    ResetNode                                                       // The Application Resets
+}
+
+/* Fixed Size FIFO, The Node Owns Its Own So Nothing Is Shared */
+struct Ring<T: Copy, const N: usize> {
+    items:          [Option<T>; N],
+    head:           usize,                  // Oldest Item
+    len:            usize
+}
+
+pub struct Node {
+    id:             u8,
+    nmt:            NmtState,
+    od:             Dictionary,
+    sdo:            sdo::SdoServer,
+    drives:         [cia402::Drive; AXES],
+    tpdos:          [pdo::Tpdo; PDOS],
+    rpdos:          [Option<Frame>; PDOS],  // Synchronous RPDOs Waiting For The Next SYNC
+    heartbeat:      u32,                    // ms Since The Last Heartbeat
+    faults:         [Option<Fault>; AXES],  // Faults Last Reported By EMCY
+    pdo_error:      bool,                   // Last RPDO Was Too Short
+    frames:         Ring<Frame, FRAMES>,
+    events:         Ring<Event, EVENTS>
+}
+
+impl<T: Copy, const N: usize> Ring<T, N> {
+    const fn init() -> Ring<T, N> {
+        return Ring {
+            items:      [None; N],
+            head:       0,
+            len:        0
+        };
+    }
+
+    /* False When Full, item Is Dropped */
+    fn push(&mut self, item: T) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.items[(self.head + self.len) % N] = Some(item);
+        self.len += 1;
+        return true;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let item = self.items[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        return item;
+    }
+}
+
+impl NmtState {
+    /* State Byte Of The Heartbeat */
+    pub fn get_code(&self) -> u8 {
+        return match self {
+            NmtState::BootUp => 0x00,
+            NmtState::PreOperational => 0x7F,
+            NmtState::Operational => 0x05,
+            NmtState::Stopped => 0x04
+        };
+    }
+}
+
+impl Node {
+    /* Node id (1 - 127), identity Is Vendor ID, Product Code, Revision And Serial Number */
+    pub fn init(id: u8, identity: [u32; 4]) -> Node {
+        return Node {
+            id,
+            nmt:        NmtState::BootUp,
+            od:         Dictionary::init(id, identity),
+            sdo:        sdo::SdoServer::init(),
+            drives:     [cia402::Drive::init(); AXES],
+            tpdos:      [pdo::Tpdo::init(); PDOS],
+            rpdos:      [None; PDOS],
+            heartbeat:  0,
+            faults:     [None; AXES],
+            pdo_error:  false,
+            frames:     Ring::init(),
+            events:     Ring::init()
+        };
+    }
+
+    /* Once The Bus Is Open, Motors Still Booting Become Pre-Operational And The Node Says So */
+    pub fn start(&mut self, control: &mut MotorControl) {
+        for &motor in MOTORS.iter() {
+            if control.get_motor_state(motor) == MotorState::BootUp {
+                control.set_motor_state(motor, MotorState::PreOperational).ok();
+            }
+        }
+        self.boot();
+        self.update(control);
+    }
+
+    pub fn get_nmt(&self) -> NmtState {
+        return self.nmt;
+    }
+
+    pub fn get_od(&self) -> &Dictionary {
+        return &self.od;
+    }
+
+    pub fn get_od_mut(&mut self) -> &mut Dictionary {
+        return &mut self.od;
+    }
+
+    /* Next Frame To Send */
+    pub fn take_frame(&mut self) -> Option<Frame> {
+        return self.frames.pop();
+    }
+
+    /* Next Thing For The Motion Side To Do */
+    pub fn take_event(&mut self) -> Option<Event> {
+        return self.events.pop();
+    }
+
+    /* A Frame From The Bus */
+    pub fn receive(&mut self, frame: &Frame, control: &mut MotorControl) {
+        if frame.rtr || self.nmt == NmtState::BootUp {
+            return;
+        }
+        let id = self.id as u16;
+
+        if frame.id == COB_NMT {
+            self.nmt_command(frame.get_data(), control);
+        } else if self.nmt == NmtState::Stopped {
+            return;
+        } else if frame.id == COB_SDO_RX + id {
+            if let Some(response) = self.sdo.request(frame.get_data(), &mut self.od, check) {
+                self.send(COB_SDO_TX + id, &response.data);
+                if let Some((index, _)) = response.written {
+                    self.written(index);
+                    self.update(control);
+                }
+            }
+        } else if frame.id as u32 == (self.od.get(od::SYNC_COB_ID, 0) & COB_ID) {
+            self.sync(control);
+        } else if self.nmt == NmtState::Operational {
+            for pdo in 0..PDOS {
+                let comm = od::RPDO_COMM + pdo as u16;
+                if pdo::get_cob_id(&self.od, comm) != Some(frame.id) {
+                    continue;
+                }
+                if pdo::is_sync(pdo::get_type(&self.od, comm)) {
+                    self.rpdos[pdo] = Some(*frame);
+                } else {
+                    self.apply(pdo, frame);
+                    self.update(control);
+                }
+            }
+        }
+    }
+
+    /* Time Passes, ms Since The Last Call, Also Polls The Motors */
+    pub fn tick(&mut self, ms: u32, control: &mut MotorControl) {
+        if self.nmt == NmtState::BootUp {
+            return;
+        }
+        if let Some(abort) = self.sdo.tick(ms) {
+            self.send(COB_SDO_TX + self.id as u16, &abort);
+        }
+        self.update(control);
+
+        /* Event Driven TPDOs */
+        if self.nmt == NmtState::Operational {
+            for pdo in 0..PDOS {
+                self.tpdos[pdo].tick(ms);
+                let comm = od::TPDO_COMM + pdo as u16;
+                if pdo::is_sync(pdo::get_type(&self.od, comm)) {
+                    continue;
+                }
+                if let Some(cob_id) = pdo::get_cob_id(&self.od, comm) {
+                    let (len, data) = pdo::Mapping::init(&self.od, od::TPDO_MAP + pdo as u16).pack(&self.od);
+                    let (inhibit, event_timer) = pdo::get_timing(&self.od, comm);
+                    if self.tpdos[pdo].is_event(len, &data, event_timer) {
+                        self.send(cob_id, &data[..len]);
+                        self.tpdos[pdo].sent(len, &data, inhibit);
+                    }
+                }
+            }
+        }
+
+        let period = self.od.get(od::HEARTBEAT_TIME, 0);
+        self.heartbeat = self.heartbeat.saturating_add(ms);
+        if period != 0 && self.heartbeat >= period {
+            self.heartbeat = 0;
+            self.send(COB_HEARTBEAT + self.id as u16, &[self.nmt.get_code()]);
+        }
+    }
+
+    /* Bring The Drive Objects Up To Date With The Motors And Act On A New Controlword */
+    pub fn update(&mut self, control: &mut MotorControl) {
+        let before = self.od.get(od::ERROR_REGISTER, 0);
+        for (axis, &motor) in MOTORS.iter().enumerate() {
+            let base = cia402::AXIS_OFFSET * axis as u16;
+            let mut actions = self.drives[axis].update(control.get_motor_state(motor));
+            let word = self.od.get(cia402::CONTROLWORD + base, 0) as u16;
+            if word != self.drives[axis].get_controlword() {
+                actions |= self.drives[axis].control(word);
+            }
+            self.act(axis, actions, control);
+
+            let state = control.get_motor_state(motor);
+            let moving = state == MotorState::Operational || state == MotorState::Homing;
+            let fault = control.get_motor_fault(motor);
+            self.od.set(cia402::STATUSWORD + base, 0, self.drives[axis].get_statusword(state, moving) as u32);
+            self.od.set(cia402::POSITION_ACTUAL + base, 0, control.get_motor_position(motor) as u32);
+            self.od.set(cia402::MODES_DISPLAY + base, 0, self.od.get(cia402::MODES + base, 0));
+            self.od.set(cia402::ERROR_CODE + base, 0, fault.map_or(0, cia402::get_error_code) as u32);
+
+            /* Each New Fault Is Reported Once */
+            if fault != self.faults[axis] {
+                self.faults[axis] = fault;
+                self.od.set(od::ERROR_REGISTER, 0, self.get_error_register() as u32);
+                if let Some(fault) = fault {
+                    self.emcy(cia402::get_error_code(fault), axis as u8);
+                }
+            }
+        }
+
+        /* Once Nothing Is Wrong Any More, Say So */
+        let after = self.get_error_register();
+        self.od.set(od::ERROR_REGISTER, 0, after as u32);
+        if before != 0 && after == 0 {
+            self.emcy(EMCY_RESET, 0);
+        }
+    }
+
+    /* What A Drive's Controlword Asked For */
+    fn act(&mut self, axis: usize, actions: u32, control: &mut MotorControl) {
+        let motor = MOTORS[axis];
+        let base = cia402::AXIS_OFFSET * axis as u16;
+        if (actions & cia402::ACT_DISABLE) != 0 {
+            self.events.push(Event::Disable(motor));
+        }
+        if (actions & cia402::ACT_ENABLE) != 0 {
+            self.events.push(Event::Enable(motor));
+        }
+        if (actions & cia402::ACT_QUICK_STOP) != 0 {
+            self.events.push(Event::QuickStop(motor));
+        }
+        if (actions & cia402::ACT_FAULT_RESET) != 0 && control.ack_fault(motor).is_ok() {
+            self.drives[axis].update(control.get_motor_state(motor));
+        }
+        if (actions & cia402::ACT_SETPOINT) != 0 {
+            let target = self.od.get(cia402::TARGET_POSITION + base, 0) as i32;
+            let delta = if (actions & cia402::ACT_RELATIVE) != 0 {
+                target
+            } else {
+                target.wrapping_sub(control.get_motor_position(motor))
+            };
+            self.events.push(Event::Move {
+                motor,
+                delta,
+                velocity:   self.od.get(cia402::PROFILE_VELOCITY + base, 0),
+                accel:      self.od.get(cia402::PROFILE_ACCEL + base, 0)
+            });
+        }
+    }
+
+    /* Error Register From The Motor Faults And The Last RPDO */
+    fn get_error_register(&self) -> u8 {
+        let mut register = 0;
+        if self.faults.iter().any(|fault| fault.is_some()) {
+            register |= ERR_GENERIC;
+        }
+        if self.pdo_error {
+            register |= ERR_GENERIC | ERR_COMMUNICATION;
+        }
+        return register;
+    }
+
+    fn nmt_command(&mut self, data: &[u8], control: &mut MotorControl) {
+        if data.len() != 2 || (data[1] != self.id && data[1] != NMT_ALL) {
+            return;
+        }
+
+        match data[0] {
+            NMT_START => {
+                for &motor in MOTORS.iter() {
+                    if control.get_motor_state(motor) == MotorState::PreOperational {
+                        control.set_motor_state(motor, MotorState::Stopped).ok();
+                    }
+                }
+                self.set_nmt(NmtState::Operational);
+            } NMT_STOP => {
+                for &motor in MOTORS.iter() {
+                    let state = control.get_motor_state(motor);
+                    if state == MotorState::Operational || state == MotorState::Homing {
+                        self.events.push(Event::QuickStop(motor));
+                    }
+                }
+                self.set_nmt(NmtState::Stopped);
+            } NMT_PRE_OPERATIONAL => {
+                for &motor in MOTORS.iter() {
+                    if control.get_motor_state(motor) == MotorState::Stopped {
+                        control.set_motor_state(motor, MotorState::PreOperational).ok();
+                    }
+                }
+                self.set_nmt(NmtState::PreOperational);
+            } NMT_RESET_NODE => {
+                self.od.reset(self.id, true);
+                self.drives = [cia402::Drive::init(); AXES];
+                self.events.push(Event::ResetNode);
+                self.boot();
+            } NMT_RESET_COMM => {
+                self.od.reset(self.id, false);
+                self.boot();
+            } _ => {
+            }
+        }
+        self.update(control);
+    }
+
+    /* Boot-Up Message, Then Pre-Operational With Nothing Left Of Earlier Transfers */
+    fn boot(&mut self) {
+        self.nmt = NmtState::BootUp;
+        self.send(COB_HEARTBEAT + self.id as u16, &[self.nmt.get_code()]);
+        self.sdo.reset();
+        self.heartbeat = 0;
+        self.set_nmt(NmtState::PreOperational);
+    }
+
+    /* PDOs Start Afresh Each Time The Node Enters Operational */
+    fn set_nmt(&mut self, nmt: NmtState) {
+        if nmt != NmtState::Operational {
+            self.tpdos = [pdo::Tpdo::init(); PDOS];
+            self.rpdos = [None; PDOS];
+        }
+        if nmt == NmtState::Stopped {
+            self.sdo.reset();
+        }
+        self.nmt = nmt;
+    }
+
+    /* Buffered RPDOs Take Effect, Then Synchronous TPDOs Go Out */
+    fn sync(&mut self, control: &mut MotorControl) {
+        if self.nmt != NmtState::Operational {
+            return;
+        }
+        for pdo in 0..PDOS {
+            if let Some(frame) = self.rpdos[pdo].take() {
+                self.apply(pdo, &frame);
+            }
+        }
+        self.update(control);
+
+        for pdo in 0..PDOS {
+            let comm = od::TPDO_COMM + pdo as u16;
+            let transmission = pdo::get_type(&self.od, comm);
+            if !pdo::is_sync(transmission) {
+                continue;
+            }
+            if let Some(cob_id) = pdo::get_cob_id(&self.od, comm) {
+                let (len, data) = pdo::Mapping::init(&self.od, od::TPDO_MAP + pdo as u16).pack(&self.od);
+                if self.tpdos[pdo].is_sync(transmission, len, &data) {
+                    self.send(cob_id, &data[..len]);
+                    self.tpdos[pdo].sent(len, &data, 0);
+                }
+            }
+        }
+    }
+
+    /* An RPDO Into The Dictionary, One Too Short Is Dropped With An EMCY */
+    fn apply(&mut self, pdo: usize, frame: &Frame) {
+        let mapping = pdo::Mapping::init(&self.od, od::RPDO_MAP + pdo as u16);
+        if mapping.unpack(&mut self.od, frame.get_data()) {
+            self.pdo_error = false;
+        } else if !self.pdo_error {
+            self.pdo_error = true;
+            self.od.set(od::ERROR_REGISTER, 0, self.get_error_register() as u32);
+            self.emcy(EMCY_PDO_LENGTH, 0);
+        }
+    }
+
+    /* A PDO's Communication Or Mapping Changed By SDO, It Starts Afresh */
+    fn written(&mut self, index: u16) {
+        for pdo in 0..PDOS {
+            let pdo_index = pdo as u16;
+            if index == od::TPDO_COMM + pdo_index || index == od::TPDO_MAP + pdo_index {
+                self.tpdos[pdo].reset();
+            } else if index == od::RPDO_COMM + pdo_index || index == od::RPDO_MAP + pdo_index {
+                self.rpdos[pdo] = None;
+            }
+        }
+    }
+
+    /* Emergency Message, Error Code, Error Register Then The Axis It Concerns */
+    fn emcy(&mut self, code: u16, axis: u8) {
+        let cob_id = self.od.get(od::EMCY_COB_ID, 0);
+        if (cob_id & pdo::COB_INVALID) != 0 || self.nmt == NmtState::Stopped {
+            return;
+        }
+        let code = code.to_le_bytes();
+        let register = self.od.get(od::ERROR_REGISTER, 0) as u8;
+        self.send((cob_id & COB_ID) as u16, &[code[0], code[1], register, axis, 0, 0, 0, 0]);
+    }
+
+    /* Frames That Do Not Fit Are Dropped, The Heartbeat Or Next Change Sends Again */
+    fn send(&mut self, id: u16, data: &[u8]) {
+        self.frames.push(Frame::init(id, data));
+    }
+}
+
+/* Every Write From The Bus Is Checked Against What The Node Can Do Before It Is Made */
+fn check(od: &Dictionary, index: u16, sub: u8, value: u32) -> Result<(), Abort> {
+    pdo::check(od, index, sub, value)?;
+    cia402::check(index, value)?;
+    if index == od::SYNC_COB_ID && (value & !COB_ID) != 0 {
+        return Err(Abort::Range);
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID:               u8 = 5;
+    const SYNC:             u16 = od::COB_SYNC as u16;
+    const EMCY:             u16 = od::COB_EMCY as u16 + ID as u16;
+    const TPDO1:            u16 = od::COB_TPDO as u16 + ID as u16;
+    const RPDO1:            u16 = od::COB_RPDO as u16 + ID as u16;
+    const HEARTBEAT:        u16 = COB_HEARTBEAT + ID as u16;
+
+    /* Controlwords Taking Axis 0 Up To Operation Enabled, Then A New Absolute Setpoint */
+    const SHUTDOWN:         u16 = 0x06;
+    const ENABLE:           u16 = 0x0F;
+    const SETPOINT:         u16 = 0x1F;
+
+    /* Node Started With Its Boot-Up Message Taken */
+    fn node(control: &mut MotorControl) -> Node {
+        let mut node = Node::init(ID, [1, 2, 3, 4]);
+        node.start(control);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x00])]);
+        return node;
+    }
+
+    fn frames(node: &mut Node) -> Vec<Frame> {
+        return core::iter::from_fn(|| node.take_frame()).collect();
+    }
+
+    fn events(node: &mut Node) -> Vec<Event> {
+        return core::iter::from_fn(|| node.take_event()).collect();
+    }
+
+    fn nmt(node: &mut Node, control: &mut MotorControl, command: u8, id: u8) {
+        node.receive(&Frame::init(COB_NMT, &[command, id]), control);
+    }
+
+    /* Expedited SDO Download, The Response Is Left Queued */
+    fn download(node: &mut Node, control: &mut MotorControl, index: u16, sub: u8, data: &[u8]) {
+        let mut request = [0x23 | (((4 - data.len()) as u8) << 2), index as u8, (index >> 8) as u8, sub, 0, 0, 0, 0];
+        request[4..4 + data.len()].copy_from_slice(data);
+        node.receive(&Frame::init(COB_SDO_RX + ID as u16, &request), control);
+    }
+
+    /* RPDO 1, Axis 0 Controlword And Target Position */
+    fn rpdo(node: &mut Node, control: &mut MotorControl, controlword: u16, target: i32) {
+        let (word, target) = (controlword.to_le_bytes(), target.to_le_bytes());
+        node.receive(&Frame::init(RPDO1, &[word[0], word[1], target[0], target[1], target[2], target[3]]), control);
+    }
+
+    /* TPDO 1, Axis 0 Statusword And Actual Position */
+    fn tpdo(status: u16, position: i32) -> Frame {
+        let (word, position) = (status.to_le_bytes(), position.to_le_bytes());
+        return Frame::init(TPDO1, &[word[0], word[1], position[0], position[1], position[2], position[3]]);
+    }
+
+    #[test]
+    fn ring_keeps_order_and_drops_when_full() {
+        let mut ring: Ring<u32, 3> = Ring::init();
+        assert!(ring.push(1) && ring.push(2) && ring.push(3));
+        assert!(!ring.push(4));
+        assert!(ring.pop() == Some(1));
+        assert!(ring.push(5));
+        assert!(ring.pop() == Some(2) && ring.pop() == Some(3) && ring.pop() == Some(5));
+        assert!(ring.pop().is_none());
+    }
+
+    #[test]
+    fn boot_up_then_heartbeat() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+        assert!(node.get_nmt() == NmtState::PreOperational);
+        assert!(MOTORS.iter().all(|&motor| control.get_motor_state(motor) == MotorState::PreOperational));
+
+        node.tick(999, &mut control);
+        assert!(frames(&mut node).is_empty());
+        node.tick(1, &mut control);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x7F])]);
+
+        /* 250 ms, Then Off */
+        download(&mut node, &mut control, od::HEARTBEAT_TIME, 0, &250u16.to_le_bytes());
+        assert!(frames(&mut node).len() == 1);
+        node.tick(250, &mut control);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x7F])]);
+        download(&mut node, &mut control, od::HEARTBEAT_TIME, 0, &0u16.to_le_bytes());
+        frames(&mut node);
+        node.tick(10000, &mut control);
+        assert!(frames(&mut node).is_empty());
+    }
+
+    #[test]
+    fn nmt_states_follow_the_commands() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+
+        /* Another Node's Command Is Ignored, Node 0 Means Every Node */
+        nmt(&mut node, &mut control, NMT_START, ID + 1);
+        assert!(node.get_nmt() == NmtState::PreOperational);
+        nmt(&mut node, &mut control, NMT_START, NMT_ALL);
+        assert!(node.get_nmt() == NmtState::Operational);
+        assert!(MOTORS.iter().all(|&motor| control.get_motor_state(motor) == MotorState::Stopped));
+        frames(&mut node);
+        node.tick(1000, &mut control);
+        assert!(frames(&mut node).last() == Some(&Frame::init(HEARTBEAT, &[0x05])));
+
+        /* Stop Quick Stops A Moving Motor, SDOs Go Unanswered, The Heartbeat Goes On */
+        assert!(control.set_motor_state(Motors::Motor2, MotorState::Operational).is_ok());
+        nmt(&mut node, &mut control, NMT_STOP, ID);
+        assert!(node.get_nmt() == NmtState::Stopped);
+        assert!(events(&mut node) == [Event::QuickStop(Motors::Motor2)]);
+        download(&mut node, &mut control, od::HEARTBEAT_TIME, 0, &500u16.to_le_bytes());
+        assert!(frames(&mut node).is_empty());
+        node.tick(1000, &mut control);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x04])]);
+
+        /* Back To Pre-Operational Takes The Stopped Motors With It */
+        assert!(control.set_motor_state(Motors::Motor2, MotorState::Stopped).is_ok());
+        nmt(&mut node, &mut control, NMT_PRE_OPERATIONAL, ID);
+        assert!(node.get_nmt() == NmtState::PreOperational);
+        assert!(MOTORS.iter().all(|&motor| control.get_motor_state(motor) == MotorState::PreOperational));
+    }
+
+    #[test]
+    fn resets_restore_the_dictionary() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+        let target = cia402::TARGET_POSITION;
+        download(&mut node, &mut control, od::HEARTBEAT_TIME, 0, &100u16.to_le_bytes());
+        download(&mut node, &mut control, target, 0, &1234i32.to_le_bytes());
+        frames(&mut node);
+
+        /* Communication Reset Keeps The Drive Objects */
+        nmt(&mut node, &mut control, NMT_RESET_COMM, ID);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x00])]);
+        assert!(node.get_nmt() == NmtState::PreOperational);
+        assert!(node.get_od().get(od::HEARTBEAT_TIME, 0) == 1000);
+        assert!(node.get_od().get(target, 0) == 1234);
+        assert!(events(&mut node).is_empty());
+
+        /* Node Reset Clears Them And Tells The Application */
+        nmt(&mut node, &mut control, NMT_RESET_NODE, ID);
+        assert!(frames(&mut node) == [Frame::init(HEARTBEAT, &[0x00])]);
+        assert!(node.get_od().get(target, 0) == 0);
+        assert!(events(&mut node) == [Event::ResetNode]);
+    }
+
+    #[test]
+    fn emcy_on_a_new_fault_and_on_reset() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+
+        control.set_motor_fault(Motors::Motor2, Fault::Driver);
+        node.tick(0, &mut control);
+        assert!(frames(&mut node) == [Frame::init(EMCY, &[0x00, 0x54, ERR_GENERIC, 1, 0, 0, 0, 0])]);
+        assert!(node.get_od().get(od::ERROR_REGISTER, 0) == ERR_GENERIC as u32);
+        assert!(node.get_od().get(cia402::ERROR_CODE + cia402::AXIS_OFFSET, 0) == 0x5400);
+
+        /* Reported Once */
+        node.tick(0, &mut control);
+        assert!(frames(&mut node).is_empty());
+
+        /* Fault Reset Through The Controlword's Rising Edge Acknowledges It */
+        download(&mut node, &mut control, cia402::CONTROLWORD + cia402::AXIS_OFFSET, 0, &0x80u16.to_le_bytes());
+        assert!(control.get_motor_state(Motors::Motor2) == MotorState::Stopped);
+        let sent = frames(&mut node);
+        assert!(sent.contains(&Frame::init(EMCY, &[0x00, 0x00, 0, 0, 0, 0, 0, 0])));
+        assert!(node.get_od().get(od::ERROR_REGISTER, 0) == 0);
+    }
+
+    #[test]
+    fn sync_sends_synchronous_tpdos_and_applies_rpdos() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+
+        /* TPDO 1 Every Second SYNC, RPDO 1 Held Until The Next SYNC */
+        download(&mut node, &mut control, od::TPDO_COMM, 2, &[2]);
+        download(&mut node, &mut control, od::RPDO_COMM, 2, &[0]);
+        nmt(&mut node, &mut control, NMT_START, ID);
+        frames(&mut node);
+
+        /* Before Operational A SYNC Does Nothing, Here The First Is Counted */
+        node.receive(&Frame::init(SYNC, &[]), &mut control);
+        assert!(frames(&mut node).is_empty());
+        control.set_motor_position(Motors::Motor1, -7);
+        node.receive(&Frame::init(SYNC, &[]), &mut control);
+        let status = node.get_od().get(cia402::STATUSWORD, 0) as u16;
+        assert!(frames(&mut node) == [tpdo(status, -7)]);
+
+        rpdo(&mut node, &mut control, SHUTDOWN, 0);
+        assert!(node.drives[0].get_state() == cia402::DriveState::SwitchOnDisabled);
+        node.receive(&Frame::init(SYNC, &[]), &mut control);
+        assert!(node.drives[0].get_state() == cia402::DriveState::ReadyToSwitchOn);
+    }
+
+    #[test]
+    fn event_tpdos_keep_the_inhibit_time() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+
+        /* 5 ms, In 100 us */
+        download(&mut node, &mut control, od::TPDO_COMM, 3, &50u16.to_le_bytes());
+        nmt(&mut node, &mut control, NMT_START, ID);
+        frames(&mut node);
+
+        node.tick(1, &mut control);
+        let status = node.get_od().get(cia402::STATUSWORD, 0) as u16;
+        assert!(frames(&mut node).contains(&tpdo(status, 0)));
+        node.tick(1, &mut control);
+        assert!(frames(&mut node).is_empty());
+
+        /* A Change Waits Out The Inhibit Time */
+        control.set_motor_position(Motors::Motor1, 300);
+        node.tick(3, &mut control);
+        assert!(frames(&mut node).is_empty());
+        node.tick(1, &mut control);
+        assert!(frames(&mut node) == [tpdo(status, 300)]);
+    }
+
+    #[test]
+    fn rpdo_drives_the_controlword() {
+        let mut control = MotorControl::init();
+        let mut node = node(&mut control);
+        nmt(&mut node, &mut control, NMT_START, ID);
+        frames(&mut node);
+
+        /* Not Before Operational Enabled */
+        rpdo(&mut node, &mut control, SETPOINT, 500);
+        assert!(events(&mut node).is_empty());
+        rpdo(&mut node, &mut control, 0, 500);
+
+        rpdo(&mut node, &mut control, SHUTDOWN, 500);
+        rpdo(&mut node, &mut control, ENABLE, 500);
+        assert!(events(&mut node) == [Event::Enable(Motors::Motor1)]);
+        assert!(node.drives[0].get_state() == cia402::DriveState::OperationEnabled);
+
+        control.set_motor_position(Motors::Motor1, 100);
+        rpdo(&mut node, &mut control, SETPOINT, 500);
+        assert!(events(&mut node) == [Event::Move { motor: Motors::Motor1, delta: 400, velocity: 1000, accel: 10000 }]);
+        assert!(node.get_od().get(cia402::TARGET_POSITION, 0) == 500);
+
+        /* Too Short, Dropped With An EMCY */
+        frames(&mut node);
+        node.receive(&Frame::init(RPDO1, &[ENABLE as u8, 0]), &mut control);
+        let register = ERR_GENERIC | ERR_COMMUNICATION;
+        assert!(frames(&mut node).contains(&Frame::init(EMCY, &[0x10, 0x82, register, 0, 0, 0, 0, 0])));
+        assert!(node.get_od().get(cia402::CONTROLWORD, 0) == SETPOINT as u32);
+    }
+}
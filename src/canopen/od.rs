@@ -0,0 +1,345 @@
+/* Object Dictionary */
+/* One Fixed Table Of Entries, Communication Profile (CiA 301), Four RPDOs And TPDOs, Then The CiA 402 Objects */
+/* Of Each Axis At 0x800 Apart, Values Of Up To 4 Bytes Are Kept As u32, Signed Ones Sign Extended */
+
+use crate::axis::interpolate::AXES;
+use super::cia402;
+
+/* Failures Reported In An SDO Abort (CiA 301 Table 22) */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Abort {
+    Toggle,
+    Timeout,
+    Command,
+    OutOfMemory,
+    WriteOnly,
+    ReadOnly,
+    NoObject,
+    NotMappable,
+    MapLength,
+    Length,
+    TooLong,
+    TooShort,
+    NoSub,
+    Range,
+    State,
+    General
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Access {
+    Const,
+    Ro,
+    Wo,
+    Rw
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    U8,
+    U16,
+    U32,
+    I8,
+    I16,
+    I32,
+    Text(&'static str)                      // Visible String, Constant
+}
+
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub index:      u16,
+    pub sub:        u8,
+    pub kind:       Kind,
+    pub access:     Access,
+    pub pdo:        bool,                   // May Be Mapped Into A PDO
+    pub default:    u32
+}
+
+pub struct Dictionary {
+    values:         [u32; ENTRIES]
+}
+
+/* Communication Profile */
+pub const DEVICE_TYPE:      u16 = 0x1000;
+pub const ERROR_REGISTER:   u16 = 0x1001;
+pub const SYNC_COB_ID:      u16 = 0x1005;
+pub const DEVICE_NAME:      u16 = 0x1008;
+pub const HARDWARE_VERSION: u16 = 0x1009;
+pub const SOFTWARE_VERSION: u16 = 0x100A;
+pub const EMCY_COB_ID:      u16 = 0x1014;
+pub const HEARTBEAT_TIME:   u16 = 0x1017;
+pub const IDENTITY:         u16 = 0x1018;
+pub const RPDO_COMM:        u16 = 0x1400;
+pub const RPDO_MAP:         u16 = 0x1600;
+pub const TPDO_COMM:        u16 = 0x1800;
+pub const TPDO_MAP:         u16 = 0x1A00;
+
+/* Objects From Here On Belong To The Application, Not The Communication Profile */
+const APPLICATION:          u16 = 0x2000;
+
+/* PDOs Of Each Direction, Up To 8 Objects Mapped Into One */
+pub const PDOS:             usize = 4;
+pub const MAX_MAPPED:       usize = 8;
+
+/* CiA 402 Drive, Stepper Motor */
+const DEVICE_TYPE_402:      u32 = 0x00030192;
+const DEFAULT_HEARTBEAT:    u32 = 1000;
+
+/* Default COB-IDs Before The Node ID Is Added, Each PDO Is 0x100 After The Last */
+pub const COB_SYNC:         u32 = 0x080;
+pub const COB_EMCY:         u32 = 0x080;
+pub const COB_TPDO:         u32 = 0x180;
+pub const COB_RPDO:         u32 = 0x200;
+pub const COB_PDO_STEP:     u32 = 0x100;
+
+/* Transmission Type, Event Driven */
+pub const TRANSMIT_EVENT:   u32 = 255;
+
+/* Table Layout */
+const COMM_ENTRIES:         usize = 13;
+const RPDO_ENTRIES:         usize = 3 + 1 + MAX_MAPPED;
+const TPDO_ENTRIES:         usize = 5 + 1 + MAX_MAPPED;
+pub const ENTRIES:          usize = COMM_ENTRIES + (PDOS * (RPDO_ENTRIES + TPDO_ENTRIES)) + (AXES * cia402::OBJECTS.len());
+
+static TABLE:               [Entry; ENTRIES] = build();
+
+impl Abort {
+    pub fn get_code(&self) -> u32 {
+        return match self {
+            Abort::Toggle => 0x05030000,
+            Abort::Timeout => 0x05040000,
+            Abort::Command => 0x05040001,
+            Abort::OutOfMemory => 0x05040005,
+            Abort::WriteOnly => 0x06010001,
+            Abort::ReadOnly => 0x06010002,
+            Abort::NoObject => 0x06020000,
+            Abort::NotMappable => 0x06040041,
+            Abort::MapLength => 0x06040042,
+            Abort::Length => 0x06070010,
+            Abort::TooLong => 0x06070012,
+            Abort::TooShort => 0x06070013,
+            Abort::NoSub => 0x06090011,
+            Abort::Range => 0x06090030,
+            Abort::State => 0x08000022,
+            Abort::General => 0x08000000
+        };
+    }
+}
+
+impl Kind {
+    /* Bytes On The Bus */
+    pub fn get_size(&self) -> usize {
+        return match self {
+            Kind::U8 | Kind::I8 => 1,
+            Kind::U16 | Kind::I16 => 2,
+            Kind::U32 | Kind::I32 => 4,
+            Kind::Text(text) => text.len()
+        };
+    }
+}
+
+impl Dictionary {
+    /* Defaults, COB-IDs For node_id, identity Is Vendor ID, Product Code, Revision And Serial Number */
+    pub fn init(node_id: u8, identity: [u32; 4]) -> Dictionary {
+        let mut od = Dictionary {
+            values:     [0; ENTRIES]
+        };
+        od.reset(node_id, true);
+        for (sub, &value) in identity.iter().enumerate() {
+            od.set(IDENTITY, sub as u8 + 1, value);
+        }
+        return od;
+    }
+
+    /* Communication Objects Back To Their Defaults, With application The Drive Objects Too, Identity Is Kept */
+    pub fn reset(&mut self, node_id: u8, application: bool) {
+        for (slot, entry) in TABLE.iter().enumerate() {
+            if entry.index != IDENTITY && (application || entry.index < APPLICATION) {
+                self.values[slot] = entry.default;
+            }
+        }
+
+        let id = node_id as u32;
+        self.set(EMCY_COB_ID, 0, COB_EMCY + id);
+        for pdo in 0..PDOS {
+            let step = COB_PDO_STEP * pdo as u32;
+            self.set(RPDO_COMM + pdo as u16, 1, COB_RPDO + step + id);
+            self.set(TPDO_COMM + pdo as u16, 1, COB_TPDO + step + id);
+        }
+    }
+
+    /* Slot Of An Entry, Telling A Missing Object From A Missing Sub-Index */
+    pub fn find(&self, index: u16, sub: u8) -> Result<usize, Abort> {
+        let mut found = false;
+        for (slot, entry) in TABLE.iter().enumerate() {
+            if entry.index == index {
+                if entry.sub == sub {
+                    return Ok(slot);
+                }
+                found = true;
+            }
+        }
+        return Err(if found { Abort::NoSub } else { Abort::NoObject });
+    }
+
+    pub fn get_entry(&self, slot: usize) -> &'static Entry {
+        return &TABLE[slot];
+    }
+
+    /* Value Of An Entry, 0 If There Is None */
+    pub fn get(&self, index: u16, sub: u8) -> u32 {
+        return match self.find(index, sub) {
+            Ok(slot) => self.values[slot],
+            Err(_) => 0
+        };
+    }
+
+    /* Set An Entry Whatever Its Access, For The Device's Own Use */
+    pub fn set(&mut self, index: u16, sub: u8, value: u32) {
+        if let Ok(slot) = self.find(index, sub) {
+            self.values[slot] = value;
+        }
+    }
+
+    /* Bytes Of An Entry As The Bus Sees Them, Little Endian, Returns The Length */
+    pub fn read(&self, index: u16, sub: u8, buf: &mut [u8]) -> Result<usize, Abort> {
+        let slot = self.find(index, sub)?;
+        let entry = &TABLE[slot];
+        if entry.access == Access::Wo {
+            return Err(Abort::WriteOnly);
+        }
+
+        let bytes = self.values[slot].to_le_bytes();
+        let data = match entry.kind {
+            Kind::Text(text) => text.as_bytes(),
+            kind => &bytes[..kind.get_size()]
+        };
+        if data.len() > buf.len() {
+            return Err(Abort::OutOfMemory);
+        }
+        buf[..data.len()].copy_from_slice(data);
+        return Ok(data.len());
+    }
+
+    /* Write From The Bus, Checked Against Access And Size, Not Against What The Value Means */
+    pub fn write(&mut self, index: u16, sub: u8, data: &[u8]) -> Result<(), Abort> {
+        let slot = self.find(index, sub)?;
+        let entry = &TABLE[slot];
+        if entry.access == Access::Ro || entry.access == Access::Const {
+            return Err(Abort::ReadOnly);
+        }
+        self.values[slot] = decode(entry.kind, data)?;
+        return Ok(());
+    }
+}
+
+/* Value Of data For An Entry Of kind, Exactly Its Size */
+pub fn decode(kind: Kind, data: &[u8]) -> Result<u32, Abort> {
+    let size = kind.get_size();
+    if data.len() > size {
+        return Err(Abort::TooLong);
+    } else if data.len() < size {
+        return Err(Abort::TooShort);
+    }
+
+    let mut bytes = [0; 4];
+    bytes[..size].copy_from_slice(data);
+    let value = u32::from_le_bytes(bytes);
+    return Ok(match kind {
+        Kind::I8 => value as u8 as i8 as i32 as u32,
+        Kind::I16 => value as u16 as i16 as i32 as u32,
+        _ => value
+    });
+}
+
+pub const fn entry(index: u16, sub: u8, kind: Kind, access: Access, pdo: bool, default: u32) -> Entry {
+    return Entry {
+        index,
+        sub,
+        kind,
+        access,
+        pdo,
+        default
+    };
+}
+
+/* Mapping Entry Of An Object, Index, Sub-Index And Length In Bits */
+pub const fn mapping(index: u16, sub: u8, bits: u8) -> u32 {
+    return ((index as u32) << 16) | ((sub as u32) << 8) | bits as u32;
+}
+
+const fn build() -> [Entry; ENTRIES] {
+    let comm = [
+        entry(DEVICE_TYPE, 0, Kind::U32, Access::Const, false, DEVICE_TYPE_402),
+        entry(ERROR_REGISTER, 0, Kind::U8, Access::Ro, true, 0),
+        entry(SYNC_COB_ID, 0, Kind::U32, Access::Rw, false, COB_SYNC),
+        entry(DEVICE_NAME, 0, Kind::Text("STM32L552 Axis Controller"), Access::Const, false, 0),
+        entry(HARDWARE_VERSION, 0, Kind::Text("NUCLEO-L552ZE-Q"), Access::Const, false, 0),
+        entry(SOFTWARE_VERSION, 0, Kind::Text(env!("CARGO_PKG_VERSION")), Access::Const, false, 0),
+        entry(EMCY_COB_ID, 0, Kind::U32, Access::Ro, false, COB_EMCY),
+        entry(HEARTBEAT_TIME, 0, Kind::U16, Access::Rw, false, DEFAULT_HEARTBEAT),
+        entry(IDENTITY, 0, Kind::U8, Access::Const, false, 4),
+        entry(IDENTITY, 1, Kind::U32, Access::Ro, false, 0),
+        entry(IDENTITY, 2, Kind::U32, Access::Ro, false, 0),
+        entry(IDENTITY, 3, Kind::U32, Access::Ro, false, 0),
+        entry(IDENTITY, 4, Kind::U32, Access::Ro, false, 0)
+    ];
+
+    let mut table = [entry(0, 0, Kind::U8, Access::Const, false, 0); ENTRIES];
+    let mut n = 0;
+    while n < COMM_ENTRIES {
+        table[n] = comm[n];
+        n += 1;
+    }
+
+    /* RPDO n Takes The Controlword And Target Position Of Axis n, TPDO n Its Statusword And Actual Position */
+    let mut pdo = 0;
+    while pdo < PDOS {
+        let axis = cia402::AXIS_OFFSET * pdo as u16;
+        let rpdo = [mapping(cia402::CONTROLWORD + axis, 0, 16), mapping(cia402::TARGET_POSITION + axis, 0, 32)];
+        let tpdo = [mapping(cia402::STATUSWORD + axis, 0, 16), mapping(cia402::POSITION_ACTUAL + axis, 0, 32)];
+
+        table[n] = entry(RPDO_COMM + pdo as u16, 0, Kind::U8, Access::Const, false, 2);
+        table[n + 1] = entry(RPDO_COMM + pdo as u16, 1, Kind::U32, Access::Rw, false, 0);
+        table[n + 2] = entry(RPDO_COMM + pdo as u16, 2, Kind::U8, Access::Rw, false, TRANSMIT_EVENT);
+        table[n + 3] = entry(RPDO_MAP + pdo as u16, 0, Kind::U8, Access::Rw, false, rpdo.len() as u32);
+        n += 4;
+        let mut sub = 0;
+        while sub < MAX_MAPPED {
+            let default = if sub < rpdo.len() { rpdo[sub] } else { 0 };
+            table[n] = entry(RPDO_MAP + pdo as u16, sub as u8 + 1, Kind::U32, Access::Rw, false, default);
+            n += 1;
+            sub += 1;
+        }
+
+        table[n] = entry(TPDO_COMM + pdo as u16, 0, Kind::U8, Access::Const, false, 5);
+        table[n + 1] = entry(TPDO_COMM + pdo as u16, 1, Kind::U32, Access::Rw, false, 0);
+        table[n + 2] = entry(TPDO_COMM + pdo as u16, 2, Kind::U8, Access::Rw, false, TRANSMIT_EVENT);
+        table[n + 3] = entry(TPDO_COMM + pdo as u16, 3, Kind::U16, Access::Rw, false, 0);
+        table[n + 4] = entry(TPDO_COMM + pdo as u16, 5, Kind::U16, Access::Rw, false, 0);
+        table[n + 5] = entry(TPDO_MAP + pdo as u16, 0, Kind::U8, Access::Rw, false, tpdo.len() as u32);
+        n += 6;
+        let mut sub = 0;
+        while sub < MAX_MAPPED {
+            let default = if sub < tpdo.len() { tpdo[sub] } else { 0 };
+            table[n] = entry(TPDO_MAP + pdo as u16, sub as u8 + 1, Kind::U32, Access::Rw, false, default);
+            n += 1;
+            sub += 1;
+        }
+        pdo += 1;
+    }
+
+    let mut axis = 0;
+    while axis < AXES {
+        let mut object = 0;
+        while object < cia402::OBJECTS.len() {
+            let mut item = cia402::OBJECTS[object];
+            item.index += cia402::AXIS_OFFSET * axis as u16;
+            table[n] = item;
+            n += 1;
+            object += 1;
+        }
+        axis += 1;
+    }
+    return table;
+}
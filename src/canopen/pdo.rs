@@ -0,0 +1,259 @@
+/* Process Data Objects */
+/* Mapping Follows CiA 301, Invalidate The PDO (COB-ID Bit 31), Set Sub-Index 0 To 0, Write The Entries, */
+/* Set Sub-Index 0 To Their Count And Validate It Again, Each Step Is Checked Here Before It Reaches The Dictionary */
+/* Transmission Types 0 - 240 Are Synchronous, 254 And 255 Event Driven With Inhibit Time And Event Timer */
+
+use super::od::{self, Abort, Access, Dictionary, MAX_MAPPED, PDOS};
+
+/* COB-ID Entry */
+pub const COB_INVALID:      u32 = 1 << 31;
+const COB_EXTENDED:         u32 = 1 << 29;
+const COB_ID:               u32 = 0x7FF;
+
+/* Transmission Types */
+const TYPE_SYNC_ACYCLIC:    u32 = 0;
+const TYPE_SYNC_MAX:        u32 = 240;
+const TYPE_RTR_SYNC:        u32 = 252;
+const TYPE_EVENT_VENDOR:    u32 = 254;
+
+/* Communication Sub-Indexes */
+const SUB_COB_ID:           u8 = 1;
+const SUB_TYPE:             u8 = 2;
+const SUB_INHIBIT:          u8 = 3;
+const SUB_EVENT_TIMER:      u8 = 5;
+
+/* Bits In One CAN Frame */
+const MAX_BITS:             u32 = 64;
+
+/* Inhibit Time Is In 100 us */
+const INHIBIT_PER_MS:       u32 = 10;
+
+/* Objects Mapped Into One PDO */
+#[derive(Clone, Copy)]
+pub struct Mapping {
+    count:          usize,
+    objects:        [(u16, u8, usize); MAX_MAPPED]  // Index, Sub-Index, Bytes
+}
+
+/* Transmit Side Of One TPDO */
+#[derive(Clone, Copy)]
+pub struct Tpdo {
+    last:           Option<(usize, [u8; 8])>,   // Data Last Sent
+    inhibit:        u32,                        // ms Before It May Be Sent Again
+    timer:          u32,                        // ms Since It Was Last Sent
+    syncs:          u32                         // SYNCs Since It Was Last Sent
+}
+
+impl Mapping {
+    /* Objects In A Mapping Object, RPDO_MAP Or TPDO_MAP Plus The PDO Number */
+    pub fn init(od: &Dictionary, map: u16) -> Mapping {
+        let mut mapping = Mapping {
+            count:      0,
+            objects:    [(0, 0, 0); MAX_MAPPED]
+        };
+        let count = core::cmp::min(od.get(map, 0) as usize, MAX_MAPPED);
+        for sub in 1..=count {
+            let value = od.get(map, sub as u8);
+            mapping.objects[mapping.count] = (index_of(value), sub_of(value), (bits_of(value) / 8) as usize);
+            mapping.count += 1;
+        }
+        return mapping;
+    }
+
+    /* Bytes The Mapped Objects Take */
+    pub fn get_len(&self) -> usize {
+        return self.objects[..self.count].iter().map(|&(_, _, bytes)| bytes).sum();
+    }
+
+    pub fn get_objects(&self) -> &[(u16, u8, usize)] {
+        return &self.objects[..self.count];
+    }
+
+    /* Data Of A TPDO From The Values Now In The Dictionary */
+    pub fn pack(&self, od: &Dictionary) -> (usize, [u8; 8]) {
+        let mut data = [0; 8];
+        let mut len = 0;
+        for &(index, sub, bytes) in self.get_objects() {
+            if len + bytes > data.len() {
+                break;
+            }
+            data[len..len + bytes].copy_from_slice(&od.get(index, sub).to_le_bytes()[..bytes]);
+            len += bytes;
+        }
+        return (len, data);
+    }
+
+    /* Write An RPDO Into The Mapped Objects, Checked When Mapped, False When data Is Too Short */
+    pub fn unpack(&self, od: &mut Dictionary, data: &[u8]) -> bool {
+        if data.len() < self.get_len() {
+            return false;
+        }
+        let mut at = 0;
+        for &(index, sub, bytes) in self.get_objects() {
+            if let Ok(slot) = od.find(index, sub) {
+                if let Ok(value) = od::decode(od.get_entry(slot).kind, &data[at..at + bytes]) {
+                    od.set(index, sub, value);
+                }
+            }
+            at += bytes;
+        }
+        return true;
+    }
+}
+
+impl Tpdo {
+    pub const fn init() -> Tpdo {
+        return Tpdo {
+            last:       None,
+            inhibit:    0,
+            timer:      0,
+            syncs:      0
+        };
+    }
+
+    /* Forget What Was Sent, The Next Chance Sends It */
+    pub fn reset(&mut self) {
+        *self = Tpdo::init();
+    }
+
+    /* Time Passes, ms Since The Last Call */
+    pub fn tick(&mut self, ms: u32) {
+        self.inhibit = self.inhibit.saturating_sub(ms);
+        self.timer = self.timer.saturating_add(ms);
+    }
+
+    /* Event Driven, On A Change Once The Inhibit Time Is Over Or When The Event Timer (ms, 0 Off) Runs Out */
+    pub fn is_event(&self, len: usize, data: &[u8; 8], event_timer: u32) -> bool {
+        if self.inhibit > 0 {
+            return false;
+        }
+        return self.last != Some((len, *data)) || (event_timer != 0 && self.timer >= event_timer);
+    }
+
+    /* On A SYNC, Type 0 Only When Changed, 1 - 240 Every That Many SYNCs */
+    pub fn is_sync(&mut self, transmission: u32, len: usize, data: &[u8; 8]) -> bool {
+        if transmission == TYPE_SYNC_ACYCLIC {
+            return self.last != Some((len, *data));
+        }
+        self.syncs += 1;
+        return self.syncs >= transmission;
+    }
+
+    /* Sent, Hold Off For inhibit In 100 us */
+    pub fn sent(&mut self, len: usize, data: &[u8; 8], inhibit: u32) {
+        self.last = Some((len, *data));
+        self.inhibit = inhibit.div_ceil(INHIBIT_PER_MS);
+        self.timer = 0;
+        self.syncs = 0;
+    }
+}
+
+/* COB-ID Of A PDO, None While It Is Invalid */
+pub fn get_cob_id(od: &Dictionary, comm: u16) -> Option<u16> {
+    let cob_id = od.get(comm, SUB_COB_ID);
+    if (cob_id & COB_INVALID) != 0 {
+        return None;
+    }
+    return Some((cob_id & COB_ID) as u16);
+}
+
+pub fn get_type(od: &Dictionary, comm: u16) -> u32 {
+    return od.get(comm, SUB_TYPE);
+}
+
+pub fn is_sync(transmission: u32) -> bool {
+    return transmission <= TYPE_SYNC_MAX;
+}
+
+/* Inhibit Time In 100 us And Event Timer In ms Of A TPDO */
+pub fn get_timing(od: &Dictionary, comm: u16) -> (u32, u32) {
+    return (od.get(comm, SUB_INHIBIT), od.get(comm, SUB_EVENT_TIMER));
+}
+
+/* A Write From The Bus To A PDO Communication Or Mapping Entry, Before It Is Made */
+pub fn check(od: &Dictionary, index: u16, sub: u8, value: u32) -> Result<(), Abort> {
+    let (comm, receive) = match index {
+        i if (od::RPDO_MAP..od::RPDO_MAP + PDOS as u16).contains(&i) => (i - od::RPDO_MAP + od::RPDO_COMM, true),
+        i if (od::TPDO_MAP..od::TPDO_MAP + PDOS as u16).contains(&i) => (i - od::TPDO_MAP + od::TPDO_COMM, false),
+        i if (od::RPDO_COMM..od::RPDO_COMM + PDOS as u16).contains(&i) => {
+            return check_comm(od, index, sub, value, true);
+        } i if (od::TPDO_COMM..od::TPDO_COMM + PDOS as u16).contains(&i) => {
+            return check_comm(od, index, sub, value, false);
+        } _ => {
+            return Ok(());
+        }
+    };
+
+    /* Mapping Only Changes While The PDO Is Invalid, The Entries Only While Sub-Index 0 Is 0 */
+    if get_cob_id(od, comm).is_some() {
+        return Err(Abort::State);
+    }
+    if sub != 0 {
+        if od.get(index, 0) != 0 {
+            return Err(Abort::State);
+        }
+        return if value == 0 { Ok(()) } else { check_object(od, value, receive) };
+    }
+
+    if value as usize > MAX_MAPPED {
+        return Err(Abort::MapLength);
+    }
+    let mut bits = 0;
+    for entry in 1..=value {
+        let mapped = od.get(index, entry as u8);
+        check_object(od, mapped, receive)?;
+        bits += bits_of(mapped);
+    }
+    if bits > MAX_BITS {
+        return Err(Abort::MapLength);
+    }
+    return Ok(());
+}
+
+fn check_comm(od: &Dictionary, comm: u16, sub: u8, value: u32, receive: bool) -> Result<(), Abort> {
+    match sub {
+        SUB_COB_ID => {
+            /* Only 11 Bit Identifiers, And A Valid One Has To Be Invalidated Before It Changes */
+            if (value & COB_EXTENDED) != 0 {
+                return Err(Abort::Range);
+            }
+            if (value & COB_INVALID) == 0 && get_cob_id(od, comm).is_some_and(|cob_id| cob_id as u32 != (value & COB_ID)) {
+                return Err(Abort::State);
+            }
+        } SUB_TYPE => {
+            let valid = value <= TYPE_SYNC_MAX || value >= TYPE_EVENT_VENDOR || (!receive && value == TYPE_RTR_SYNC);
+            if !valid {
+                return Err(Abort::Range);
+            }
+        } _ => {
+        }
+    }
+    return Ok(());
+}
+
+/* One Mapping Entry, The Object Has To Be Mappable, Writable For An RPDO And Its Whole Size */
+fn check_object(od: &Dictionary, value: u32, receive: bool) -> Result<(), Abort> {
+    let slot = od.find(index_of(value), sub_of(value))?;
+    let entry = od.get_entry(slot);
+    let readable = entry.access != Access::Wo;
+    let writable = entry.access == Access::Wo || entry.access == Access::Rw;
+    if !entry.pdo || (receive && !writable) || (!receive && !readable) {
+        return Err(Abort::NotMappable);
+    }
+    if bits_of(value) as usize != entry.kind.get_size() * 8 {
+        return Err(Abort::NotMappable);
+    }
+    return Ok(());
+}
+
+fn index_of(value: u32) -> u16 {
+    return (value >> 16) as u16;
+}
+
+fn sub_of(value: u32) -> u8 {
+    return (value >> 8) as u8;
+}
+
+fn bits_of(value: u32) -> u32 {
+    return value & 0xFF;
+}
@@ -0,0 +1,398 @@
+/* Service Data Object Server */
+/* Expedited Transfers Carry Up To 4 Bytes In The Initiate Frame, Segmented Ones 7 Bytes Per Segment With A Toggle Bit */
+/* A Download Is Only Written To The Dictionary Once Complete, After Access, Size And check Have Passed */
+/* Block Transfer Is Not Supported, A Client Asking For It Gets An Abort */
+
+use super::od::{self, Abort, Access, Dictionary};
+
+/* Client Command Specifier, Bits 7 - 5 Of Byte 0 */
+const CCS_DOWNLOAD_SEGMENT: u8 = 0;
+const CCS_DOWNLOAD:         u8 = 1;
+const CCS_UPLOAD:           u8 = 2;
+const CCS_UPLOAD_SEGMENT:   u8 = 3;
+const CCS_ABORT:            u8 = 4;
+
+/* Server Responses */
+const SCS_DOWNLOAD_SEGMENT: u8 = 0x20;
+const SCS_DOWNLOAD:         u8 = 0x60;
+const SCS_UPLOAD:           u8 = 0x40;
+const SCS_UPLOAD_SEGMENT:   u8 = 0x00;
+const SCS_ABORT:            u8 = 0x80;
+
+/* Command Byte Fields */
+const CMD_SIZED:            u8 = 1 << 0;    // Size Indicated
+const CMD_EXPEDITED:        u8 = 1 << 1;
+const CMD_LAST:             u8 = 1 << 0;    // Segment: No More Segments
+const CMD_TOGGLE:           u8 = 1 << 4;
+const CMD_N_SHIFT:          u8 = 2;         // Expedited: Bytes Without Data
+const CMD_SEG_N_SHIFT:      u8 = 1;         // Segment: Bytes Without Data
+
+/* Bytes Of Data In One Expedited Frame And One Segment */
+const EXPEDITED_BYTES:      usize = 4;
+const SEGMENT_BYTES:        usize = 7;
+
+/* Largest Value Moved In Segments */
+const SDO_BUFFER:           usize = 64;
+
+/* A Transfer The Client Leaves Unfinished This Long (ms) Is Aborted */
+const SDO_TIMEOUT:          u32 = 1000;
+
+/* Frames Of Another Length Are Not SDOs */
+const SDO_FRAME:            usize = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Transfer {
+    Idle,
+    Download,
+    Upload
+}
+
+/* Response To One Request, written Names The Entry A Completed Download Changed */
+#[derive(Clone, Copy)]
+pub struct Response {
+    pub data:       [u8; 8],
+    pub written:    Option<(u16, u8)>
+}
+
+pub struct SdoServer {
+    transfer:       Transfer,
+    index:          u16,
+    sub:            u8,
+    toggle:         u8,                     // Next Segment's Toggle Bit
+    size:           Option<usize>,          // Download Size The Client Gave
+    buf:            [u8; SDO_BUFFER],
+    len:            usize,                  // Bytes In buf
+    at:             usize,                  // Upload: Bytes Sent
+    elapsed:        u32                     // ms Since The Last Request Of This Transfer
+}
+
+impl Response {
+    fn init(data: [u8; 8]) -> Response {
+        return Response {
+            data,
+            written:    None
+        };
+    }
+}
+
+impl SdoServer {
+    pub const fn init() -> SdoServer {
+        return SdoServer {
+            transfer:   Transfer::Idle,
+            index:      0,
+            sub:        0,
+            toggle:     0,
+            size:       None,
+            buf:        [0; SDO_BUFFER],
+            len:        0,
+            at:         0,
+            elapsed:    0
+        };
+    }
+
+    /* A Request From The Client, check Sees Every Value Before It Is Written */
+    pub fn request(&mut self, data: &[u8], od: &mut Dictionary, check: fn(&Dictionary, u16, u8, u32) -> Result<(), Abort>) -> Option<Response> {
+        if data.len() != SDO_FRAME {
+            return None;
+        }
+        let command = data[0];
+        self.elapsed = 0;
+
+        match command >> 5 {
+            CCS_DOWNLOAD => {
+                self.transfer = Transfer::Idle;
+                self.index = u16::from_le_bytes([data[1], data[2]]);
+                self.sub = data[3];
+                return Some(match self.download(command, &data[4..], od, check) {
+                    Ok(written) => Response {
+                        data:       self.reply(SCS_DOWNLOAD, &[]),
+                        written
+                    },
+                    Err(abort) => self.abort(abort)
+                });
+            } CCS_DOWNLOAD_SEGMENT => {
+                return Some(match self.segment(command, &data[1..], od, check) {
+                    Ok(written) => Response {
+                        data:       [SCS_DOWNLOAD_SEGMENT | (command & CMD_TOGGLE), 0, 0, 0, 0, 0, 0, 0],
+                        written
+                    },
+                    Err(abort) => self.abort(abort)
+                });
+            } CCS_UPLOAD => {
+                self.transfer = Transfer::Idle;
+                self.index = u16::from_le_bytes([data[1], data[2]]);
+                self.sub = data[3];
+                return Some(match self.upload(od) {
+                    Ok(reply) => Response::init(reply),
+                    Err(abort) => self.abort(abort)
+                });
+            } CCS_UPLOAD_SEGMENT => {
+                return Some(match self.upload_segment(command) {
+                    Ok(reply) => Response::init(reply),
+                    Err(abort) => self.abort(abort)
+                });
+            } CCS_ABORT => {
+                self.transfer = Transfer::Idle;
+                return None;
+            } _ => {
+                return Some(self.abort(Abort::Command));
+            }
+        }
+    }
+
+    /* Time Passes, ms Since The Last Call, An Abort To Send Once A Transfer Times Out */
+    pub fn tick(&mut self, ms: u32) -> Option<[u8; 8]> {
+        if self.transfer == Transfer::Idle {
+            return None;
+        }
+        self.elapsed = self.elapsed.saturating_add(ms);
+        if self.elapsed < SDO_TIMEOUT {
+            return None;
+        }
+        return Some(self.abort(Abort::Timeout).data);
+    }
+
+    /* Drop Any Transfer Under Way */
+    pub fn reset(&mut self) {
+        self.transfer = Transfer::Idle;
+    }
+
+    /* Initiate Download, Expedited Writes At Once, Otherwise Segments Follow */
+    fn download(&mut self, command: u8, data: &[u8], od: &mut Dictionary, check: fn(&Dictionary, u16, u8, u32) -> Result<(), Abort>) -> Result<Option<(u16, u8)>, Abort> {
+        let slot = od.find(self.index, self.sub)?;
+        let entry = od.get_entry(slot);
+        if entry.access == Access::Ro || entry.access == Access::Const {
+            return Err(Abort::ReadOnly);
+        }
+
+        if (command & CMD_EXPEDITED) != 0 {
+            let len = if (command & CMD_SIZED) != 0 {
+                EXPEDITED_BYTES - ((command >> CMD_N_SHIFT) & 0x3) as usize
+            } else {
+                core::cmp::min(entry.kind.get_size(), EXPEDITED_BYTES)
+            };
+            self.buf[..len].copy_from_slice(&data[..len]);
+            self.len = len;
+            return self.commit(od, check).map(Some);
+        }
+
+        /* Segmented, The Size Is Checked Against The Entry Before Any Data Comes */
+        self.size = None;
+        if (command & CMD_SIZED) != 0 {
+            let size = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+            if size > SDO_BUFFER {
+                return Err(Abort::OutOfMemory);
+            }
+            if size > entry.kind.get_size() {
+                return Err(Abort::TooLong);
+            }
+            self.size = Some(size);
+        }
+        self.transfer = Transfer::Download;
+        self.toggle = 0;
+        self.len = 0;
+        return Ok(None);
+    }
+
+    fn segment(&mut self, command: u8, data: &[u8], od: &mut Dictionary, check: fn(&Dictionary, u16, u8, u32) -> Result<(), Abort>) -> Result<Option<(u16, u8)>, Abort> {
+        if self.transfer != Transfer::Download {
+            return Err(Abort::Command);
+        }
+        if (command & CMD_TOGGLE) != self.toggle {
+            return Err(Abort::Toggle);
+        }
+        self.toggle ^= CMD_TOGGLE;
+
+        let len = SEGMENT_BYTES - ((command >> CMD_SEG_N_SHIFT) & 0x7) as usize;
+        if self.len + len > SDO_BUFFER {
+            return Err(Abort::OutOfMemory);
+        }
+        self.buf[self.len..self.len + len].copy_from_slice(&data[..len]);
+        self.len += len;
+        if (command & CMD_LAST) == 0 {
+            return Ok(None);
+        }
+
+        self.transfer = Transfer::Idle;
+        if let Some(size) = self.size {
+            if self.len != size {
+                return Err(Abort::Length);
+            }
+        }
+        return self.commit(od, check).map(Some);
+    }
+
+    /* The Whole Value Is In buf, Check And Write It */
+    fn commit(&mut self, od: &mut Dictionary, check: fn(&Dictionary, u16, u8, u32) -> Result<(), Abort>) -> Result<(u16, u8), Abort> {
+        let slot = od.find(self.index, self.sub)?;
+        let value = od::decode(od.get_entry(slot).kind, &self.buf[..self.len])?;
+        check(od, self.index, self.sub, value)?;
+        od.write(self.index, self.sub, &self.buf[..self.len])?;
+        return Ok((self.index, self.sub));
+    }
+
+    /* Initiate Upload, Expedited When It Fits, Otherwise The Size Now And Segments On Request */
+    /* An Empty Value Has No Expedited Form (n Is 2 Bits), It Goes As Size 0 And One Empty Last Segment */
+    fn upload(&mut self, od: &Dictionary) -> Result<[u8; 8], Abort> {
+        self.len = od.read(self.index, self.sub, &mut self.buf)?;
+        if self.len > 0 && self.len <= EXPEDITED_BYTES {
+            let command = SCS_UPLOAD | ((EXPEDITED_BYTES - self.len) as u8) << CMD_N_SHIFT | CMD_EXPEDITED | CMD_SIZED;
+            let mut reply = self.reply(command, &[]);
+            reply[4..4 + self.len].copy_from_slice(&self.buf[..self.len]);
+            return Ok(reply);
+        }
+
+        self.transfer = Transfer::Upload;
+        self.toggle = 0;
+        self.at = 0;
+        return Ok(self.reply(SCS_UPLOAD | CMD_SIZED, &(self.len as u32).to_le_bytes()));
+    }
+
+    fn upload_segment(&mut self, command: u8) -> Result<[u8; 8], Abort> {
+        if self.transfer != Transfer::Upload {
+            return Err(Abort::Command);
+        }
+        if (command & CMD_TOGGLE) != self.toggle {
+            return Err(Abort::Toggle);
+        }
+
+        let len = core::cmp::min(self.len - self.at, SEGMENT_BYTES);
+        let last = self.at + len == self.len;
+        let mut reply = [0; 8];
+        reply[0] = SCS_UPLOAD_SEGMENT | self.toggle | ((SEGMENT_BYTES - len) as u8) << CMD_SEG_N_SHIFT;
+        if last {
+            reply[0] |= CMD_LAST;
+            self.transfer = Transfer::Idle;
+        }
+        reply[1..1 + len].copy_from_slice(&self.buf[self.at..self.at + len]);
+        self.at += len;
+        self.toggle ^= CMD_TOGGLE;
+        return Ok(reply);
+    }
+
+    /* Abort The Transfer, The Response Carries The Entry And The Reason */
+    fn abort(&mut self, abort: Abort) -> Response {
+        self.transfer = Transfer::Idle;
+        return Response::init(self.reply(SCS_ABORT, &abort.get_code().to_le_bytes()));
+    }
+
+    /* Response Naming The Current Entry, data In Bytes 4 - 7 */
+    fn reply(&self, command: u8, data: &[u8]) -> [u8; 8] {
+        let index = self.index.to_le_bytes();
+        let mut reply = [command, index[0], index[1], self.sub, 0, 0, 0, 0];
+        reply[4..4 + data.len()].copy_from_slice(data);
+        return reply;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allow(_od: &Dictionary, _index: u16, _sub: u8, _value: u32) -> Result<(), Abort> {
+        return Ok(());
+    }
+
+    fn refuse(_od: &Dictionary, _index: u16, _sub: u8, _value: u32) -> Result<(), Abort> {
+        return Err(Abort::Range);
+    }
+
+    fn dictionary() -> Dictionary {
+        return Dictionary::init(5, [0; 4]);
+    }
+
+    /* Abort Code Of An Abort Response For index:sub */
+    fn aborted(response: Option<Response>, index: u16, sub: u8) -> u32 {
+        let data = response.unwrap().data;
+        let name = index.to_le_bytes();
+        assert!(data[0] == SCS_ABORT && data[1] == name[0] && data[2] == name[1] && data[3] == sub);
+        return u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    }
+
+    #[test]
+    fn expedited_download_writes_the_entry() {
+        let mut od = dictionary();
+        let mut server = SdoServer::init();
+
+        /* 2 Bytes, n = 2 */
+        let response = server.request(&[0x2B, 0x17, 0x10, 0x00, 0xF4, 0x01, 0x00, 0x00], &mut od, allow).unwrap();
+        assert!(response.data == [SCS_DOWNLOAD, 0x17, 0x10, 0x00, 0, 0, 0, 0]);
+        assert!(response.written == Some((od::HEARTBEAT_TIME, 0)));
+        assert!(od.get(od::HEARTBEAT_TIME, 0) == 500);
+
+        /* Expedited Upload Reads It Back, n = 2 */
+        let response = server.request(&[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0], &mut od, allow).unwrap();
+        assert!(response.data == [0x4B, 0x17, 0x10, 0x00, 0xF4, 0x01, 0x00, 0x00]);
+        assert!(response.written.is_none());
+    }
+
+    #[test]
+    fn segmented_upload_of_the_device_name() {
+        let mut od = dictionary();
+        let mut server = SdoServer::init();
+        let mut name = [0; SDO_BUFFER];
+        let len = od.read(od::DEVICE_NAME, 0, &mut name).ok().unwrap();
+        assert!(len > EXPEDITED_BYTES);
+
+        /* Initiate Gives The Size */
+        let response = server.request(&[0x40, 0x08, 0x10, 0x00, 0, 0, 0, 0], &mut od, allow).unwrap();
+        assert!(response.data[..4] == [SCS_UPLOAD | CMD_SIZED, 0x08, 0x10, 0x00]);
+        assert!(u32::from_le_bytes([response.data[4], response.data[5], response.data[6], response.data[7]]) as usize == len);
+
+        /* Segments Alternate The Toggle Until The Last */
+        let mut text = [0; SDO_BUFFER];
+        let mut at = 0;
+        let mut toggle = 0;
+        loop {
+            let data = server.request(&[0x60 | toggle, 0, 0, 0, 0, 0, 0, 0], &mut od, allow).unwrap().data;
+            assert!((data[0] >> 5) == 0 && (data[0] & CMD_TOGGLE) == toggle);
+            let n = SEGMENT_BYTES - ((data[0] >> CMD_SEG_N_SHIFT) & 0x7) as usize;
+            text[at..at + n].copy_from_slice(&data[1..1 + n]);
+            at += n;
+            toggle ^= CMD_TOGGLE;
+            if (data[0] & CMD_LAST) != 0 {
+                break;
+            }
+            assert!(n == SEGMENT_BYTES);
+        }
+        assert!(at == len && text[..len] == name[..len]);
+
+        /* The Transfer Is Over */
+        assert!(aborted(server.request(&[0x60 | toggle, 0, 0, 0, 0, 0, 0, 0], &mut od, allow), od::DEVICE_NAME, 0) == 0x05040001);
+    }
+
+    #[test]
+    fn aborts_carry_their_codes() {
+        let mut od = dictionary();
+        let mut server = SdoServer::init();
+
+        /* No Object, No Sub-Index */
+        assert!(aborted(server.request(&[0x40, 0x00, 0x20, 0x00, 0, 0, 0, 0], &mut od, allow), 0x2000, 0) == 0x06020000);
+        assert!(aborted(server.request(&[0x40, 0x17, 0x10, 0x09, 0, 0, 0, 0], &mut od, allow), 0x1017, 9) == 0x06090011);
+
+        /* Writing A Constant */
+        assert!(aborted(server.request(&[0x2F, 0x08, 0x10, 0x00, 1, 0, 0, 0], &mut od, allow), 0x1008, 0) == 0x06010002);
+
+        /* Wrong Length, 1 Byte Into A U16 */
+        assert!(aborted(server.request(&[0x2F, 0x17, 0x10, 0x00, 1, 0, 0, 0], &mut od, allow), 0x1017, 0) == 0x06070013);
+
+        /* check Refuses The Value, Nothing Is Written */
+        assert!(aborted(server.request(&[0x2B, 0x17, 0x10, 0x00, 0xF4, 0x01, 0, 0], &mut od, refuse), 0x1017, 0) == 0x06090030);
+        assert!(od.get(od::HEARTBEAT_TIME, 0) != 500);
+
+        /* Toggle Out Of Step */
+        server.request(&[0x40, 0x08, 0x10, 0x00, 0, 0, 0, 0], &mut od, allow).unwrap();
+        assert!(aborted(server.request(&[0x70, 0, 0, 0, 0, 0, 0, 0], &mut od, allow), 0x1008, 0) == 0x05030000);
+
+        /* Block Upload Is Not Supported */
+        let data = server.request(&[0xA0, 0x08, 0x10, 0x00, 0, 0, 0, 0], &mut od, allow).unwrap().data;
+        assert!(data[0] == SCS_ABORT && u32::from_le_bytes([data[4], data[5], data[6], data[7]]) == 0x05040001);
+
+        /* A Transfer Left Hanging Times Out Once */
+        server.request(&[0x40, 0x08, 0x10, 0x00, 0, 0, 0, 0], &mut od, allow).unwrap();
+        assert!(server.tick(SDO_TIMEOUT - 1).is_none());
+        let data = server.tick(1).unwrap();
+        assert!(data[0] == SCS_ABORT && u32::from_le_bytes([data[4], data[5], data[6], data[7]]) == 0x05040000);
+        assert!(server.tick(SDO_TIMEOUT).is_none());
+    }
+}
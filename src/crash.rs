@@ -0,0 +1,212 @@
+/* Panic Handling With A Crash Record That Survives The Reset */
+/* The Record Lives In .noinit RAM, Which The Startup Neither Copies Nor Zeroes, And Is Checked With A CRC */
+/* So Power-On Garbage Is Never Taken For A Crash */
+
+use core::arch::asm;
+use core::fmt;
+use core::fmt::Write;
+use core::mem;
+use core::mem::MaybeUninit;
+use core::panic::PanicInfo;
+use core::ptr;
+use crate::driver::crc;
+use crate::fault;
+
+const MESSAGE_SIZE:         usize = 96;
+const FILE_SIZE:            usize = 48;
+
+/* Marks A Record Written By This Firmware */
+const MAGIC:                u32 = 0xC7A5_4ED0;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CrashRecord {
+    magic:          u32,
+    pending:        u32,                    // Not Yet Taken By The Application
+    reset_count:    u32,                    // Panics Since Power-On
+    line:           u32,
+    column:         u32,
+    pc:             u32,                    // Return Address Into The Panic Path, From caller
+    file_len:       u32,
+    message_len:    u32,
+    file:           [u8; FILE_SIZE],
+    message:        [u8; MESSAGE_SIZE],
+    crc:            u32                     // CRC-32 Of Everything Above
+}
+
+/* Fixed Buffer Formatter, Text Past The End Is Dropped */
+struct Text<'a> {
+    buf:            &'a mut [u8],
+    len:            usize
+}
+
+#[link_section = ".noinit"]
+static mut RECORD:          MaybeUninit<CrashRecord> = MaybeUninit::uninit();
+
+/* Set While Panicking, A Second Panic Resets Straight Away */
+static mut PANICKING:       bool = false;
+
+impl CrashRecord {
+    const fn init() -> CrashRecord {
+        return CrashRecord {
+            magic:          MAGIC,
+            pending:        0,
+            reset_count:    0,
+            line:           0,
+            column:         0,
+            pc:             0,
+            file_len:       0,
+            message_len:    0,
+            file:           [0; FILE_SIZE],
+            message:        [0; MESSAGE_SIZE],
+            crc:            0
+        };
+    }
+
+    pub fn get_message(&self) -> &str {
+        return text(&self.message, self.message_len);
+    }
+
+    pub fn get_file(&self) -> &str {
+        return text(&self.file, self.file_len);
+    }
+
+    pub fn get_line(&self) -> u32 {
+        return self.line;
+    }
+
+    pub fn get_column(&self) -> u32 {
+        return self.column;
+    }
+
+    pub fn get_pc(&self) -> u32 {
+        return self.pc;
+    }
+
+    pub fn get_reset_count(&self) -> u32 {
+        return self.reset_count;
+    }
+
+    pub fn write<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "\r\n*** Panic Before Reset {} ***\r\n", self.reset_count)?;
+        write!(w, "{}:{}:{} PC 0x{:08X}\r\n", self.get_file(), self.line, self.column, self.pc)?;
+        write!(w, "{}\r\n", self.get_message())?;
+        return Ok(());
+    }
+
+    fn checksum(&self) -> u32 {
+        let len = mem::size_of::<CrashRecord>() - mem::size_of::<u32>();
+        let bytes = unsafe { core::slice::from_raw_parts(self as *const CrashRecord as *const u8, len) };
+        return crc::calculate(crc::CRC32_ISO_HDLC, bytes);
+    }
+
+    fn valid(&self) -> bool {
+        return self.magic == MAGIC && (self.file_len as usize) <= FILE_SIZE &&
+            (self.message_len as usize) <= MESSAGE_SIZE && self.crc == self.checksum();
+    }
+
+    fn seal(&mut self) {
+        self.crc = self.checksum();
+    }
+}
+
+impl<'a> fmt::Write for Text<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = core::cmp::min(s.len(), self.buf.len() - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        return Ok(());
+    }
+}
+
+/* Call Early At Start Up, Keeps A Valid Record And Starts A Fresh One Otherwise */
+pub fn init() {
+    unsafe {
+        let record = ptr::read_volatile(ptr::addr_of!(RECORD) as *const CrashRecord);
+        if !record.valid() {
+            let mut record = CrashRecord::init();
+            record.seal();
+            ptr::write_volatile(ptr::addr_of_mut!(RECORD) as *mut CrashRecord, record);
+        }
+    }
+}
+
+/* Record Of The Panic Before The Last Reset, Only Returned Once */
+pub fn take() -> Option<CrashRecord> {
+    unsafe {
+        let mut record = ptr::read_volatile(ptr::addr_of!(RECORD) as *const CrashRecord);
+        if !record.valid() || record.pending == 0 {
+            return None;
+        }
+        let taken = record;
+        record.pending = 0;
+        record.seal();
+        ptr::write_volatile(ptr::addr_of_mut!(RECORD) as *mut CrashRecord, record);
+        return Some(taken);
+    }
+}
+
+/* Panics Since Power-On */
+pub fn get_reset_count() -> u32 {
+    let record = unsafe { ptr::read_volatile(ptr::addr_of!(RECORD) as *const CrashRecord) };
+    return if record.valid() { record.reset_count } else { 0 };
+}
+
+/* Return Address Of The Function It Is Inlined Into, Read Straight From LR Without The Thumb Bit */
+/* Only Right Before Anything Is Called, So It Has To Be The First Thing The #[panic_handler] Does */
+#[inline(always)]
+pub fn caller() -> u32 {
+    let lr: u32;
+    unsafe { asm!("mov {0}, lr", out(reg) lr, options(nomem, nostack, preserves_flags)) };
+    return lr & !1;
+}
+
+/* Body Of The #[panic_handler], pc From caller, Reports On The Fault Port, Saves The Record And Resets */
+pub fn panic(info: &PanicInfo, pc: u32) -> ! {
+    unsafe {
+        if PANICKING {
+            fault::reset();
+        }
+        PANICKING = true;
+    }
+
+    let mut record = unsafe { ptr::read_volatile(ptr::addr_of!(RECORD) as *const CrashRecord) };
+    if !record.valid() {
+        record = CrashRecord::init();
+    }
+    record.pending = 1;
+    record.reset_count = record.reset_count.wrapping_add(1);
+    record.pc = pc;
+
+    let mut message = Text { buf: &mut record.message, len: 0 };
+    let _ = write!(message, "{}", info.message());
+    record.message_len = message.len as u32;
+
+    if let Some(location) = info.location() {
+        let mut file = Text { buf: &mut record.file, len: 0 };
+        let _ = file.write_str(location.file());
+        record.file_len = file.len as u32;
+        record.line = location.line();
+        record.column = location.column();
+    } else {
+        record.file_len = 0;
+        record.line = 0;
+        record.column = 0;
+    }
+
+    record.seal();
+    unsafe { ptr::write_volatile(ptr::addr_of_mut!(RECORD) as *mut CrashRecord, record) };
+
+    fault::report(format_args!("\r\n*** Panic ***\r\n{}:{}:{} PC 0x{:08X}\r\n{}\r\n",
+        record.get_file(), record.line, record.column, record.pc, record.get_message()));
+    fault::reset();
+}
+
+/* Truncation Can Split A Character, Only The Valid Part Is Returned */
+fn text(buf: &[u8], len: u32) -> &str {
+    let buf = &buf[..core::cmp::min(len as usize, buf.len())];
+    return match core::str::from_utf8(buf) {
+        Ok(s) => s,
+        Err(error) => unsafe { core::str::from_utf8_unchecked(&buf[..error.valid_up_to()]) }
+    };
+}
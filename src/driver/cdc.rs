@@ -0,0 +1,299 @@
+use super::usb;
+use super::serial;
+
+/* USB Communications Device Class, Abstract Control Model (CDC-ACM) */
+/* Shows Up On The Host As A Virtual Serial Port (ttyACM / COM) */
+pub struct Cdc {
+    usb:            usb::Usb,               // Peripheral Registers
+    configured:     bool,                   // Host Has Selected The Configuration
+    line_coding:    LineCoding,             // Line Coding Set By The Host, Only Reported Back
+    dtr:            bool,                   // Data Terminal Ready, A Terminal Has The Port Open
+    rts:            bool,                   // Request To Send
+    rx_buf:         [u8; RX_BUF_SIZE],      // Received Data Ring Buffer
+    rx_head:        usize,                  // Next Byte To Write
+    rx_tail:        usize,                  // Next Byte To Read
+    rx_paused:      bool                    // OUT Endpoint Left NAK Until There Is Room
+}
+
+/* Line Coding (CDC PSTN 6.3.11) */
+#[derive(Clone, Copy)]
+pub struct LineCoding {
+    pub baud:       u32,                    // Data Terminal Rate In Bits Per Second
+    pub stop_bits:  u8,                     // 0 - 1 Stop Bit, 1 - 1.5 Stop Bits, 2 - 2 Stop Bits
+    pub parity:     u8,                     // 0 - None, 1 - Odd, 2 - Even, 3 - Mark, 4 - Space
+    pub data_bits:  u8                      // 5, 6, 7, 8 Or 16
+}
+
+/* Endpoints */
+pub const EP_DATA:          u8 = 1;         // Bulk IN 0x81 And Bulk OUT 0x01
+pub const EP_NOTIFY:        u8 = 2;         // Interrupt IN 0x82
+const DATA_SIZE:            u16 = 64;
+const NOTIFY_SIZE:          u16 = 8;
+
+/* Class Requests */
+const SET_LINE_CODING:          u8 = 0x20;
+const GET_LINE_CODING:          u8 = 0x21;
+const SET_CONTROL_LINE_STATE:   u8 = 0x22;
+const SEND_BREAK:               u8 = 0x23;
+
+/* Control Line State */
+const LINE_DTR:             u16 = 1 << 0;
+const LINE_RTS:             u16 = 1 << 1;
+
+const RX_BUF_SIZE:          usize = 256;
+
+/* Polls Of The IN Endpoint Before A Write Is Dropped, Stops A Closed Port From Hanging The Caller */
+const TX_TIMEOUT:           u32 = 100000;
+
+/* Identification, ST Virtual COM Port VID / PID */
+const VID:                  u16 = 0x0483;
+const PID:                  u16 = 0x5740;
+const MANUFACTURER:         &str = "STMicroelectronics";
+const PRODUCT:              &str = "STM32L552ZE Virtual COM Port";
+const SERIAL_NUMBER:        &str = "000000000001";
+
+const DEVICE_DESCRIPTOR: [u8; 18] = [
+    18,                                     // bLength
+    usb::DESC_DEVICE,                       // bDescriptorType
+    0x00, 0x02,                             // bcdUSB 2.00
+    0x02,                                   // bDeviceClass CDC
+    0x00,                                   // bDeviceSubClass
+    0x00,                                   // bDeviceProtocol
+    usb::EP0_SIZE as u8,                    // bMaxPacketSize0
+    VID as u8, (VID >> 8) as u8,            // idVendor
+    PID as u8, (PID >> 8) as u8,            // idProduct
+    0x00, 0x02,                             // bcdDevice 2.00
+    1,                                      // iManufacturer
+    2,                                      // iProduct
+    3,                                      // iSerialNumber
+    1                                       // bNumConfigurations
+];
+
+const CONFIG_DESCRIPTOR: [u8; 67] = [
+    /* Configuration */
+    9, usb::DESC_CONFIG, 67, 0,             // bLength, bDescriptorType, wTotalLength
+    2,                                      // bNumInterfaces
+    1,                                      // bConfigurationValue
+    0,                                      // iConfiguration
+    0xA0,                                   // bmAttributes Bus Powered, Remote Wakeup
+    50,                                     // bMaxPower 100 mA
+    /* Communication Interface */
+    9, usb::DESC_INTERFACE, 0, 0,           // bLength, bDescriptorType, bInterfaceNumber, bAlternateSetting
+    1,                                      // bNumEndpoints
+    0x02, 0x02, 0x01,                       // Communications, Abstract Control Model, AT Commands
+    0,                                      // iInterface
+    /* Header Functional Descriptor */
+    5, 0x24, 0x00, 0x10, 0x01,              // CDC 1.10
+    /* Call Management Functional Descriptor */
+    5, 0x24, 0x01, 0x00, 1,                 // No Call Management, Data Interface 1
+    /* Abstract Control Management Functional Descriptor */
+    4, 0x24, 0x02, 0x02,                    // Line Coding And Serial State
+    /* Union Functional Descriptor */
+    5, 0x24, 0x06, 0, 1,                    // Master Interface 0, Slave Interface 1
+    /* Notification Endpoint */
+    7, usb::DESC_ENDPOINT, 0x80 | EP_NOTIFY, 0x03, NOTIFY_SIZE as u8, 0, 16,
+    /* Data Interface */
+    9, usb::DESC_INTERFACE, 1, 0,           // bLength, bDescriptorType, bInterfaceNumber, bAlternateSetting
+    2,                                      // bNumEndpoints
+    0x0A, 0x00, 0x00,                       // CDC Data
+    0,                                      // iInterface
+    /* Data Endpoints */
+    7, usb::DESC_ENDPOINT, EP_DATA, 0x02, DATA_SIZE as u8, 0, 0,
+    7, usb::DESC_ENDPOINT, 0x80 | EP_DATA, 0x02, DATA_SIZE as u8, 0, 0
+];
+
+impl LineCoding {
+    pub const fn init() -> LineCoding {
+        return LineCoding {
+            baud:       115200,
+            stop_bits:  0,
+            parity:     0,
+            data_bits:  8
+        };
+    }
+}
+
+impl Cdc {
+    pub fn init(usb: usb::Usb) -> Cdc {
+        return Cdc {
+            usb,
+            configured:     false,
+            line_coding:    LineCoding::init(),
+            dtr:            false,
+            rts:            false,
+            rx_buf:         [0; RX_BUF_SIZE],
+            rx_head:        0,
+            rx_tail:        0,
+            rx_paused:      false
+        };
+    }
+
+    /* Host Configured The Device And A Terminal Has The Port Open */
+    pub fn get_connected(&self) -> bool {
+        return self.configured && self.dtr;
+    }
+
+    pub fn get_rts(&self) -> bool {
+        return self.rts;
+    }
+
+    pub fn get_line_coding(&self) -> LineCoding {
+        return self.line_coding;
+    }
+
+    /* Number Of Received Bytes Waiting To Be Read */
+    pub fn get_read(&self) -> usize {
+        return (self.rx_head + RX_BUF_SIZE - self.rx_tail) % RX_BUF_SIZE;
+    }
+
+    /* Read Received Bytes, Returns The Number Copied Into buf */
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let mut len = 0;
+        while len < buf.len() && self.rx_tail != self.rx_head {
+            buf[len] = self.rx_buf[self.rx_tail];
+            self.rx_tail = (self.rx_tail + 1) % RX_BUF_SIZE;
+            len += 1;
+        }
+
+        if self.rx_paused && self.rx_free() >= DATA_SIZE as usize {
+            self.rx_paused = false;
+            self.usb.ep_set_stat_rx(EP_DATA, usb::EpStat::Valid);
+        }
+        return len;
+    }
+
+    fn rx_free(&self) -> usize {
+        return RX_BUF_SIZE - 1 - self.get_read();
+    }
+
+    /* Wait For The Previous Packet To Go Out, Gives Up If The Host Stops Reading */
+    fn tx_wait(&self) -> bool {
+        for _ in 0..TX_TIMEOUT {
+            if self.usb.ep_tx_ready(EP_DATA) {
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+impl usb::UsbClass for Cdc {
+    fn device_descriptor(&self) -> &[u8] {
+        return &DEVICE_DESCRIPTOR;
+    }
+
+    fn config_descriptor(&self) -> &[u8] {
+        return &CONFIG_DESCRIPTOR;
+    }
+
+    fn string_descriptor(&self, index: u8) -> Option<&str> {
+        return match index {
+            1 => Some(MANUFACTURER),
+            2 => Some(PRODUCT),
+            3 => Some(SERIAL_NUMBER),
+            _ => None
+        };
+    }
+
+    fn configure(&mut self, dev: &mut usb::UsbDevice) {
+        let data = dev.ep_open(EP_DATA, usb::EpType::Bulk, DATA_SIZE, DATA_SIZE);
+        let notify = dev.ep_open(EP_NOTIFY, usb::EpType::Interrupt, NOTIFY_SIZE, 0);
+        self.configured = data && notify;
+        self.rx_head = 0;
+        self.rx_tail = 0;
+        self.rx_paused = false;
+    }
+
+    fn reset(&mut self) {
+        self.configured = false;
+        self.dtr = false;
+        self.rts = false;
+    }
+
+    fn control_in(&mut self, setup: &usb::SetupPacket, buf: &mut [u8]) -> Option<usize> {
+        if setup.req_type() != usb::REQ_TYPE_CLASS || setup.request != GET_LINE_CODING || buf.len() < 7 {
+            return None;
+        }
+        buf[0..4].copy_from_slice(&self.line_coding.baud.to_le_bytes());
+        buf[4] = self.line_coding.stop_bits;
+        buf[5] = self.line_coding.parity;
+        buf[6] = self.line_coding.data_bits;
+        return Some(7);
+    }
+
+    fn control_out(&mut self, setup: &usb::SetupPacket, data: &[u8]) -> bool {
+        if setup.req_type() != usb::REQ_TYPE_CLASS {
+            return false;
+        }
+
+        match setup.request {
+            SET_LINE_CODING => {
+                if data.len() < 7 {
+                    return false;
+                }
+                self.line_coding = LineCoding {
+                    baud:       u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+                    stop_bits:  data[4],
+                    parity:     data[5],
+                    data_bits:  data[6]
+                };
+                return true;
+            } SET_CONTROL_LINE_STATE => {
+                self.dtr = (setup.value & LINE_DTR) != 0;
+                self.rts = (setup.value & LINE_RTS) != 0;
+                return true;
+            } SEND_BREAK => {
+                return true;
+            } _ => {
+                return false;
+            }
+        }
+    }
+
+    fn ep_out(&mut self, usb: &usb::Usb, ep: u8) {
+        if ep != EP_DATA {
+            return;
+        }
+
+        let mut packet = [0u8; DATA_SIZE as usize];
+        let len = usb.ep_read(ep, &mut packet);
+        for &byte in packet[..len].iter() {
+            let next = (self.rx_head + 1) % RX_BUF_SIZE;
+            if next == self.rx_tail {
+                break;
+            }
+            self.rx_buf[self.rx_head] = byte;
+            self.rx_head = next;
+        }
+
+        /* The Endpoint Answers NAK After Each Packet, Only Take The Next One If It Fits */
+        if self.rx_free() >= DATA_SIZE as usize {
+            usb.ep_set_stat_rx(ep, usb::EpStat::Valid);
+        } else {
+            self.rx_paused = true;
+        }
+    }
+
+    fn ep_in(&mut self, _usb: &usb::Usb, _ep: u8) {}
+}
+
+impl serial::Serial for Cdc {
+    /* Data Is Dropped While No Terminal Has The Port Open */
+    fn write(&self, buf: &[u8]) {
+        if !self.get_connected() {
+            return;
+        }
+
+        for chunk in buf.chunks(DATA_SIZE as usize) {
+            if !self.tx_wait() {
+                return;
+            }
+            self.usb.ep_write(EP_DATA, chunk);
+        }
+
+        /* A Full Last Packet Does Not End The Transfer, Follow It With A Zero Length Packet */
+        if !buf.is_empty() && buf.len() % (DATA_SIZE as usize) == 0 && self.tx_wait() {
+            self.usb.ep_write(EP_DATA, &[]);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use core::ptr;
+
+/* Cyclic Redundancy Check Calculation Unit (CRC) - Reference Manual pg 367 */
+pub struct Crc {
+    dr:         *mut u32,       // Data Register
+    cr:         *mut u32,       // Control Register
+    init:       *mut u32,       // Initial CRC Value
+    pol:        *mut u32,       // Polynomial
+    params:     Params          // Output Reflection And Final XOR Are Applied On Read
+}
+
+/* Same Calculation As Crc Done Bit By Bit, Used Where The Peripheral Is Not Available And To Cross Check It */
+pub struct SoftCrc {
+    params:     Params,
+    value:      u32
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Width {
+    Bits7,
+    Bits8,
+    Bits16,
+    Bits32
+}
+
+/* CRC Model Parameters, As In The Catalogue Of Parametrised CRC Algorithms */
+#[derive(Clone, Copy)]
+pub struct Params {
+    pub width:      Width,
+    pub poly:       u32,            // Polynomial Without The Top Bit, Normal Form
+    pub init:       u32,            // Register Value Before The First Byte
+    pub refin:      bool,           // Reflect Each Input Byte
+    pub refout:     bool,           // Reflect The Register Before The Final XOR
+    pub xorout:     u32             // XOR Applied To The Result
+}
+
+/* Presets, Each Gives The Check Value For The Nine Bytes "123456789" */
+pub const CRC32_ISO_HDLC: Params = Params {     // Check 0xCBF43926, Ethernet / Zip / PNG
+    width:      Width::Bits32,
+    poly:       0x04C11DB7,
+    init:       0xFFFFFFFF,
+    refin:      true,
+    refout:     true,
+    xorout:     0xFFFFFFFF
+};
+
+pub const CRC16_MODBUS: Params = Params {       // Check 0x4B37
+    width:      Width::Bits16,
+    poly:       0x8005,
+    init:       0xFFFF,
+    refin:      true,
+    refout:     true,
+    xorout:     0x0000
+};
+
+pub const CRC8_SMBUS: Params = Params {         // Check 0xF4
+    width:      Width::Bits8,
+    poly:       0x07,
+    init:       0x00,
+    refin:      false,
+    refout:     false,
+    xorout:     0x00
+};
+
+/* Register Offsets */
+const DR:                   u32 = 0x00;
+const CR:                   u32 = 0x08;
+const INIT:                 u32 = 0x10;
+const POL:                  u32 = 0x14;
+
+/* Control Register */
+const CR_RESET:             u32 = 1 << 0;   // Load INIT Into The Data Register
+const CR_POLYSIZE:          u32 = 3;
+const CR_REV_IN_BYTE:       u32 = 1 << 5;   // Bit Reversal Done Byte By Byte
+const CR_REV_OUT:           u32 = 1 << 7;
+
+/* Clock Registers Used By clock_init */
+const RCC_AHB1ENR:          u32 = 0x48;
+const RCC_CRCEN:            u32 = 1 << 12;
+
+impl Width {
+    pub fn get_bits(&self) -> u32 {
+        return match self {
+            Width::Bits7 => 7,
+            Width::Bits8 => 8,
+            Width::Bits16 => 16,
+            Width::Bits32 => 32
+        };
+    }
+
+    pub fn get_mask(&self) -> u32 {
+        return u32::MAX >> (32 - self.get_bits());
+    }
+
+    /* POLYSIZE Field Encoding */
+    fn polysize(&self) -> u32 {
+        return match self {
+            Width::Bits32 => 0,
+            Width::Bits16 => 1,
+            Width::Bits8 => 2,
+            Width::Bits7 => 3
+        };
+    }
+}
+
+impl Crc {
+    pub fn init(base: u32) -> Crc {
+        return Crc {
+            dr:         (base + DR) as *mut u32,
+            cr:         (base + CR) as *mut u32,
+            init:       (base + INIT) as *mut u32,
+            pol:        (base + POL) as *mut u32,
+            params:     CRC32_ISO_HDLC
+        };
+    }
+
+    /* Program The Model And Start A New Calculation */
+    pub fn open(&mut self, params: Params) {
+        let mut cr = params.width.polysize() << CR_POLYSIZE;
+        if params.refin {
+            cr |= CR_REV_IN_BYTE;
+        }
+        if params.refout {
+            cr |= CR_REV_OUT;
+        }
+
+        self.params = params;
+        write(self.cr, cr);
+        write(self.pol, params.poly & params.width.get_mask());
+        write(self.init, params.init & params.width.get_mask());
+        self.reset();
+    }
+
+    /* Start A New Calculation With The Current Model */
+    pub fn reset(&self) {
+        write(self.cr, read(self.cr) | CR_RESET);
+    }
+
+    /* Whole Words Go In Big Endian So Bytes Are Taken In Memory Order */
+    pub fn update(&self, data: &[u8]) {
+        let words = data.len() - (data.len() % 4);
+        for chunk in data[..words].chunks(4) {
+            write(self.dr, u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        }
+        for &byte in data[words..].iter() {
+            unsafe { ptr::write_volatile(self.dr as *mut u8, byte) };
+        }
+    }
+
+    /* Result So Far, The Calculation Can Carry On */
+    pub fn get_value(&self) -> u32 {
+        return (read(self.dr) ^ self.params.xorout) & self.params.width.get_mask();
+    }
+
+    /* One Shot Calculation Of A Whole Buffer */
+    pub fn calculate(&mut self, params: Params, data: &[u8]) -> u32 {
+        self.open(params);
+        self.update(data);
+        return self.get_value();
+    }
+}
+
+impl SoftCrc {
+    pub const fn init(params: Params) -> SoftCrc {
+        return SoftCrc {
+            params,
+            value:      params.init
+        };
+    }
+
+    pub fn reset(&mut self) {
+        self.value = self.params.init;
+    }
+
+    /* Most Significant Bit First Through The Register, Input Bytes Reflected First If refin */
+    pub fn update(&mut self, data: &[u8]) {
+        let bits = self.params.width.get_bits();
+        let mask = self.params.width.get_mask();
+        let top = 1 << (bits - 1);
+
+        for &byte in data.iter() {
+            let byte = if self.params.refin { byte.reverse_bits() } else { byte };
+            for i in (0..8).rev() {
+                let feedback = ((self.value & top) != 0) ^ (((byte >> i) & 1) != 0);
+                self.value = (self.value << 1) & mask;
+                if feedback {
+                    self.value ^= self.params.poly & mask;
+                }
+            }
+        }
+    }
+
+    pub fn get_value(&self) -> u32 {
+        let bits = self.params.width.get_bits();
+        let mut value = self.value;
+        if self.params.refout {
+            value = value.reverse_bits() >> (32 - bits);
+        }
+        return (value ^ self.params.xorout) & self.params.width.get_mask();
+    }
+}
+
+/* One Shot Software Calculation Of A Whole Buffer */
+pub fn calculate(params: Params, data: &[u8]) -> u32 {
+    let mut crc = SoftCrc::init(params);
+    crc.update(data);
+    return crc.get_value();
+}
+
+pub fn clock_init(rcc_base: u32) {
+    let rcc_ahb1enr = (rcc_base + RCC_AHB1ENR) as *mut u32;
+    write(rcc_ahb1enr, read(rcc_ahb1enr) | RCC_CRCEN);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    fn params(width: Width, poly: u32, init: u32, reflect: bool, xorout: u32) -> Params {
+        return Params { width, poly, init, refin: reflect, refout: reflect, xorout };
+    }
+
+    #[test]
+    fn presets_give_their_check_values() {
+        assert_eq!(calculate(CRC32_ISO_HDLC, CHECK), 0xCBF43926);
+        assert_eq!(calculate(CRC16_MODBUS, CHECK), 0x4B37);
+        assert_eq!(calculate(CRC8_SMBUS, CHECK), 0xF4);
+    }
+
+    #[test]
+    fn catalogue_check_values() {
+        assert_eq!(calculate(params(Width::Bits32, 0x04C11DB7, 0xFFFFFFFF, false, 0xFFFFFFFF), CHECK), 0xFC891918);  // CRC-32/BZIP2
+        assert_eq!(calculate(params(Width::Bits32, 0x1EDC6F41, 0xFFFFFFFF, true, 0xFFFFFFFF), CHECK), 0xE3069283);   // CRC-32/ISCSI
+        assert_eq!(calculate(params(Width::Bits16, 0x1021, 0x0000, false, 0x0000), CHECK), 0x31C3);                  // CRC-16/XMODEM
+        assert_eq!(calculate(params(Width::Bits16, 0x1021, 0xFFFF, false, 0x0000), CHECK), 0x29B1);                  // CRC-16/IBM-3740
+        assert_eq!(calculate(params(Width::Bits16, 0x1021, 0x0000, true, 0x0000), CHECK), 0x2189);                   // CRC-16/KERMIT
+        assert_eq!(calculate(params(Width::Bits8, 0x31, 0x00, true, 0x00), CHECK), 0xA1);                           // CRC-8/MAXIM-DOW
+        assert_eq!(calculate(params(Width::Bits7, 0x09, 0x00, false, 0x00), CHECK), 0x75);                          // CRC-7/MMC
+    }
+
+    #[test]
+    fn updates_in_pieces_match_one_shot() {
+        let mut crc = SoftCrc::init(CRC32_ISO_HDLC);
+        crc.update(&CHECK[..4]);
+        crc.update(&CHECK[4..]);
+        assert_eq!(crc.get_value(), 0xCBF43926);
+
+        crc.reset();
+        assert_eq!(crc.get_value(), 0);
+        crc.update(CHECK);
+        assert_eq!(crc.get_value(), 0xCBF43926);
+    }
+}
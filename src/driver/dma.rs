@@ -0,0 +1,138 @@
+use core::ptr;
+
+/* Direct Memory Access Channel (DMA) With Its DMAMUX Request Line - Reference Manual pg 509 */
+pub struct Dma {
+    isr:        *mut u32,       // Interrupt Status Register
+    ifcr:       *mut u32,       // Interrupt Flag Clear Register
+    ccr:        *mut u32,       // Channel Configuration Register
+    cndtr:      *mut u32,       // Channel Number Of Data To Transfer Register
+    cpar:       *mut u32,       // Channel Peripheral Address Register
+    cm0ar:      *mut u32,       // Channel Memory 0 Address Register
+    ccr_mux:    *mut u32,       // DMAMUX Channel Configuration Register
+    shift:      u32             // Position Of The Channel Flags In ISR / IFCR
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dir {
+    PeriphToMem,
+    MemToPeriph
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Size {
+    Byte,
+    HalfWord,
+    Word
+}
+
+/* Register Offsets */
+const ISR:                  u32 = 0x00;
+const IFCR:                 u32 = 0x04;
+const CCR:                  u32 = 0x08;
+const CNDTR:                u32 = 0x0C;
+const CPAR:                 u32 = 0x10;
+const CM0AR:                u32 = 0x14;
+const CHANNEL_STRIDE:       u32 = 0x14;
+const MUX_STRIDE:           u32 = 0x04;
+
+/* Channel Configuration Register */
+const CCR_EN:               u32 = 1 << 0;
+const CCR_DIR:              u32 = 1 << 4;   // Read From Memory
+const CCR_MINC:             u32 = 1 << 7;
+const CCR_PSIZE:            u32 = 8;
+const CCR_MSIZE:            u32 = 10;
+const CCR_PL_HIGH:          u32 = 2 << 12;
+
+/* Interrupt Flags, Four Per Channel */
+const IFCR_CGIF:            u32 = 1 << 0;
+const ISR_TCIF:             u32 = 1 << 1;
+const ISR_TEIF:             u32 = 1 << 3;
+const FLAG_MASK:            u32 = 0xF;
+
+/* DMAMUX Channel Configuration Register */
+const MUX_DMAREQ_ID:        u32 = 0x7F;
+
+/* Largest Number Of Data Items In A Single Transfer */
+pub const MAX_COUNT:        u32 = 0xFFFF;
+
+/* Clock Registers Used By clock_init */
+const RCC_AHB1ENR:          u32 = 0x48;
+const RCC_DMA1EN:           u32 = 1 << 0;
+const RCC_DMA2EN:           u32 = 1 << 1;
+const RCC_DMAMUX1EN:        u32 = 1 << 2;
+
+impl Dma {
+    /* Channel Is 1 - 8, DMA2 Channels Use DMAMUX Channels 8 - 15, Pass DMAMUX1_BASE + 0x20 For Them */
+    pub fn init(base: u32, mux_base: u32, channel: u32) -> Dma {
+        let index = channel - 1;
+        return Dma {
+            isr:        (base + ISR) as *mut u32,
+            ifcr:       (base + IFCR) as *mut u32,
+            ccr:        (base + CCR + index * CHANNEL_STRIDE) as *mut u32,
+            cndtr:      (base + CNDTR + index * CHANNEL_STRIDE) as *mut u32,
+            cpar:       (base + CPAR + index * CHANNEL_STRIDE) as *mut u32,
+            cm0ar:      (base + CM0AR + index * CHANNEL_STRIDE) as *mut u32,
+            ccr_mux:    (mux_base + index * MUX_STRIDE) as *mut u32,
+            shift:      index * 4
+        };
+    }
+
+    /* Start A Transfer Of count Items, The Peripheral Address Is Fixed And The Memory Address Increments */
+    pub fn start(&self, request: u32, dir: Dir, periph: u32, mem: u32, count: u32, size: Size) {
+        self.close();
+        write(self.ccr_mux, request & MUX_DMAREQ_ID);
+        write(self.cpar, periph);
+        write(self.cm0ar, mem);
+        write(self.cndtr, count & MAX_COUNT);
+
+        let mut ccr = CCR_MINC | CCR_PL_HIGH | ((size as u32) << CCR_PSIZE) | ((size as u32) << CCR_MSIZE);
+        if dir == Dir::MemToPeriph {
+            ccr |= CCR_DIR;
+        }
+        write(self.ccr, ccr);
+        write(self.ccr, ccr | CCR_EN);
+    }
+
+    pub fn close(&self) {
+        write(self.ccr, read(self.ccr) & !CCR_EN);
+        write(self.ifcr, IFCR_CGIF << self.shift);
+    }
+
+    pub fn get_complete(&self) -> bool {
+        return (read(self.isr) & (ISR_TCIF << self.shift)) != 0;
+    }
+
+    pub fn get_error(&self) -> bool {
+        return (read(self.isr) & (ISR_TEIF << self.shift)) != 0;
+    }
+
+    /* Data Items Left To Transfer */
+    pub fn get_remaining(&self) -> u32 {
+        return read(self.cndtr) & MAX_COUNT;
+    }
+
+    pub fn clr_flags(&self) {
+        write(self.ifcr, FLAG_MASK << self.shift);
+    }
+
+    /* Block Until The Transfer Ends, True If It Completed Without A Transfer Error */
+    pub fn wait(&self) -> bool {
+        while !self.get_complete() && !self.get_error() {}
+        let ok = !self.get_error();
+        self.close();
+        return ok;
+    }
+}
+
+pub fn clock_init(rcc_base: u32) {
+    let rcc_ahb1enr = (rcc_base + RCC_AHB1ENR) as *mut u32;
+    write(rcc_ahb1enr, read(rcc_ahb1enr) | RCC_DMA1EN | RCC_DMA2EN | RCC_DMAMUX1EN);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
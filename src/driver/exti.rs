@@ -0,0 +1,115 @@
+use core::ptr;
+
+/* Extended Interrupts And Events Controller (EXTI), GPIO Lines 0 - 15 */
+/* Line n Takes Pin n Of One GPIO Port, Chosen In EXTICR */
+pub struct Exti {
+    rtsr:       *mut u32,       // Rising Trigger Selection Register 1
+    ftsr:       *mut u32,       // Falling Trigger Selection Register 1
+    swier:      *mut u32,       // Software Interrupt Event Register 1
+    rpr:        *mut u32,       // Rising Edge Pending Register 1
+    fpr:        *mut u32,       // Falling Edge Pending Register 1
+    exticr:     *mut u32,       // External Interrupt Selection Registers 1 - 4
+    imr:        *mut u32        // CPU Wakeup With Interrupt Mask Register 1
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Port {
+    PortA = 0,
+    PortB,
+    PortC,
+    PortD,
+    PortE,
+    PortF,
+    PortG,
+    PortH
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Both
+}
+
+/* Register Offsets */
+const RTSR1:                u32 = 0x00;
+const FTSR1:                u32 = 0x04;
+const SWIER1:               u32 = 0x08;
+const RPR1:                 u32 = 0x0C;
+const FPR1:                 u32 = 0x10;
+const EXTICR1:              u32 = 0x60;
+const IMR1:                 u32 = 0x80;
+
+/* External Interrupt Selection, Four Lines Per Register, Eight Bits Each */
+const EXTICR_LINES:         u32 = 4;
+const EXTICR_WIDTH:         u32 = 8;
+const EXTICR_MASK:          u32 = 0xFF;
+
+/* GPIO Lines */
+pub const LINES:            u32 = 16;
+
+impl Exti {
+    pub fn init(base: u32) -> Exti {
+        return Exti {
+            rtsr:       (base + RTSR1) as *mut u32,
+            ftsr:       (base + FTSR1) as *mut u32,
+            swier:      (base + SWIER1) as *mut u32,
+            rpr:        (base + RPR1) as *mut u32,
+            fpr:        (base + FPR1) as *mut u32,
+            exticr:     (base + EXTICR1) as *mut u32,
+            imr:        (base + IMR1) as *mut u32
+        };
+    }
+
+    /* Connect Pin line Of port To The Line And Pick The Edges That Trigger It, The Line Stays Masked */
+    pub fn open(&self, line: u32, port: Port, edge: Edge) {
+        let line = line % LINES;
+        self.set_mask(line, false);
+
+        let exticr = unsafe { self.exticr.add((line / EXTICR_LINES) as usize) };
+        let shift = (line % EXTICR_LINES) * EXTICR_WIDTH;
+        write(exticr, (read(exticr) & !(EXTICR_MASK << shift)) | ((port as u32) << shift));
+
+        set_bit(self.rtsr, line, edge != Edge::Falling);
+        set_bit(self.ftsr, line, edge != Edge::Rising);
+        self.clr_pending(line);
+    }
+
+    /* Unmasked Lines Raise Their EXTIn_IRQ */
+    pub fn set_mask(&self, line: u32, enable: bool) {
+        set_bit(self.imr, line % LINES, enable);
+    }
+
+    /* Either Edge Seen Since The Last clr_pending */
+    pub fn get_pending(&self, line: u32) -> bool {
+        let bit = 1 << (line % LINES);
+        return ((read(self.rpr) | read(self.fpr)) & bit) != 0;
+    }
+
+    pub fn clr_pending(&self, line: u32) {
+        let bit = 1 << (line % LINES);
+        write(self.rpr, bit);
+        write(self.fpr, bit);
+    }
+
+    /* Trigger The Line From Software As If The Rising Edge Had Been Seen */
+    pub fn trigger(&self, line: u32) {
+        write(self.swier, 1 << (line % LINES));
+    }
+}
+
+fn set_bit(reg: *mut u32, bit: u32, val: bool) {
+    if val {
+        write(reg, read(reg) | (1 << bit));
+    } else {
+        write(reg, read(reg) & !(1 << bit));
+    }
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
@@ -0,0 +1,358 @@
+use core::ptr;
+
+/* FD Controller Area Network (FDCAN) - Reference Manual pg 1789 */
+/* Classic CAN Frames With 11 Bit Identifiers, Everything Received Goes To Rx FIFO 0, Sending Uses The Tx FIFO */
+/* The Message RAM Layout Is Fixed On This Part, Only The Element Offsets Below Are Needed */
+#[derive(Clone, Copy)]
+pub struct Fdcan {
+    cccr:       *mut u32,       // CC Control Register
+    nbtp:       *mut u32,       // Nominal Bit Timing And Prescaler Register
+    ecr:        *mut u32,       // Error Counter Register
+    psr:        *mut u32,       // Protocol Status Register
+    ir:         *mut u32,       // Interrupt Register
+    ie:         *mut u32,       // Interrupt Enable Register
+    ile:        *mut u32,       // Interrupt Line Enable Register
+    rxgfc:      *mut u32,       // Global Filter Configuration Register
+    rxf0s:      *mut u32,       // Rx FIFO 0 Status Register
+    rxf0a:      *mut u32,       // Rx FIFO 0 Acknowledge Register
+    txbc:       *mut u32,       // Tx Buffer Configuration Register
+    txfqs:      *mut u32,       // Tx FIFO/Queue Status Register
+    txbar:      *mut u32,       // Tx Buffer Add Request Register
+    ram:        *mut u32        // Message RAM Of This Instance
+}
+
+/* One Classic CAN Frame */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub id:         u16,                    // 11 Bit Identifier
+    pub rtr:        bool,                   // Remote Request, No Data
+    pub len:        u8,                     // Data Length, 0 - 8
+    pub data:       [u8; 8]
+}
+
+/* Nominal Bit Timing In Time Quanta, Not Yet Less One As The Register Takes Them */
+#[derive(Clone, Copy, PartialEq)]
+pub struct BitTiming {
+    pub prescaler:  u32,                    // Kernel Clocks Per Time Quantum
+    pub seg1:       u32,                    // Propagation Plus Phase 1
+    pub seg2:       u32,                    // Phase 2
+    pub sjw:        u32                     // Resynchronisation Jump Width
+}
+
+/* Kernel Clock Of The Peripheral */
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClockSource {
+    Hse,
+    Pllq,
+    Pllsai1p
+}
+
+/* Register Offsets */
+const CCCR:                 u32 = 0x18;
+const NBTP:                 u32 = 0x1C;
+const ECR:                  u32 = 0x40;
+const PSR:                  u32 = 0x44;
+const IR:                   u32 = 0x50;
+const IE:                   u32 = 0x54;
+const ILE:                  u32 = 0x5C;
+const RXGFC:                u32 = 0x80;
+const RXF0S:                u32 = 0x90;
+const RXF0A:                u32 = 0x94;
+const TXBC:                 u32 = 0xC0;
+const TXFQS:                u32 = 0xC4;
+const TXBAR:                u32 = 0xCC;
+
+/* CC Control Register */
+const CCCR_INIT:            u32 = 1 << 0;   // Initialisation
+const CCCR_CCE:             u32 = 1 << 1;   // Configuration Change Enable
+const CCCR_DAR:             u32 = 1 << 6;   // Disable Automatic Retransmission
+const CCCR_FDOE:            u32 = 1 << 8;   // FD Operation Enable
+const CCCR_BRSE:            u32 = 1 << 9;   // Bit Rate Switching Enable
+
+/* Nominal Bit Timing And Prescaler Register, Fields Hold The Value Less One */
+const NBTP_NSJW_SHIFT:      u32 = 25;
+const NBTP_NBRP_SHIFT:      u32 = 16;
+const NBTP_NTSEG1_SHIFT:    u32 = 8;
+const NBTP_NTSEG2_SHIFT:    u32 = 0;
+
+/* Error Counter Register */
+const ECR_TEC:              u32 = 0x00FF;   // Transmit Error Counter
+const ECR_REC:              u32 = 0x7F00;   // Receive Error Counter
+const ECR_REC_SHIFT:        u32 = 8;
+
+/* Protocol Status Register */
+const PSR_EP:               u32 = 1 << 5;   // Error Passive
+const PSR_BO:               u32 = 1 << 7;   // Bus Off
+
+/* Interrupt Register And Interrupt Enable Register */
+pub const IR_RF0N:          u32 = 1 << 0;   // Rx FIFO 0 New Message
+pub const IR_RF0L:          u32 = 1 << 2;   // Rx FIFO 0 Message Lost
+pub const IR_TC:            u32 = 1 << 7;   // Transmission Completed
+pub const IR_TFE:           u32 = 1 << 9;   // Tx FIFO Empty
+pub const IR_EP:            u32 = 1 << 17;  // Error Passive
+pub const IR_EW:            u32 = 1 << 18;  // Warning Status
+pub const IR_BO:            u32 = 1 << 19;  // Bus Off Status
+
+/* Interrupt Line Enable Register */
+const ILE_EINT0:            u32 = 1 << 0;   // Interrupt Line 0
+
+/* Global Filter Configuration Register */
+const RXGFC_RRFE:           u32 = 1 << 0;   // Reject Remote Frames Extended
+const RXGFC_ANFE_REJECT:    u32 = 2 << 2;   // Non Matching Extended Frames Rejected
+const RXGFC_ANFS_FIFO0:     u32 = 0 << 4;   // Non Matching Standard Frames To FIFO 0
+
+/* Rx FIFO 0 Status Register */
+const RXF0S_F0FL:           u32 = 0x0000000F;   // Fill Level
+const RXF0S_F0GI:           u32 = 0x00000300;   // Get Index
+const RXF0S_F0GI_SHIFT:     u32 = 8;
+
+/* Tx Buffer Configuration Register */
+const TXBC_TFQM:            u32 = 1 << 24;  // Queue Mode, FIFO When Clear
+
+/* Tx FIFO/Queue Status Register */
+const TXFQS_TFQPI:          u32 = 0x00030000;   // Put Index
+const TXFQS_TFQPI_SHIFT:    u32 = 16;
+const TXFQS_TFQF:           u32 = 1 << 21;      // Tx FIFO Full
+
+/* Message RAM, Word Offsets Of Each Section And The Size Of One Element */
+const RAM_WORDS:            usize = 212;
+const RAM_RXF0:             usize = 44;
+const RAM_TXB:              usize = 158;
+const ELEMENT_WORDS:        usize = 18;
+
+/* Rx And Tx Element Header */
+const ELEM_RTR:             u32 = 1 << 29;  // Remote Frame
+const ELEM_STD_ID_SHIFT:    u32 = 18;
+const ELEM_STD_ID:          u32 = 0x7FF;
+const ELEM_DLC_SHIFT:       u32 = 16;
+const ELEM_DLC:             u32 = 0xF;
+
+/* Bit Timing Search, Time Quanta Per Bit And Sample Point In Eighths */
+const TQ_MAX:               u32 = 25;
+const TQ_MIN:               u32 = 8;
+const PRESCALER_MAX:        u32 = 512;
+const SAMPLE_EIGHTHS:       u32 = 7;
+const SJW_MAX:              u32 = 4;
+
+/* Polls Of INIT Before Giving Up, It Follows Within A Few Bit Times */
+const INIT_TIMEOUT:         u32 = 100000;
+
+/* Clock Registers Used By clock_init */
+const RCC_CCIPR1:           u32 = 0x88;
+const RCC_APB1ENR2:         u32 = 0x5C;
+const RCC_FDCANSEL:         u32 = 0x03000000;
+const RCC_FDCANSEL_SHIFT:   u32 = 24;
+const RCC_FDCAN1EN:         u32 = 1 << 9;
+
+impl Frame {
+    /* Data Frame, Anything Past 8 Bytes Is Dropped */
+    pub fn init(id: u16, data: &[u8]) -> Frame {
+        let len = core::cmp::min(data.len(), 8);
+        let mut frame = Frame {
+            id:     id & ELEM_STD_ID as u16,
+            rtr:    false,
+            len:    len as u8,
+            data:   [0; 8]
+        };
+        frame.data[..len].copy_from_slice(&data[..len]);
+        return frame;
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        return &self.data[..self.len as usize];
+    }
+}
+
+impl BitTiming {
+    /* Most Time Quanta That Divide The Clock Exactly, Sampling At 87.5 % As CiA 301 Recommends */
+    pub fn init(clock: u32, bitrate: u32) -> Option<BitTiming> {
+        if bitrate == 0 {
+            return None;
+        }
+        for tq in (TQ_MIN..=TQ_MAX).rev() {
+            let quanta = bitrate * tq;
+            if !clock.is_multiple_of(quanta) || clock / quanta > PRESCALER_MAX {
+                continue;
+            }
+            let sample = (tq * SAMPLE_EIGHTHS + 4) / 8;
+            let seg2 = tq - sample;
+            return Some(BitTiming {
+                prescaler:  clock / quanta,
+                seg1:       sample - 1,
+                seg2,
+                sjw:        core::cmp::min(seg2, SJW_MAX)
+            });
+        }
+        return None;
+    }
+}
+
+impl Fdcan {
+    /* base Is The Peripheral, ram_base Its Message RAM */
+    pub fn init(base: u32, ram_base: u32) -> Fdcan {
+        return Fdcan {
+            cccr:       (base + CCCR) as *mut u32,
+            nbtp:       (base + NBTP) as *mut u32,
+            ecr:        (base + ECR) as *mut u32,
+            psr:        (base + PSR) as *mut u32,
+            ir:         (base + IR) as *mut u32,
+            ie:         (base + IE) as *mut u32,
+            ile:        (base + ILE) as *mut u32,
+            rxgfc:      (base + RXGFC) as *mut u32,
+            rxf0s:      (base + RXF0S) as *mut u32,
+            rxf0a:      (base + RXF0A) as *mut u32,
+            txbc:       (base + TXBC) as *mut u32,
+            txfqs:      (base + TXFQS) as *mut u32,
+            txbar:      (base + TXBAR) as *mut u32,
+            ram:        ram_base as *mut u32
+        };
+    }
+
+    /* Classic CAN At bitrate From A clock Hz Kernel Clock, False When No Bit Timing Fits Or The Core Never Left INIT */
+    pub fn open(&self, clock: u32, bitrate: u32) -> bool {
+        let timing = match BitTiming::init(clock, bitrate) {
+            Some(timing) => timing,
+            None => return false
+        };
+
+        if !self.enter_init() {
+            return false;
+        }
+        write(self.cccr, read(self.cccr) | CCCR_CCE);
+        write(self.cccr, read(self.cccr) & !(CCCR_FDOE | CCCR_BRSE | CCCR_DAR));
+        write(self.nbtp, ((timing.sjw - 1) << NBTP_NSJW_SHIFT) | ((timing.prescaler - 1) << NBTP_NBRP_SHIFT) |
+            ((timing.seg1 - 1) << NBTP_NTSEG1_SHIFT) | ((timing.seg2 - 1) << NBTP_NTSEG2_SHIFT));
+        write(self.rxgfc, RXGFC_ANFS_FIFO0 | RXGFC_ANFE_REJECT | RXGFC_RRFE);
+        write(self.txbc, read(self.txbc) & !TXBC_TFQM);
+
+        /* Message RAM Is Not Cleared By Reset */
+        for i in 0..RAM_WORDS {
+            write(unsafe { self.ram.add(i) }, 0);
+        }
+        write(self.ir, !0);
+        return self.leave_init();
+    }
+
+    pub fn close(&self) {
+        self.enter_init();
+    }
+
+    /* Queue A Frame, False While The Tx FIFO Is Full */
+    pub fn send(&self, frame: &Frame) -> bool {
+        let txfqs = read(self.txfqs);
+        if (txfqs & TXFQS_TFQF) != 0 {
+            return false;
+        }
+        let index = ((txfqs & TXFQS_TFQPI) >> TXFQS_TFQPI_SHIFT) as usize;
+        let element = unsafe { self.ram.add(RAM_TXB + (index * ELEMENT_WORDS)) };
+
+        let rtr = if frame.rtr { ELEM_RTR } else { 0 };
+        let len = core::cmp::min(frame.len, 8);
+        write(element, rtr | ((frame.id as u32 & ELEM_STD_ID) << ELEM_STD_ID_SHIFT));
+        write(unsafe { element.add(1) }, (len as u32) << ELEM_DLC_SHIFT);
+        write(unsafe { element.add(2) }, u32::from_le_bytes([frame.data[0], frame.data[1], frame.data[2], frame.data[3]]));
+        write(unsafe { element.add(3) }, u32::from_le_bytes([frame.data[4], frame.data[5], frame.data[6], frame.data[7]]));
+        write(self.txbar, 1 << index);
+        return true;
+    }
+
+    /* Oldest Frame In Rx FIFO 0 */
+    pub fn recv(&self) -> Option<Frame> {
+        let rxf0s = read(self.rxf0s);
+        if (rxf0s & RXF0S_F0FL) == 0 {
+            return None;
+        }
+        let index = ((rxf0s & RXF0S_F0GI) >> RXF0S_F0GI_SHIFT) as usize;
+        let element = unsafe { self.ram.add(RAM_RXF0 + (index * ELEMENT_WORDS)) };
+
+        let header = read(element);
+        let dlc = (read(unsafe { element.add(1) }) >> ELEM_DLC_SHIFT) & ELEM_DLC;
+        let low = read(unsafe { element.add(2) }).to_le_bytes();
+        let high = read(unsafe { element.add(3) }).to_le_bytes();
+        write(self.rxf0a, index as u32);
+
+        return Some(Frame {
+            id:     ((header >> ELEM_STD_ID_SHIFT) & ELEM_STD_ID) as u16,
+            rtr:    (header & ELEM_RTR) != 0,
+            len:    core::cmp::min(dlc, 8) as u8,
+            data:   [low[0], low[1], low[2], low[3], high[0], high[1], high[2], high[3]]
+        });
+    }
+
+    /* Interrupts Routed To Line 0 (FDCAN1_IT0) */
+    pub fn set_interrupt(&self, flags: u32) {
+        write(self.ie, read(self.ie) | flags);
+        write(self.ile, read(self.ile) | ILE_EINT0);
+    }
+
+    pub fn clr_interrupt(&self, flags: u32) {
+        write(self.ie, read(self.ie) & !flags);
+    }
+
+    pub fn get_flags(&self) -> u32 {
+        return read(self.ir);
+    }
+
+    /* Flags Are Cleared By Writing 1 */
+    pub fn clr_flags(&self, flags: u32) {
+        write(self.ir, flags);
+    }
+
+    pub fn get_tx_errors(&self) -> u32 {
+        return read(self.ecr) & ECR_TEC;
+    }
+
+    pub fn get_rx_errors(&self) -> u32 {
+        return (read(self.ecr) & ECR_REC) >> ECR_REC_SHIFT;
+    }
+
+    pub fn is_error_passive(&self) -> bool {
+        return (read(self.psr) & PSR_EP) != 0;
+    }
+
+    pub fn is_bus_off(&self) -> bool {
+        return (read(self.psr) & PSR_BO) != 0;
+    }
+
+    /* Bus Off Leaves The Core In INIT, Clearing It Rejoins After 129 x 11 Recessive Bits */
+    pub fn recover(&self) -> bool {
+        return self.leave_init();
+    }
+
+    fn enter_init(&self) -> bool {
+        write(self.cccr, read(self.cccr) | CCCR_INIT);
+        for _ in 0..INIT_TIMEOUT {
+            if (read(self.cccr) & CCCR_INIT) != 0 {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    fn leave_init(&self) -> bool {
+        write(self.cccr, read(self.cccr) & !CCCR_INIT);
+        for _ in 0..INIT_TIMEOUT {
+            if (read(self.cccr) & CCCR_INIT) == 0 {
+                return true;
+            }
+        }
+        return false;
+    }
+}
+
+/* Select The Kernel Clock, Which Has To Be Running, And Enable The Peripheral Clock */
+pub fn clock_init(rcc_base: u32, source: ClockSource) {
+    let rcc_ccipr1 = (rcc_base + RCC_CCIPR1) as *mut u32;
+    let rcc_apb1enr2 = (rcc_base + RCC_APB1ENR2) as *mut u32;
+
+    write(rcc_ccipr1, (read(rcc_ccipr1) & !RCC_FDCANSEL) | ((source as u32) << RCC_FDCANSEL_SHIFT));
+    write(rcc_apb1enr2, read(rcc_apb1enr2) | RCC_FDCAN1EN);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
@@ -0,0 +1,232 @@
+use core::ptr;
+
+/* Global TrustZone Controller (GTZC) - Reference Manual pg 159 */
+/* Every Register Is Only Writable From The Secure World Through The Secure Aliases */
+
+/* TrustZone Security Controller, Secure And Privileged Attributes Of Each Peripheral */
+pub struct Tzsc {
+    cr:         *mut u32,       // Control Register
+    seccfgr:    *mut u32,       // Secure Configuration Registers 1 - 2
+    privcfgr:   *mut u32        // Privilege Configuration Registers 1 - 2
+}
+
+/* TrustZone Illegal Access Controller, Flags Accesses Blocked By The Security Settings */
+pub struct Tzic {
+    ier:        *mut u32,       // Interrupt Enable Registers 1 - 3
+    sr:         *mut u32,       // Status Registers 1 - 3
+    fcr:        *mut u32        // Flag Clear Registers 1 - 3
+}
+
+/* Block Based Memory Protection Controller, Security Of Each 256 Byte Block Of An SRAM */
+pub struct Mpcbb {
+    cr:         *mut u32,       // Control Register
+    vctr:       *mut u32,       // Vector Registers, 32 Blocks Each
+    blocks:     u32             // Number Of Blocks In The SRAM
+}
+
+/* Peripherals Under TZSC Control, Bit Position In SECCFGR1 Then SECCFGR2 */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Periph {
+    Tim2 = 0,
+    Tim3,
+    Tim4,
+    Tim5,
+    Tim6,
+    Tim7,
+    Wwdg,
+    Iwdg,
+    Spi2,
+    Spi3,
+    Usart2,
+    Usart3,
+    Uart4,
+    Uart5,
+    I2c1,
+    I2c2,
+    I2c3,
+    Crs,
+    Dac1,
+    Opamp,
+    Lptim1,
+    Lpuart1,
+    I2c4,
+    Lptim2,
+    Lptim3,
+    Fdcan1,
+    UsbFs,
+    Ucpd1,
+    Vrefbuf,
+    Comp,
+    Tim1,
+    Spi1,
+    Tim8 = 32,
+    Usart1,
+    Tim15,
+    Tim16,
+    Tim17,
+    Sai1,
+    Sai2,
+    Dfsdm1,
+    Crc,
+    Tsc,
+    Icache,
+    Adc,
+    Aes,
+    Hash,
+    Rng,
+    Pka,
+    Sdmmc1,
+    Fsmc,
+    Octospi1
+}
+
+/* TZSC Register Offsets */
+const TZSC_CR:              u32 = 0x00;
+const TZSC_SECCFGR1:        u32 = 0x10;
+const TZSC_PRIVCFGR1:       u32 = 0x20;
+
+/* TZIC Register Offsets */
+const TZIC_IER1:            u32 = 0x00;
+const TZIC_SR1:             u32 = 0x10;
+const TZIC_FCR1:            u32 = 0x20;
+const TZIC_REGISTERS:       usize = 3;
+
+/* MPCBB Register Offsets */
+const MPCBB_CR:             u32 = 0x000;
+const MPCBB_VCTR0:          u32 = 0x100;
+
+/* Lock Bit Of TZSC_CR And MPCBB_CR, Cleared Only By A Reset */
+const CR_LCK:               u32 = 1 << 0;
+
+/* MPCBB Block Size */
+pub const BLOCK_SIZE:       u32 = 256;
+
+/* Clock Registers Used By clock_init */
+const RCC_AHB1ENR:          u32 = 0x48;
+const RCC_GTZCEN:           u32 = 1 << 22;
+
+impl Tzsc {
+    pub fn init(base: u32) -> Tzsc {
+        return Tzsc {
+            cr:         (base + TZSC_CR) as *mut u32,
+            seccfgr:    (base + TZSC_SECCFGR1) as *mut u32,
+            privcfgr:   (base + TZSC_PRIVCFGR1) as *mut u32
+        };
+    }
+
+    /* Secure Peripherals Only Answer Secure Accesses Through Their Secure Alias */
+    pub fn set_secure(&self, periph: Periph, secure: bool) {
+        set_bit(self.seccfgr, periph as u32, secure);
+    }
+
+    pub fn get_secure(&self, periph: Periph) -> bool {
+        return get_bit(self.seccfgr, periph as u32);
+    }
+
+    pub fn set_privileged(&self, periph: Periph, privileged: bool) {
+        set_bit(self.privcfgr, periph as u32, privileged);
+    }
+
+    /* Freeze The Configuration Until The Next Reset */
+    pub fn lock(&self) {
+        write(self.cr, read(self.cr) | CR_LCK);
+    }
+}
+
+impl Tzic {
+    pub fn init(base: u32) -> Tzic {
+        return Tzic {
+            ier:        (base + TZIC_IER1) as *mut u32,
+            sr:         (base + TZIC_SR1) as *mut u32,
+            fcr:        (base + TZIC_FCR1) as *mut u32
+        };
+    }
+
+    /* Raise GTZC_IRQ On Any Illegal Access */
+    pub fn set_interrupt(&self) {
+        for i in 0..TZIC_REGISTERS {
+            write(unsafe { self.ier.add(i) }, u32::MAX);
+        }
+    }
+
+    pub fn clr_interrupt(&self) {
+        for i in 0..TZIC_REGISTERS {
+            write(unsafe { self.ier.add(i) }, 0);
+        }
+    }
+
+    /* First Pending Illegal Access Source, Numbered Register By Register As In The Reference Manual Table */
+    pub fn get_illegal(&self) -> Option<u32> {
+        for i in 0..TZIC_REGISTERS {
+            let sr = read(unsafe { self.sr.add(i) });
+            if sr != 0 {
+                return Some((i as u32) * 32 + sr.trailing_zeros());
+            }
+        }
+        return None;
+    }
+
+    pub fn clr_illegal(&self, source: u32) {
+        write(unsafe { self.fcr.add((source / 32) as usize) }, 1 << (source % 32));
+    }
+}
+
+impl Mpcbb {
+    /* size Is The Size Of The SRAM This Controller Covers In Bytes */
+    pub fn init(base: u32, size: u32) -> Mpcbb {
+        return Mpcbb {
+            cr:         (base + MPCBB_CR) as *mut u32,
+            vctr:       (base + MPCBB_VCTR0) as *mut u32,
+            blocks:     size / BLOCK_SIZE
+        };
+    }
+
+    /* Blocks Covering offset To offset + len From The Start Of The SRAM, Rounded Out To Whole Blocks */
+    pub fn set_secure(&self, offset: u32, len: u32, secure: bool) {
+        if len == 0 {
+            return;
+        }
+        let first = offset / BLOCK_SIZE;
+        let last = core::cmp::min((offset + len - 1) / BLOCK_SIZE + 1, self.blocks);
+        for block in first..last {
+            set_bit(self.vctr, block, secure);
+        }
+    }
+
+    pub fn get_secure(&self, offset: u32) -> bool {
+        return get_bit(self.vctr, offset / BLOCK_SIZE);
+    }
+
+    /* Freeze The Configuration Until The Next Reset */
+    pub fn lock(&self) {
+        write(self.cr, read(self.cr) | CR_LCK);
+    }
+}
+
+pub fn clock_init(rcc_base: u32) {
+    let rcc_ahb1enr = (rcc_base + RCC_AHB1ENR) as *mut u32;
+    write(rcc_ahb1enr, read(rcc_ahb1enr) | RCC_GTZCEN);
+}
+
+/* Bit n Of A Run Of Consecutive 32 Bit Registers */
+fn set_bit(reg: *mut u32, n: u32, val: bool) {
+    let reg = unsafe { reg.add((n / 32) as usize) };
+    if val {
+        write(reg, read(reg) | (1 << (n % 32)));
+    } else {
+        write(reg, read(reg) & !(1 << (n % 32)));
+    }
+}
+
+fn get_bit(reg: *mut u32, n: u32) -> bool {
+    let reg = unsafe { reg.add((n / 32) as usize) };
+    return (read(reg) & (1 << (n % 32))) != 0;
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
@@ -0,0 +1,341 @@
+use core::ptr;
+use super::dma;
+
+/* Hash Processor (HASH) - Reference Manual pg 1213 */
+/* SHA-1, SHA-224, SHA-256 And MD5, Plain Or HMAC, Fed By The CPU Or By DMA */
+pub struct Hash {
+    cr:         *mut u32,       // Control Register
+    din:        *mut u32,       // Data Input Register
+    str:        *mut u32,       // Start Register
+    imr:        *mut u32,       // Interrupt Enable Register
+    sr:         *mut u32,       // Status Register
+    csr:        *mut u32,       // Context Swap Registers 0 - 53
+    hr:         *mut u32        // Digest Registers 0 - 7
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+    Md5
+}
+
+/* One Hash Computation, Several Can Be In Progress And Take Turns On The Peripheral */
+/* Only Whole Blocks Reach The Hardware Before finalize, The Rest Waits In block */
+pub struct Context<'a> {
+    algo:       Algorithm,
+    key:        Option<&'a [u8]>,           // HMAC Key, None For A Plain Hash
+    started:    bool,                       // Hardware Has Been Initialised, Saved Registers Are Valid
+    block:      [u8; BLOCK_SIZE],           // Bytes Not Yet Written To The Hardware
+    block_len:  usize,
+    imr:        u32,                        // Saved Registers
+    str:        u32,
+    cr:         u32,
+    csr:        [u32; CSR_HMAC_COUNT]
+}
+
+/* Register Offsets */
+const CR:                   u32 = 0x000;
+const DIN:                  u32 = 0x004;
+const STR:                  u32 = 0x008;
+const IMR:                  u32 = 0x020;
+const SR:                   u32 = 0x024;
+const CSR:                  u32 = 0x0F8;
+const HR:                   u32 = 0x310;
+
+/* Control Register */
+const CR_INIT:              u32 = 1 << 2;   // Initialise The Digest Computation
+const CR_DMAE:              u32 = 1 << 3;   // DMA Enable
+const CR_DATATYPE_BYTE:     u32 = 2 << 4;   // Byte Swapped Input, Data Is Hashed In Memory Order
+const CR_MODE:              u32 = 1 << 6;   // HMAC Mode
+const CR_ALGO0:             u32 = 1 << 7;
+const CR_MDMAT:             u32 = 1 << 13;  // Multiple DMA Transfers, DCAL Is Not Set When The DMA Completes
+const CR_LKEY:              u32 = 1 << 16;  // HMAC Key Longer Than A Block
+const CR_ALGO1:             u32 = 1 << 18;
+
+/* Start Register */
+const STR_NBLW:             u32 = 0x1F;     // Number Of Valid Bits In The Last Word
+const STR_DCAL:             u32 = 1 << 8;   // Digest Calculation
+
+/* Status Register */
+const SR_DINIS:             u32 = 1 << 0;   // Data Input Interrupt Status, Ready For A New Block
+const SR_DCIS:              u32 = 1 << 1;   // Digest Calculation Completed
+const SR_BUSY:              u32 = 1 << 3;
+
+/* Context Swap Registers To Save, HMAC Also Keeps The Key State */
+const CSR_COUNT:            usize = 38;
+const CSR_HMAC_COUNT:       usize = 54;
+
+/* All Supported Algorithms Work On 512 Bit Blocks */
+const BLOCK_SIZE:           usize = 64;
+const BLOCK_WORDS:          u32 = (BLOCK_SIZE / 4) as u32;
+
+/* DMAMUX Request Line And Largest Whole Block DMA Transfer In Words */
+const DMA_REQUEST:          u32 = 92;
+const DMA_MAX_WORDS:        u32 = dma::MAX_COUNT - (dma::MAX_COUNT % BLOCK_WORDS);
+
+pub const MAX_DIGEST_LEN:   usize = 32;
+
+/* Clock Registers Used By clock_init */
+const RCC_AHB2ENR:          u32 = 0x4C;
+const RCC_HASHEN:           u32 = 1 << 17;
+
+impl Algorithm {
+    pub fn get_digest_len(&self) -> usize {
+        return match self {
+            Algorithm::Sha1 => 20,
+            Algorithm::Sha224 => 28,
+            Algorithm::Sha256 => 32,
+            Algorithm::Md5 => 16
+        };
+    }
+
+    fn bits(&self) -> u32 {
+        return match self {
+            Algorithm::Sha1 => 0,
+            Algorithm::Sha224 => CR_ALGO1,
+            Algorithm::Sha256 => CR_ALGO1 | CR_ALGO0,
+            Algorithm::Md5 => CR_ALGO0
+        };
+    }
+}
+
+impl<'a> Context<'a> {
+    pub fn init(algo: Algorithm) -> Context<'a> {
+        return Context {
+            algo,
+            key:        None,
+            started:    false,
+            block:      [0; BLOCK_SIZE],
+            block_len:  0,
+            imr:        0,
+            str:        0,
+            cr:         0,
+            csr:        [0; CSR_HMAC_COUNT]
+        };
+    }
+
+    pub fn init_hmac(algo: Algorithm, key: &'a [u8]) -> Context<'a> {
+        let mut ctx = Context::init(algo);
+        ctx.key = Some(key);
+        return ctx;
+    }
+
+    pub fn get_algorithm(&self) -> Algorithm {
+        return self.algo;
+    }
+
+    /* Start Over With The Same Algorithm And Key */
+    pub fn reset(&mut self) {
+        self.started = false;
+        self.block_len = 0;
+    }
+
+    /* Copy As Much Of data As Fits Into The Pending Block, Returns What Is Left */
+    fn buffer<'b>(&mut self, data: &'b [u8]) -> &'b [u8] {
+        let len = core::cmp::min(BLOCK_SIZE - self.block_len, data.len());
+        self.block[self.block_len..self.block_len + len].copy_from_slice(&data[..len]);
+        self.block_len += len;
+        return &data[len..];
+    }
+
+    fn csr_count(&self) -> usize {
+        return if self.key.is_some() { CSR_HMAC_COUNT } else { CSR_COUNT };
+    }
+}
+
+impl Hash {
+    pub fn init(base: u32) -> Hash {
+        return Hash {
+            cr:         (base + CR) as *mut u32,
+            din:        (base + DIN) as *mut u32,
+            str:        (base + STR) as *mut u32,
+            imr:        (base + IMR) as *mut u32,
+            sr:         (base + SR) as *mut u32,
+            csr:        (base + CSR) as *mut u32,
+            hr:         (base + HR) as *mut u32
+        };
+    }
+
+    pub fn get_busy(&self) -> bool {
+        return (read(self.sr) & SR_BUSY) != 0;
+    }
+
+    /* Add Message Bytes, Any Number Of Calls In Any Sizes */
+    pub fn update(&self, ctx: &mut Context, data: &[u8]) {
+        let data = ctx.buffer(data);
+        if ctx.block_len < BLOCK_SIZE {
+            return;
+        }
+
+        self.resume(ctx);
+        self.write_words(&ctx.block);
+        self.wait_input();
+        let blocks = data.len() - (data.len() % BLOCK_SIZE);
+        for block in data[..blocks].chunks(BLOCK_SIZE) {
+            self.write_words(block);
+            self.wait_input();
+        }
+        self.suspend(ctx);
+
+        ctx.block_len = 0;
+        ctx.buffer(&data[blocks..]);
+    }
+
+    /* Same As update But Whole Blocks Are Moved By DMA, Falls Back To The CPU For A Buffer Not On A Word Boundary */
+    /* Returns False On A DMA Transfer Error, The Context Is Then Lost And Must Be Reset */
+    pub fn update_dma(&self, ctx: &mut Context, dma: &dma::Dma, data: &[u8]) -> bool {
+        let data = ctx.buffer(data);
+        if ctx.block_len < BLOCK_SIZE {
+            return true;
+        }
+
+        self.resume(ctx);
+        self.write_words(&ctx.block);
+        self.wait_input();
+        ctx.block_len = 0;
+
+        let blocks = data.len() - (data.len() % BLOCK_SIZE);
+        if (data.as_ptr() as u32) % 4 != 0 {
+            for block in data[..blocks].chunks(BLOCK_SIZE) {
+                self.write_words(block);
+                self.wait_input();
+            }
+        } else {
+            write(self.cr, read(self.cr) | CR_DMAE | CR_MDMAT);
+            let mut addr = data.as_ptr() as u32;
+            let mut words = (blocks / 4) as u32;
+            while words > 0 {
+                let count = core::cmp::min(words, DMA_MAX_WORDS);
+                dma.start(DMA_REQUEST, dma::Dir::MemToPeriph, self.din as u32, addr, count, dma::Size::Word);
+                if !dma.wait() {
+                    write(self.cr, read(self.cr) & !(CR_DMAE | CR_MDMAT));
+                    ctx.reset();
+                    return false;
+                }
+                self.wait_input();
+                addr += count * 4;
+                words -= count;
+            }
+            write(self.cr, read(self.cr) & !(CR_DMAE | CR_MDMAT));
+        }
+        self.suspend(ctx);
+
+        ctx.buffer(&data[blocks..]);
+        return true;
+    }
+
+    /* Finish The Computation, Copies The Digest Into out And Returns Its Length, The Context Can Be Reused */
+    pub fn finalize(&self, ctx: &mut Context, out: &mut [u8]) -> usize {
+        self.resume(ctx);
+        self.write_last(&ctx.block[..ctx.block_len]);
+
+        /* HMAC Outer Hash Takes The Key Again */
+        if let Some(key) = ctx.key {
+            self.wait_input();
+            self.write_last(key);
+        }
+
+        while (read(self.sr) & SR_DCIS) == 0 {}
+        let len = core::cmp::min(out.len(), ctx.algo.get_digest_len());
+        for (i, chunk) in out[..len].chunks_mut(4).enumerate() {
+            let word = read(unsafe { self.hr.add(i) }).to_be_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+
+        ctx.reset();
+        return len;
+    }
+
+    /* One Shot Hash Of A Whole Message */
+    pub fn digest(&self, algo: Algorithm, data: &[u8], out: &mut [u8]) -> usize {
+        let mut ctx = Context::init(algo);
+        self.update(&mut ctx, data);
+        return self.finalize(&mut ctx, out);
+    }
+
+    /* One Shot HMAC Of A Whole Message */
+    pub fn hmac(&self, algo: Algorithm, key: &[u8], data: &[u8], out: &mut [u8]) -> usize {
+        let mut ctx = Context::init_hmac(algo, key);
+        self.update(&mut ctx, data);
+        return self.finalize(&mut ctx, out);
+    }
+
+    /* Load The Context Into The Hardware, Starting A New Computation The First Time */
+    fn resume(&self, ctx: &mut Context) {
+        if ctx.started {
+            write(self.imr, ctx.imr);
+            write(self.str, ctx.str);
+            write(self.cr, ctx.cr);
+            write(self.cr, ctx.cr | CR_INIT);
+            for i in 0..ctx.csr_count() {
+                write(unsafe { self.csr.add(i) }, ctx.csr[i]);
+            }
+            return;
+        }
+
+        let mut cr = CR_DATATYPE_BYTE | ctx.algo.bits();
+        if let Some(key) = ctx.key {
+            cr |= CR_MODE;
+            if key.len() > BLOCK_SIZE {
+                cr |= CR_LKEY;
+            }
+        }
+        write(self.imr, 0);
+        write(self.cr, cr);
+        write(self.cr, cr | CR_INIT);
+
+        /* HMAC Inner Hash Starts With The Key */
+        if let Some(key) = ctx.key {
+            self.write_last(key);
+            self.wait_input();
+        }
+        ctx.started = true;
+    }
+
+    /* Save The Hardware State Once The Last Block Has Been Taken In */
+    fn suspend(&self, ctx: &mut Context) {
+        self.wait_input();
+        ctx.imr = read(self.imr);
+        ctx.str = read(self.str) & STR_NBLW;
+        ctx.cr = read(self.cr) & !CR_INIT;
+        for i in 0..ctx.csr_count() {
+            ctx.csr[i] = read(unsafe { self.csr.add(i) });
+        }
+    }
+
+    fn wait_input(&self) {
+        while (read(self.sr) & (SR_DINIS | SR_BUSY)) != SR_DINIS {}
+    }
+
+    /* Little Endian Words Give Memory Order With Byte Swapping, A Short Last Word Is Zero Padded */
+    fn write_words(&self, data: &[u8]) {
+        for chunk in data.chunks(4) {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            write(self.din, u32::from_le_bytes(word));
+        }
+    }
+
+    /* Last Data Of A Phase, Valid Bits Of The Final Word Then Start The Digest Calculation */
+    fn write_last(&self, data: &[u8]) {
+        let nblw = ((data.len() % 4) * 8) as u32;
+        write(self.str, nblw);
+        self.write_words(data);
+        write(self.str, nblw | STR_DCAL);
+    }
+}
+
+pub fn clock_init(rcc_base: u32) {
+    let rcc_ahb2enr = (rcc_base + RCC_AHB2ENR) as *mut u32;
+    write(rcc_ahb2enr, read(rcc_ahb2enr) | RCC_HASHEN);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
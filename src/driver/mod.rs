@@ -1,2 +1,13 @@
 /* Public Modules */
-pub mod w5200;
\ No newline at end of file
+pub mod w5200;
+pub mod serial;
+pub mod usb;
+pub mod cdc;
+pub mod pd;
+pub mod ucpd;
+pub mod rng;
+pub mod dma;
+pub mod hash;
+pub mod crc;
+pub mod exti;
+pub mod fdcan;
\ No newline at end of file
@@ -0,0 +1,848 @@
+/* USB Power Delivery Messages And Sink Policy Engine (USB PD 3.0 Chapter 6 And 8) */
+/* Hardware Independent, The Physical And Protocol Layers Are Supplied Through PdPhy */
+
+/* Maximum Data Objects In A Non Extended Message */
+pub const MAX_DATA_OBJECTS:     usize = 7;
+/* Header Plus Data Objects, The CRC Is Added And Checked By The Hardware */
+pub const MAX_MESSAGE_SIZE:     usize = 2 + 4 * MAX_DATA_OBJECTS;
+
+/* Message Header */
+const HEADER_TYPE:              u16 = 0x001F;
+const HEADER_DATA_ROLE:         u16 = 1 << 5;
+const HEADER_REV_SHIFT:         u16 = 6;
+const HEADER_REV:               u16 = 0x0003 << HEADER_REV_SHIFT;
+const HEADER_POWER_ROLE:        u16 = 1 << 8;
+const HEADER_ID_SHIFT:          u16 = 9;
+const HEADER_ID:                u16 = 0x0007 << HEADER_ID_SHIFT;
+const HEADER_COUNT_SHIFT:       u16 = 12;
+const HEADER_COUNT:             u16 = 0x0007 << HEADER_COUNT_SHIFT;
+const HEADER_EXTENDED:          u16 = 1 << 15;
+
+/* Specification Revision */
+pub const REV_2_0:              u8 = 1;
+pub const REV_3_0:              u8 = 2;
+
+/* Control Messages */
+#[derive(Clone, Copy, PartialEq)]
+pub enum ControlMsg {
+    GoodCrc = 1,
+    GotoMin = 2,
+    Accept = 3,
+    Reject = 4,
+    Ping = 5,
+    PsRdy = 6,
+    GetSourceCap = 7,
+    GetSinkCap = 8,
+    DrSwap = 9,
+    PrSwap = 10,
+    VconnSwap = 11,
+    Wait = 12,
+    SoftReset = 13,
+    NotSupported = 16
+}
+
+/* Data Messages */
+#[derive(Clone, Copy, PartialEq)]
+pub enum DataMsg {
+    SourceCapabilities = 1,
+    Request = 2,
+    Bist = 3,
+    SinkCapabilities = 4,
+    VendorDefined = 15
+}
+
+/* Power Data Object (PD 6.4.1) */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pdo {
+    Fixed { voltage_mv: u32, current_ma: u32 },
+    Variable { min_mv: u32, max_mv: u32, current_ma: u32 },
+    Battery { min_mv: u32, max_mv: u32, power_mw: u32 },
+    Pps { min_mv: u32, max_mv: u32, current_ma: u32 },
+    Unknown
+}
+
+/* Power Data Object Fields */
+const PDO_TYPE_SHIFT:           u32 = 30;
+const PDO_FIXED:                u32 = 0;
+const PDO_BATTERY:              u32 = 1;
+const PDO_VARIABLE:             u32 = 2;
+const PDO_AUGMENTED:            u32 = 3;
+const PDO_APDO_SHIFT:           u32 = 28;
+const PDO_APDO_PPS:             u32 = 0;
+
+/* Request Data Object Fields */
+const RDO_POSITION_SHIFT:       u32 = 28;
+const RDO_MISMATCH:             u32 = 1 << 26;
+const RDO_NO_SUSPEND:           u32 = 1 << 24;
+const RDO_OP_SHIFT:             u32 = 10;
+const RDO_PPS_VOLTAGE_SHIFT:    u32 = 9;
+
+/* Sink Fixed Supply PDO, Higher Capability Bit Set When More Than vSafe5V Is Wanted */
+const SINK_PDO_HIGHER_CAP:      u32 = 1 << 28;
+
+/* Safe Default Voltage Every Source Offers In Its First PDO */
+pub const VSAFE5V_MV:           u32 = 5000;
+
+/* Timers In Milliseconds (PD 6.6) */
+const T_SINK_WAIT_CAP:          u32 = 465;      // Wait For Source Capabilities (310 - 620)
+const T_SENDER_RESPONSE:        u32 = 27;       // Wait For A Response To A Request (24 - 30)
+const T_PS_TRANSITION:          u32 = 500;      // Wait For PS_RDY After Accept (450 - 550)
+const T_SINK_REQUEST:           u32 = 100;      // Wait Before Retrying A Request Answered With Wait
+const T_PPS_REQUEST:            u32 = 8000;     // PPS Contracts Lapse Without A Request Every 10 s
+const T_HARD_RESET_RECOVER:     u32 = 1000;     // Source Cycles VBUS After A Hard Reset
+const T_ERROR_RECOVERY:         u32 = 5000;     // Stay Disabled Before Trying Again
+
+/* Counters */
+const N_HARD_RESET_COUNT:       u8 = 2;
+
+/* Message As Carried Between The Policy Engine And The Protocol Layer */
+#[derive(Clone, Copy)]
+pub struct Message {
+    pub header:     u16,                            // Message Header
+    pub data:       [u32; MAX_DATA_OBJECTS]         // Data Objects, Only get_count() Are Valid
+}
+
+/* Physical And Protocol Layer Under The Policy Engine */
+pub trait PdPhy {
+    /* A Source Is Attached On One Of The CC Lines */
+    fn get_attached(&mut self) -> bool;
+    /* Send A Message, The Message ID Is Filled In, Returns true Once A GoodCRC Came Back */
+    fn transmit(&mut self, msg: &mut Message) -> bool;
+    /* Next Received Message Other Than GoodCRC, Which Is Answered By The Protocol Layer */
+    fn receive(&mut self) -> Option<Message>;
+    /* Send Hard Reset Signalling */
+    fn hard_reset(&mut self);
+    /* Hard Reset Signalling Was Received Since The Last Call */
+    fn get_hard_reset(&mut self) -> bool;
+    /* Reset Message IDs After A Soft Or Hard Reset */
+    fn reset_protocol(&mut self);
+}
+
+impl Message {
+    pub const fn init() -> Message {
+        return Message {
+            header:     0,
+            data:       [0; MAX_DATA_OBJECTS]
+        };
+    }
+
+    /* Header For A Message Sent By A Sink (Power Role Sink, Data Role UFP) */
+    pub fn sink_header(msg_type: u8, count: usize, revision: u8) -> u16 {
+        return (msg_type as u16 & HEADER_TYPE) | (((revision as u16) << HEADER_REV_SHIFT) & HEADER_REV) | (((count as u16) << HEADER_COUNT_SHIFT) & HEADER_COUNT);
+    }
+
+    pub fn control(msg: ControlMsg, revision: u8) -> Message {
+        let mut message = Message::init();
+        message.header = Message::sink_header(msg as u8, 0, revision);
+        return message;
+    }
+
+    pub fn data(msg: DataMsg, objects: &[u32], revision: u8) -> Message {
+        let mut message = Message::init();
+        let count = if objects.len() > MAX_DATA_OBJECTS { MAX_DATA_OBJECTS } else { objects.len() };
+        message.header = Message::sink_header(msg as u8, count, revision);
+        message.data[..count].copy_from_slice(&objects[..count]);
+        return message;
+    }
+
+    pub fn get_type(&self) -> u8 {
+        return (self.header & HEADER_TYPE) as u8;
+    }
+
+    pub fn get_count(&self) -> usize {
+        return ((self.header & HEADER_COUNT) >> HEADER_COUNT_SHIFT) as usize;
+    }
+
+    pub fn get_id(&self) -> u8 {
+        return ((self.header & HEADER_ID) >> HEADER_ID_SHIFT) as u8;
+    }
+
+    pub fn set_id(&mut self, id: u8) {
+        self.header = (self.header & !HEADER_ID) | (((id as u16) << HEADER_ID_SHIFT) & HEADER_ID);
+    }
+
+    pub fn get_revision(&self) -> u8 {
+        return ((self.header & HEADER_REV) >> HEADER_REV_SHIFT) as u8;
+    }
+
+    pub fn get_extended(&self) -> bool {
+        return (self.header & HEADER_EXTENDED) != 0;
+    }
+
+    /* Sent By The Source Port (Power Role Bit Set) */
+    pub fn get_from_source(&self) -> bool {
+        return (self.header & HEADER_POWER_ROLE) != 0;
+    }
+
+    pub fn get_from_dfp(&self) -> bool {
+        return (self.header & HEADER_DATA_ROLE) != 0;
+    }
+
+    pub fn is_control(&self, msg: ControlMsg) -> bool {
+        return self.get_count() == 0 && !self.get_extended() && self.get_type() == msg as u8;
+    }
+
+    pub fn is_data(&self, msg: DataMsg) -> bool {
+        return self.get_count() > 0 && !self.get_extended() && self.get_type() == msg as u8;
+    }
+
+    /* Serialise Little Endian, Returns The Payload Size */
+    pub fn to_bytes(&self, buf: &mut [u8; MAX_MESSAGE_SIZE]) -> usize {
+        buf[0..2].copy_from_slice(&self.header.to_le_bytes());
+        let count = self.get_count();
+        for i in 0..count {
+            buf[2 + 4 * i..6 + 4 * i].copy_from_slice(&self.data[i].to_le_bytes());
+        }
+        return 2 + 4 * count;
+    }
+
+    /* Deserialise A Received Payload, Rejects Sizes That Disagree With The Header */
+    pub fn from_bytes(buf: &[u8]) -> Option<Message> {
+        if buf.len() < 2 {
+            return None;
+        }
+        let mut message = Message::init();
+        message.header = u16::from_le_bytes([buf[0], buf[1]]);
+        let count = message.get_count();
+        if message.get_extended() || buf.len() < 2 + 4 * count {
+            return None;
+        }
+        for i in 0..count {
+            message.data[i] = u32::from_le_bytes([buf[2 + 4 * i], buf[3 + 4 * i], buf[4 + 4 * i], buf[5 + 4 * i]]);
+        }
+        return Some(message);
+    }
+}
+
+impl Pdo {
+    pub fn parse(pdo: u32) -> Pdo {
+        match pdo >> PDO_TYPE_SHIFT {
+            PDO_FIXED => {
+                return Pdo::Fixed {
+                    voltage_mv:     ((pdo >> 10) & 0x3FF) * 50,
+                    current_ma:     (pdo & 0x3FF) * 10
+                };
+            } PDO_BATTERY => {
+                return Pdo::Battery {
+                    min_mv:         ((pdo >> 10) & 0x3FF) * 50,
+                    max_mv:         ((pdo >> 20) & 0x3FF) * 50,
+                    power_mw:       (pdo & 0x3FF) * 250
+                };
+            } PDO_VARIABLE => {
+                return Pdo::Variable {
+                    min_mv:         ((pdo >> 10) & 0x3FF) * 50,
+                    max_mv:         ((pdo >> 20) & 0x3FF) * 50,
+                    current_ma:     (pdo & 0x3FF) * 10
+                };
+            } _ => {
+                if ((pdo >> PDO_APDO_SHIFT) & 0x3) == PDO_APDO_PPS {
+                    return Pdo::Pps {
+                        min_mv:     ((pdo >> 8) & 0xFF) * 100,
+                        max_mv:     ((pdo >> 17) & 0xFF) * 100,
+                        current_ma: (pdo & 0x7F) * 50
+                    };
+                }
+                return Pdo::Unknown;
+            }
+        }
+    }
+
+    /* Current Available At The Given Voltage, None When The Voltage Is Outside The PDO */
+    pub fn get_current_at(&self, voltage_mv: u32) -> Option<u32> {
+        match *self {
+            Pdo::Fixed { voltage_mv: v, current_ma } => {
+                return if v == voltage_mv { Some(current_ma) } else { None };
+            } Pdo::Variable { min_mv, max_mv, current_ma } | Pdo::Pps { min_mv, max_mv, current_ma } => {
+                return if voltage_mv >= min_mv && voltage_mv <= max_mv { Some(current_ma) } else { None };
+            } Pdo::Battery { min_mv, max_mv, power_mw } => {
+                if voltage_mv >= min_mv && voltage_mv <= max_mv && voltage_mv > 0 {
+                    return Some(power_mw * 1000 / voltage_mv);
+                }
+                return None;
+            } Pdo::Unknown => {
+                return None;
+            }
+        }
+    }
+}
+
+/* Request Data Object For A Fixed Or Variable PDO (Position Is 1 Based) */
+pub fn request_rdo(position: usize, current_ma: u32, max_current_ma: u32, mismatch: bool) -> u32 {
+    let mut rdo = ((position as u32 & 0x7) << RDO_POSITION_SHIFT) | RDO_NO_SUSPEND;
+    rdo |= ((current_ma / 10) & 0x3FF) << RDO_OP_SHIFT;
+    rdo |= (max_current_ma / 10) & 0x3FF;
+    if mismatch {
+        rdo |= RDO_MISMATCH;
+    }
+    return rdo;
+}
+
+/* Request Data Object For A Battery PDO, Power Is Carried In 250 mW Units */
+pub fn request_battery_rdo(position: usize, power_mw: u32, max_power_mw: u32, mismatch: bool) -> u32 {
+    let mut rdo = ((position as u32 & 0x7) << RDO_POSITION_SHIFT) | RDO_NO_SUSPEND;
+    rdo |= ((power_mw / 250) & 0x3FF) << RDO_OP_SHIFT;
+    rdo |= (max_power_mw / 250) & 0x3FF;
+    if mismatch {
+        rdo |= RDO_MISMATCH;
+    }
+    return rdo;
+}
+
+/* Request Data Object For A Programmable Power Supply APDO */
+pub fn request_pps_rdo(position: usize, voltage_mv: u32, current_ma: u32) -> u32 {
+    let mut rdo = ((position as u32 & 0x7) << RDO_POSITION_SHIFT) | RDO_NO_SUSPEND;
+    rdo |= ((voltage_mv / 20) & 0x7FF) << RDO_PPS_VOLTAGE_SHIFT;
+    rdo |= (current_ma / 50) & 0x7F;
+    return rdo;
+}
+
+/* Sink Policy Engine States (PD 8.3.3.3) */
+#[derive(Clone, Copy, PartialEq)]
+pub enum SinkState {
+    Disabled,
+    Unattached,
+    WaitCapabilities,
+    EvaluateCapability,
+    SelectCapability,
+    TransitionSink,
+    Ready,
+    SendSoftReset,
+    HardReset,
+    TransitionDefault,
+    ErrorRecovery
+}
+
+/* Contract The Sink Wants From The Source */
+#[derive(Clone, Copy)]
+pub struct SinkConfig {
+    pub voltage_mv:     u32,                    // Requested Voltage
+    pub current_ma:     u32,                    // Requested Operating Current
+    pub allow_pps:      bool                    // Use A PPS APDO When No Fixed PDO Matches
+}
+
+/* Contract Agreed With The Source */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Contract {
+    pub position:       usize,                  // Source PDO Position (1 Based)
+    pub voltage_mv:     u32,                    // Negotiated Voltage
+    pub current_ma:     u32,                    // Negotiated Current
+    pub pps:            bool,                   // PPS Contract, Has To Be Refreshed
+    pub mismatch:       bool                    // Requested Contract Was Not Offered
+}
+
+/* Sink Policy Engine, Call poll() Periodically With A Millisecond Time Base */
+pub struct Sink {
+    config:             SinkConfig,             // Requested Contract
+    state:              SinkState,              // Current State
+    timer:              u32,                    // Time The Current State Timer Started
+    timeout:            u32,                    // Duration Of The Current State Timer, 0 If Not Running
+    hard_reset_count:   u8,                     // Hard Resets Sent Without Getting A Contract
+    revision:           u8,                     // Specification Revision Agreed With The Source
+    caps:               [u32; MAX_DATA_OBJECTS],// Last Source Capabilities
+    caps_count:         usize,                  // Number Of Source Capabilities
+    pending:            Option<Contract>,       // Contract Requested And Waiting On Accept / PS_RDY
+    contract:           Option<Contract>,       // Explicit Contract In Place
+    queued:             Option<Message>         // Received In A State That Does Not Take Messages, For The Next
+}
+
+impl Sink {
+    pub const fn init(config: SinkConfig) -> Sink {
+        return Sink {
+            config,
+            state:              SinkState::Unattached,
+            timer:              0,
+            timeout:            0,
+            hard_reset_count:   0,
+            revision:           REV_3_0,
+            caps:               [0; MAX_DATA_OBJECTS],
+            caps_count:         0,
+            pending:            None,
+            contract:           None,
+            queued:             None
+        };
+    }
+
+    pub fn get_state(&self) -> SinkState {
+        return self.state;
+    }
+
+    pub fn get_contract(&self) -> Option<Contract> {
+        return self.contract;
+    }
+
+    /* Source Capabilities Last Offered, Parsed */
+    pub fn get_capability(&self, position: usize) -> Option<Pdo> {
+        if position == 0 || position > self.caps_count {
+            return None;
+        }
+        return Some(Pdo::parse(self.caps[position - 1]));
+    }
+
+    /* Change The Requested Contract, Renegotiated Straight Away When A Contract Is In Place */
+    pub fn set_config(&mut self, config: SinkConfig, now: u32) {
+        self.config = config;
+        if self.state == SinkState::Ready {
+            self.enter(SinkState::EvaluateCapability, now, 0);
+        }
+    }
+
+    pub fn disable(&mut self) {
+        self.state = SinkState::Disabled;
+        self.contract = None;
+        self.pending = None;
+        self.queued = None;
+    }
+
+    pub fn enable(&mut self, now: u32) {
+        if self.state == SinkState::Disabled {
+            self.enter(SinkState::Unattached, now, 0);
+        }
+    }
+
+    fn enter(&mut self, state: SinkState, now: u32, timeout: u32) {
+        self.state = state;
+        self.timer = now;
+        self.timeout = timeout;
+    }
+
+    fn expired(&self, now: u32) -> bool {
+        return self.timeout != 0 && now.wrapping_sub(self.timer) >= self.timeout;
+    }
+
+    /* Run The Policy Engine, now Is A Free Running Millisecond Count */
+    pub fn poll<P: PdPhy>(&mut self, phy: &mut P, now: u32) {
+        if self.state == SinkState::Disabled {
+            return;
+        }
+
+        if !phy.get_attached() {
+            if self.state != SinkState::Unattached && self.state != SinkState::ErrorRecovery {
+                self.detach(phy, now);
+            }
+            if self.state == SinkState::Unattached {
+                return;
+            }
+        }
+
+        if phy.get_hard_reset() {
+            self.transition_default(phy, now);
+        }
+
+        let msg = match self.queued.take() {
+            Some(msg) => Some(msg),
+            None => phy.receive()
+        };
+
+        match self.state {
+            SinkState::Unattached => {
+                /* A Source_Capabilities Already Here Is Handled By WaitCapabilities On The Next Poll */
+                phy.reset_protocol();
+                self.queued = msg;
+                self.hard_reset_count = 0;
+                self.enter(SinkState::WaitCapabilities, now, T_SINK_WAIT_CAP);
+            } SinkState::WaitCapabilities => {
+                if let Some(msg) = msg {
+                    if msg.is_data(DataMsg::SourceCapabilities) {
+                        self.store_caps(&msg);
+                        self.enter(SinkState::EvaluateCapability, now, 0);
+                    }
+                } else if self.expired(now) {
+                    self.enter(SinkState::HardReset, now, 0);
+                }
+            } SinkState::EvaluateCapability => {
+                /* New Capabilities Replace Those Being Evaluated, Anything Else Waits Until The Request Is Sent */
+                if let Some(msg) = msg {
+                    if msg.is_data(DataMsg::SourceCapabilities) {
+                        self.store_caps(&msg);
+                    } else if msg.is_control(ControlMsg::SoftReset) {
+                        self.accept_soft_reset(phy, now);
+                        return;
+                    } else {
+                        self.queued = Some(msg);
+                    }
+                }
+                self.select_capability(phy, now);
+            } SinkState::SelectCapability => {
+                if let Some(msg) = msg {
+                    if msg.is_control(ControlMsg::Accept) {
+                        self.enter(SinkState::TransitionSink, now, T_PS_TRANSITION);
+                    } else if msg.is_control(ControlMsg::Reject) || msg.is_control(ControlMsg::Wait) {
+                        self.pending = None;
+                        if self.contract.is_some() {
+                            /* Keep The Existing Contract, Try Again Later If Asked To Wait, A PPS One Still Needs Refreshing */
+                            let retry = if msg.is_control(ControlMsg::Wait) {
+                                T_SINK_REQUEST
+                            } else if self.contract.is_some_and(|contract| contract.pps) {
+                                T_PPS_REQUEST
+                            } else {
+                                0
+                            };
+                            self.enter(SinkState::Ready, now, retry);
+                        } else {
+                            self.enter(SinkState::WaitCapabilities, now, T_SINK_WAIT_CAP);
+                        }
+                    } else if msg.is_control(ControlMsg::SoftReset) {
+                        self.accept_soft_reset(phy, now);
+                    } else {
+                        self.enter(SinkState::SendSoftReset, now, 0);
+                    }
+                } else if self.expired(now) {
+                    self.enter(SinkState::HardReset, now, 0);
+                }
+            } SinkState::TransitionSink => {
+                if let Some(msg) = msg {
+                    if msg.is_control(ControlMsg::PsRdy) {
+                        self.contract = self.pending;
+                        self.pending = None;
+                        self.hard_reset_count = 0;
+                        let refresh = match self.contract {
+                            Some(contract) if contract.pps => T_PPS_REQUEST,
+                            _ => 0
+                        };
+                        self.enter(SinkState::Ready, now, refresh);
+                    } else {
+                        /* Protocol Error During A Power Transition Needs A Hard Reset */
+                        self.enter(SinkState::HardReset, now, 0);
+                    }
+                } else if self.expired(now) {
+                    self.enter(SinkState::HardReset, now, 0);
+                }
+            } SinkState::Ready => {
+                if let Some(msg) = msg {
+                    self.ready_message(phy, &msg, now);
+                } else if self.expired(now) {
+                    /* Wait Retry Or PPS Keep Alive */
+                    self.enter(SinkState::EvaluateCapability, now, 0);
+                }
+            } SinkState::SendSoftReset => {
+                phy.reset_protocol();
+                let mut reset = Message::control(ControlMsg::SoftReset, self.revision);
+                if phy.transmit(&mut reset) {
+                    self.enter(SinkState::WaitCapabilities, now, T_SINK_WAIT_CAP);
+                } else {
+                    self.enter(SinkState::HardReset, now, 0);
+                }
+            } SinkState::HardReset => {
+                if self.hard_reset_count >= N_HARD_RESET_COUNT {
+                    self.contract = None;
+                    self.pending = None;
+                    self.enter(SinkState::ErrorRecovery, now, T_ERROR_RECOVERY);
+                } else {
+                    self.hard_reset_count += 1;
+                    phy.hard_reset();
+                    self.transition_default(phy, now);
+                }
+            } SinkState::TransitionDefault => {
+                /* The Source Drops VBUS To vSafe0V And Back, Then Sends Its Capabilities Again */
+                if let Some(msg) = msg {
+                    if msg.is_data(DataMsg::SourceCapabilities) {
+                        self.store_caps(&msg);
+                        self.enter(SinkState::EvaluateCapability, now, 0);
+                    }
+                } else if self.expired(now) {
+                    self.enter(SinkState::WaitCapabilities, now, T_SINK_WAIT_CAP);
+                }
+            } SinkState::ErrorRecovery => {
+                if self.expired(now) {
+                    self.hard_reset_count = 0;
+                    self.enter(SinkState::Unattached, now, 0);
+                }
+            } SinkState::Disabled => {}
+        }
+    }
+
+    /* Messages Handled With An Explicit Contract In Place */
+    fn ready_message<P: PdPhy>(&mut self, phy: &mut P, msg: &Message, now: u32) {
+        if msg.is_data(DataMsg::SourceCapabilities) {
+            self.store_caps(msg);
+            self.enter(SinkState::EvaluateCapability, now, 0);
+        } else if msg.is_control(ControlMsg::GetSinkCap) {
+            let mut reply = self.sink_caps();
+            phy.transmit(&mut reply);
+        } else if msg.is_control(ControlMsg::SoftReset) {
+            self.accept_soft_reset(phy, now);
+        } else if msg.is_control(ControlMsg::GotoMin) {
+            /* Go To Minimum Is Only Sent With GiveBack, Which This Sink Never Sets */
+            self.enter(SinkState::SendSoftReset, now, 0);
+        } else if msg.is_control(ControlMsg::Ping) || msg.is_data(DataMsg::VendorDefined) {
+            /* Ping Needs No Answer, Structured VDMs Are Ignored By A Sink Without Alternate Modes */
+        } else if msg.is_control(ControlMsg::Accept) || msg.is_control(ControlMsg::PsRdy) || msg.is_control(ControlMsg::Reject) {
+            /* Unexpected In Ready, Protocol Error */
+            self.enter(SinkState::SendSoftReset, now, 0);
+        } else {
+            let reply = if self.revision >= REV_3_0 { ControlMsg::NotSupported } else { ControlMsg::Reject };
+            let mut reply = Message::control(reply, self.revision);
+            phy.transmit(&mut reply);
+        }
+    }
+
+    fn accept_soft_reset<P: PdPhy>(&mut self, phy: &mut P, now: u32) {
+        phy.reset_protocol();
+        let mut accept = Message::control(ControlMsg::Accept, self.revision);
+        if phy.transmit(&mut accept) {
+            self.pending = None;
+            self.enter(SinkState::WaitCapabilities, now, T_SINK_WAIT_CAP);
+        } else {
+            self.enter(SinkState::HardReset, now, 0);
+        }
+    }
+
+    fn transition_default<P: PdPhy>(&mut self, phy: &mut P, now: u32) {
+        phy.reset_protocol();
+        self.contract = None;
+        self.pending = None;
+        self.queued = None;
+        self.revision = REV_3_0;
+        self.enter(SinkState::TransitionDefault, now, T_HARD_RESET_RECOVER);
+    }
+
+    fn detach<P: PdPhy>(&mut self, phy: &mut P, now: u32) {
+        phy.reset_protocol();
+        self.contract = None;
+        self.pending = None;
+        self.queued = None;
+        self.caps_count = 0;
+        self.hard_reset_count = 0;
+        self.revision = REV_3_0;
+        self.enter(SinkState::Unattached, now, 0);
+    }
+
+    fn store_caps(&mut self, msg: &Message) {
+        self.caps_count = msg.get_count();
+        self.caps[..self.caps_count].copy_from_slice(&msg.data[..self.caps_count]);
+        /* Talk The Lower Of Both Revisions */
+        let revision = msg.get_revision();
+        self.revision = if revision < REV_3_0 { REV_2_0 } else { REV_3_0 };
+    }
+
+    /* Pick The Best Source Capability For The Configuration And Send The Request */
+    fn select_capability<P: PdPhy>(&mut self, phy: &mut P, now: u32) {
+        let contract = self.evaluate();
+        let rdo = match self.get_capability(contract.position) {
+            Some(Pdo::Pps { .. }) => request_pps_rdo(contract.position, contract.voltage_mv, contract.current_ma),
+            Some(Pdo::Battery { .. }) => {
+                let power = contract.voltage_mv * contract.current_ma / 1000;
+                request_battery_rdo(contract.position, power, power, contract.mismatch)
+            } _ => request_rdo(contract.position, contract.current_ma, contract.current_ma, contract.mismatch)
+        };
+
+        let mut request = Message::data(DataMsg::Request, &[rdo], self.revision);
+        if phy.transmit(&mut request) {
+            self.pending = Some(contract);
+            self.enter(SinkState::SelectCapability, now, T_SENDER_RESPONSE);
+        } else {
+            self.enter(SinkState::SendSoftReset, now, 0);
+        }
+    }
+
+    /* Fixed Or Variable PDO At The Requested Voltage First, Then PPS, Otherwise vSafe5V With Mismatch Set */
+    pub fn evaluate(&self) -> Contract {
+        let mut pps: Option<Contract> = None;
+
+        for position in 1..=self.caps_count {
+            let pdo = Pdo::parse(self.caps[position - 1]);
+            let current = match pdo.get_current_at(self.config.voltage_mv) {
+                Some(current) => current,
+                None => continue
+            };
+            if current < self.config.current_ma {
+                continue;
+            }
+
+            match pdo {
+                Pdo::Pps { .. } => {
+                    if self.config.allow_pps && self.revision >= REV_3_0 && pps.is_none() {
+                        pps = Some(Contract {
+                            position,
+                            voltage_mv:     self.config.voltage_mv,
+                            current_ma:     self.config.current_ma,
+                            pps:            true,
+                            mismatch:       false
+                        });
+                    }
+                } Pdo::Fixed { .. } | Pdo::Variable { .. } | Pdo::Battery { .. } => {
+                    return Contract {
+                        position,
+                        voltage_mv:     self.config.voltage_mv,
+                        current_ma:     self.config.current_ma,
+                        pps:            false,
+                        mismatch:       false
+                    };
+                } Pdo::Unknown => {}
+            }
+        }
+
+        if let Some(contract) = pps {
+            return contract;
+        }
+
+        /* The First PDO Is Always vSafe5V Fixed */
+        let current = match self.get_capability(1) {
+            Some(Pdo::Fixed { current_ma, .. }) => current_ma,
+            _ => 0
+        };
+        let current = if self.config.current_ma < current { self.config.current_ma } else { current };
+        return Contract {
+            position:       1,
+            voltage_mv:     VSAFE5V_MV,
+            current_ma:     current,
+            pps:            false,
+            mismatch:       true
+        };
+    }
+
+    /* Sink Capabilities, vSafe5V Plus The Configured Voltage When It Is Higher */
+    fn sink_caps(&self) -> Message {
+        let current = (self.config.current_ma / 10) & 0x3FF;
+        let vsafe = ((VSAFE5V_MV / 50) << 10) | current;
+        if self.config.voltage_mv <= VSAFE5V_MV {
+            return Message::data(DataMsg::SinkCapabilities, &[vsafe], self.revision);
+        }
+        let wanted = (((self.config.voltage_mv / 50) & 0x3FF) << 10) | current;
+        return Message::data(DataMsg::SinkCapabilities, &[vsafe | SINK_PDO_HIGHER_CAP, wanted], self.revision);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Source Offering vSafe5V At 3 A And A 9 - 20 V 30 W Battery Supply */
+    const FIXED_5V_3A:  u32 = ((5000 / 50) << 10) | (3000 / 10);
+    const BATTERY_30W:  u32 = (PDO_BATTERY << PDO_TYPE_SHIFT) | ((20000 / 50) << 20) | ((9000 / 50) << 10) | (30000 / 250);
+
+    /* PPS APDO Of 3.3 - 11 V At 3 A */
+    const PPS_11V_3A:   u32 = (PDO_AUGMENTED << PDO_TYPE_SHIFT) | ((11000 / 100) << 17) | ((3300 / 100) << 8) | (3000 / 50);
+
+    struct Phy {
+        rx:         Option<Message>,
+        sent:       Vec<Message>
+    }
+
+    impl PdPhy for Phy {
+        fn get_attached(&mut self) -> bool {
+            return true;
+        }
+
+        fn transmit(&mut self, msg: &mut Message) -> bool {
+            self.sent.push(*msg);
+            return true;
+        }
+
+        fn receive(&mut self) -> Option<Message> {
+            return self.rx.take();
+        }
+
+        fn hard_reset(&mut self) {
+        }
+
+        fn get_hard_reset(&mut self) -> bool {
+            return false;
+        }
+
+        fn reset_protocol(&mut self) {
+        }
+    }
+
+    fn caps() -> Message {
+        return Message::data(DataMsg::SourceCapabilities, &[FIXED_5V_3A, BATTERY_30W], REV_3_0);
+    }
+
+    #[test]
+    fn battery_rdo_in_250mw_units() {
+        let rdo = request_battery_rdo(2, 24000, 30000, false);
+        assert_eq!(rdo >> RDO_POSITION_SHIFT, 2);
+        assert_eq!((rdo >> RDO_OP_SHIFT) & 0x3FF, 96);
+        assert_eq!(rdo & 0x3FF, 120);
+    }
+
+    #[test]
+    fn battery_supply_requested_by_power() {
+        let mut sink = Sink::init(SinkConfig { voltage_mv: 12000, current_ma: 2000, allow_pps: false });
+        let mut phy = Phy { rx: None, sent: Vec::new() };
+        sink.poll(&mut phy, 0);
+        phy.rx = Some(caps());
+        sink.poll(&mut phy, 1);
+        sink.poll(&mut phy, 2);
+
+        assert!(sink.get_state() == SinkState::SelectCapability);
+        let request = phy.sent.last().unwrap();
+        assert!(request.is_data(DataMsg::Request));
+        assert_eq!(request.data[0] >> RDO_POSITION_SHIFT, 2);
+        assert_eq!((request.data[0] >> RDO_OP_SHIFT) & 0x3FF, 24000 / 250);
+        assert_eq!(request.data[0] & 0x3FF, 24000 / 250);
+    }
+
+    #[test]
+    fn early_source_capabilities_are_kept() {
+        let mut sink = Sink::init(SinkConfig { voltage_mv: 5000, current_ma: 1000, allow_pps: false });
+        let mut phy = Phy { rx: Some(caps()), sent: Vec::new() };
+        sink.poll(&mut phy, 0);
+        assert!(sink.get_state() == SinkState::WaitCapabilities);
+        sink.poll(&mut phy, 1);
+        assert!(sink.get_state() == SinkState::EvaluateCapability);
+        sink.poll(&mut phy, 2);
+        assert!(sink.get_state() == SinkState::SelectCapability);
+        assert_eq!(phy.sent.len(), 1);
+        assert_eq!(phy.sent[0].data[0] >> RDO_POSITION_SHIFT, 1);
+    }
+
+    #[test]
+    fn capabilities_while_evaluating_replace_the_old() {
+        let mut sink = Sink::init(SinkConfig { voltage_mv: 12000, current_ma: 2000, allow_pps: false });
+        let mut phy = Phy { rx: None, sent: Vec::new() };
+        sink.poll(&mut phy, 0);
+        phy.rx = Some(Message::data(DataMsg::SourceCapabilities, &[FIXED_5V_3A], REV_3_0));
+        sink.poll(&mut phy, 1);
+        assert!(sink.get_state() == SinkState::EvaluateCapability);
+
+        phy.rx = Some(caps());
+        sink.poll(&mut phy, 2);
+        assert!(sink.get_capability(2) == Some(Pdo::Battery { min_mv: 9000, max_mv: 20000, power_mw: 30000 }));
+        assert_eq!(phy.sent[0].data[0] >> RDO_POSITION_SHIFT, 2);
+    }
+
+    #[test]
+    fn wait_capabilities_times_out_on_the_tick() {
+        let mut sink = Sink::init(SinkConfig { voltage_mv: 5000, current_ma: 1000, allow_pps: false });
+        let mut phy = Phy { rx: None, sent: Vec::new() };
+        sink.poll(&mut phy, 100);
+        sink.poll(&mut phy, 100 + T_SINK_WAIT_CAP - 1);
+        assert!(sink.get_state() == SinkState::WaitCapabilities);
+        sink.poll(&mut phy, 100 + T_SINK_WAIT_CAP);
+        assert!(sink.get_state() == SinkState::HardReset);
+    }
+
+    #[test]
+    fn rejected_pps_request_keeps_refreshing() {
+        let mut sink = Sink::init(SinkConfig { voltage_mv: 7000, current_ma: 2000, allow_pps: true });
+        let mut phy = Phy { rx: None, sent: Vec::new() };
+        sink.poll(&mut phy, 0);
+        phy.rx = Some(Message::data(DataMsg::SourceCapabilities, &[FIXED_5V_3A, PPS_11V_3A], REV_3_0));
+        sink.poll(&mut phy, 1);
+        sink.poll(&mut phy, 2);
+        phy.rx = Some(Message::control(ControlMsg::Accept, REV_3_0));
+        sink.poll(&mut phy, 3);
+        phy.rx = Some(Message::control(ControlMsg::PsRdy, REV_3_0));
+        sink.poll(&mut phy, 4);
+        assert!(sink.get_state() == SinkState::Ready);
+        assert!(sink.get_contract().map(|contract| contract.pps) == Some(true));
+
+        /* The Refresh Is Rejected, The Contract Stands And Is Refreshed Again In Time */
+        sink.poll(&mut phy, 4 + T_PPS_REQUEST);
+        sink.poll(&mut phy, 5 + T_PPS_REQUEST);
+        assert!(sink.get_state() == SinkState::SelectCapability);
+        phy.rx = Some(Message::control(ControlMsg::Reject, REV_3_0));
+        sink.poll(&mut phy, 6 + T_PPS_REQUEST);
+        assert!(sink.get_state() == SinkState::Ready);
+        assert!(sink.get_contract().is_some());
+
+        let requests = phy.sent.len();
+        sink.poll(&mut phy, 5 + (2 * T_PPS_REQUEST));
+        assert!(sink.get_state() == SinkState::Ready);
+        sink.poll(&mut phy, 6 + (2 * T_PPS_REQUEST));
+        sink.poll(&mut phy, 7 + (2 * T_PPS_REQUEST));
+        assert!(sink.get_state() == SinkState::SelectCapability);
+        assert!(phy.sent.len() == requests + 1 && phy.sent[requests].is_data(DataMsg::Request));
+    }
+}
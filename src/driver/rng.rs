@@ -0,0 +1,232 @@
+use core::ptr;
+
+/* True Random Number Generator (RNG) - Reference Manual pg 1195 */
+pub struct Rng {
+    cr:         *mut u32,       // Control Register
+    sr:         *mut u32,       // Status Register
+    dr:         *mut u32        // Data Register
+}
+
+/* Failures Reported By The Generator */
+#[derive(Clone, Copy, PartialEq)]
+pub enum RngError {
+    ClockError,                 // RNG Clock Too Slow Compared To The AHB Clock
+    SeedError,                  // Noise Source Failed Its Health Tests
+    Timeout                     // No Data Became Ready
+}
+
+/* Register Offsets */
+const CR:                   u32 = 0x00;
+const SR:                   u32 = 0x04;
+const DR:                   u32 = 0x08;
+
+/* Control Register */
+const CR_RNGEN:             u32 = 1 << 2;   // Random Number Generator Enable
+const CR_CED:               u32 = 1 << 5;   // Clock Error Detection, Active When Clear
+const CR_CONDRST:           u32 = 1 << 30;  // Conditioning Soft Reset
+
+/* Status Register */
+const SR_DRDY:              u32 = 1 << 0;   // Data Ready
+const SR_CECS:              u32 = 1 << 1;   // Clock Error Current Status
+const SR_SECS:              u32 = 1 << 2;   // Seed Error Current Status
+const SR_CEIS:              u32 = 1 << 5;   // Clock Error Interrupt Status
+const SR_SEIS:              u32 = 1 << 6;   // Seed Error Interrupt Status
+
+/* Words Discarded To Flush The Pipeline After A Seed Error */
+const SEED_FLUSH:           u32 = 12;
+
+/* Polls Of Data Ready Before Giving Up, A Word Takes Around 200 RNG Clocks */
+const DRDY_TIMEOUT:         u32 = 10000;
+
+/* Attempts At Recovering From An Error Before Reporting It */
+const RECOVER_ATTEMPTS:     u32 = 3;
+
+/* Ephemeral Port Range (RFC 6335) */
+const PORT_MIN:             u16 = 49152;
+
+/* Clock Registers Used By clock_init */
+const RCC_CRRCR:            u32 = 0x98;
+const RCC_AHB2ENR:          u32 = 0x4C;
+const RCC_HSI48ON:          u32 = 1 << 0;
+const RCC_HSI48RDY:         u32 = 1 << 1;
+const RCC_RNGEN:            u32 = 1 << 18;
+
+impl Rng {
+    pub fn init(base: u32) -> Rng {
+        return Rng {
+            cr:         (base + CR) as *mut u32,
+            sr:         (base + SR) as *mut u32,
+            dr:         (base + DR) as *mut u32
+        };
+    }
+
+    /* Enable With Clock Error Detection Active */
+    pub fn open(&self) {
+        write(self.cr, read(self.cr) & !CR_CED);
+        write(self.cr, read(self.cr) | CR_RNGEN);
+    }
+
+    pub fn close(&self) {
+        write(self.cr, read(self.cr) & !CR_RNGEN);
+    }
+
+    pub fn get_clock_error(&self) -> bool {
+        return (read(self.sr) & (SR_CECS | SR_CEIS)) != 0;
+    }
+
+    pub fn get_seed_error(&self) -> bool {
+        return (read(self.sr) & (SR_SECS | SR_SEIS)) != 0;
+    }
+
+    /* Clock Error Clears Itself Once The Clock Is Back In Range, Only The Flag Needs Clearing (Written 0) */
+    fn recover_clock(&self) -> bool {
+        write(self.sr, SR_SEIS);
+        return (read(self.sr) & SR_CECS) == 0;
+    }
+
+    /* Seed Error Recovery, Flush The Pipeline And Restart The Conditioning If The Error Remains */
+    fn recover_seed(&self) -> bool {
+        write(self.sr, SR_CEIS);
+        for _ in 0..SEED_FLUSH {
+            read(self.dr);
+        }
+        if (read(self.sr) & (SR_SEIS | SR_SECS)) == 0 {
+            return true;
+        }
+
+        write(self.cr, read(self.cr) | CR_CONDRST);
+        write(self.cr, read(self.cr) & !CR_CONDRST);
+        for _ in 0..DRDY_TIMEOUT {
+            if (read(self.cr) & CR_CONDRST) == 0 {
+                break;
+            }
+        }
+        write(self.sr, SR_CEIS);
+        return (read(self.sr) & SR_SECS) == 0;
+    }
+
+    /* One Conditioned Word Straight From The Hardware, Errors Are Not Recovered */
+    fn read_word(&self) -> Result<u32, RngError> {
+        for _ in 0..DRDY_TIMEOUT {
+            let sr = read(self.sr);
+            if (sr & (SR_SECS | SR_SEIS)) != 0 {
+                return Err(RngError::SeedError);
+            } else if (sr & (SR_CECS | SR_CEIS)) != 0 {
+                return Err(RngError::ClockError);
+            } else if (sr & SR_DRDY) != 0 {
+                let word = read(self.dr);
+                /* A Zero Read With A Seed Error Pending Is Not Random */
+                if word == 0 && (read(self.sr) & SR_SEIS) != 0 {
+                    return Err(RngError::SeedError);
+                }
+                return Ok(word);
+            }
+        }
+        return Err(RngError::Timeout);
+    }
+
+    /* Next Random Word, Recovering From Clock And Seed Errors Where Possible */
+    pub fn next_u32(&self) -> Result<u32, RngError> {
+        let mut last = RngError::Timeout;
+        for _ in 0..RECOVER_ATTEMPTS {
+            match self.read_word() {
+                Ok(word) => {
+                    return Ok(word);
+                } Err(error) => {
+                    self.recover(error);
+                    last = error;
+                }
+            }
+        }
+        return Err(last);
+    }
+
+    pub fn next_u64(&self) -> Result<u64, RngError> {
+        let lo = self.next_u32()? as u64;
+        let hi = self.next_u32()? as u64;
+        return Ok((hi << 32) | lo);
+    }
+
+    pub fn fill_bytes(&self, buf: &mut [u8]) -> Result<(), RngError> {
+        for chunk in buf.chunks_mut(4) {
+            let word = self.next_u32()?.to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        return Ok(());
+    }
+
+    /* Random Ephemeral Port For An Outgoing Connection (49152 - 65535) */
+    pub fn next_port(&self) -> Result<u16, RngError> {
+        let word = self.next_u32()?;
+        return Ok(PORT_MIN | (word as u16 & !PORT_MIN));
+    }
+
+    fn recover(&self, error: RngError) {
+        match error {
+            RngError::ClockError => {
+                self.recover_clock();
+            } RngError::SeedError => {
+                self.recover_seed();
+            } _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl RngError {
+    fn code(&self) -> u32 {
+        return rand_core::Error::CUSTOM_START + *self as u32;
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        return match Rng::next_u32(self) {
+            Ok(word) => word,
+            Err(_) => panic!("RNG failure")
+        };
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        return match Rng::next_u64(self) {
+            Ok(word) => word,
+            Err(_) => panic!("RNG failure")
+        };
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if Rng::fill_bytes(self, dest).is_err() {
+            panic!("RNG failure");
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        return Rng::fill_bytes(self, dest).map_err(|error| {
+            let code = core::num::NonZeroU32::new(error.code()).unwrap();
+            rand_core::Error::from(code)
+        });
+    }
+}
+
+/* Output Comes From A Hardware Entropy Source With Conditioning */
+#[cfg(feature = "rand_core")]
+impl rand_core::CryptoRng for Rng {}
+
+/* Clock The RNG From HSI48 */
+pub fn clock_init(rcc_base: u32) {
+    let rcc_crrcr = (rcc_base + RCC_CRRCR) as *mut u32;
+    let rcc_ahb2enr = (rcc_base + RCC_AHB2ENR) as *mut u32;
+
+    write(rcc_crrcr, read(rcc_crrcr) | RCC_HSI48ON);
+    while (read(rcc_crrcr) & RCC_HSI48RDY) == 0 {}
+    write(rcc_ahb2enr, read(rcc_ahb2enr) | RCC_RNGEN);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
@@ -0,0 +1,33 @@
+use core::fmt;
+use super::super::stm32hal::usart;
+
+/* Common Write Path For Anything That Behaves Like A Serial Port (USART, USB CDC) */
+pub trait Serial {
+    fn write(&self, buf: &[u8]);
+}
+
+impl Serial for usart::Usart {
+    fn write(&self, buf: &[u8]) {
+        usart::Usart::write(self, buf);
+    }
+}
+
+/* Formatter Over A Serial Port, Allows write!() And writeln!() To Any Port */
+pub struct Writer<'a, T: Serial> {
+    port:           &'a T                   // Port To Write Formatted Text To
+}
+
+impl<'a, T: Serial> Writer<'a, T> {
+    pub fn init(port: &'a T) -> Writer<'a, T> {
+        return Writer {
+            port
+        };
+    }
+}
+
+impl<'a, T: Serial> fmt::Write for Writer<'a, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.port.write(s.as_bytes());
+        return Ok(());
+    }
+}
@@ -0,0 +1,492 @@
+use core::ptr;
+use super::pd;
+
+/* USB Type-C / USB Power Delivery Interface (UCPD) - Reference Manual pg 2001 */
+#[derive(Clone, Copy)]
+pub struct Ucpd {
+    cfg1:       *mut u32,       // Configuration Register 1
+    cfg2:       *mut u32,       // Configuration Register 2
+    cr:         *mut u32,       // Control Register
+    imr:        *mut u32,       // Interrupt Mask Register
+    sr:         *mut u32,       // Status Register
+    icr:        *mut u32,       // Interrupt Clear Register
+    tx_ordset:  *mut u32,       // Tx Ordered Set Type Register
+    tx_paysz:   *mut u32,       // Tx Payload Size Register
+    txdr:       *mut u32,       // Tx Data Register
+    rx_ordset:  *mut u32,       // Rx Ordered Set Register
+    rx_paysz:   *mut u32,       // Rx Payload Size Register
+    rxdr:       *mut u32        // Rx Data Register
+}
+
+/* CC Line The Cable Is Connected On */
+#[derive(Clone, Copy, PartialEq)]
+pub enum CcLine {
+    Cc1,
+    Cc2
+}
+
+/* Voltage State Of A CC Line Seen By A Sink, Advertises The Source Current */
+#[derive(Clone, Copy, PartialEq)]
+pub enum CcState {
+    Open,                       // vRa, Nothing Attached
+    Default,                    // vRd-USB, Default USB Power
+    Power1A5,                   // vRd-1.5, 1.5 A At 5 V
+    Power3A0                    // vRd-3.0, 3.0 A At 5 V
+}
+
+/* Result Of A Transmission */
+#[derive(Clone, Copy, PartialEq)]
+pub enum TxStatus {
+    Sent,
+    Discarded,                  // Bus Busy, An Incoming Message Had Priority
+    Aborted
+}
+
+/* Result Of A Reception */
+#[derive(Clone, Copy, PartialEq)]
+pub enum RxStatus {
+    Empty,
+    Message(usize),             // Message Of The Given Length Received On SOP
+    HardReset,
+    Error
+}
+
+/* Register Offsets */
+const CFG1:                 u32 = 0x00;
+const CFG2:                 u32 = 0x04;
+const CR:                   u32 = 0x0C;
+const IMR:                  u32 = 0x10;
+const SR:                   u32 = 0x14;
+const ICR:                  u32 = 0x18;
+const TX_ORDSET:            u32 = 0x1C;
+const TX_PAYSZ:             u32 = 0x20;
+const TXDR:                 u32 = 0x24;
+const RX_ORDSET:            u32 = 0x28;
+const RX_PAYSZ:             u32 = 0x2C;
+const RXDR:                 u32 = 0x30;
+
+/* Configuration Register 1, Timing For A 16 MHz HSI16 Kernel Clock */
+const CFG1_HBITCLKDIV:      u32 = 27 - 1;   // Half Bit Clock 16 MHz / 27, 296 kbps
+const CFG1_IFRGAP:          u32 = (17 - 1) << 6;    // Interframe Gap, 28.7 us
+const CFG1_TRANSWIN:        u32 = (8 - 1) << 11;    // Transition Window, 13.5 us
+const CFG1_RXORDSETEN:      u32 = 0x009 << 20;      // Receive SOP And Hard Reset
+const CFG1_UCPDEN:          u32 = 1 << 31;
+
+/* Control Register */
+const CR_TXMODE:            u32 = 0x3;      // Normal Ordered Set Transmission
+const CR_TXSEND:            u32 = 1 << 2;
+const CR_TXHRST:            u32 = 1 << 3;
+const CR_PHYRXEN:           u32 = 1 << 5;
+const CR_PHYCCSEL:          u32 = 1 << 6;
+const CR_ANAMODE:           u32 = 1 << 9;   // Sink
+const CR_CCENABLE:          u32 = 0x3 << 10;
+
+/* Status And Interrupt Clear Registers */
+const SR_TXIS:              u32 = 1 << 0;
+const SR_TXMSGDISC:         u32 = 1 << 1;
+const SR_TXMSGSENT:         u32 = 1 << 2;
+const SR_TXMSGABT:          u32 = 1 << 3;
+const SR_HRSTDISC:          u32 = 1 << 4;
+const SR_HRSTSENT:          u32 = 1 << 5;
+const SR_TXUND:             u32 = 1 << 6;
+const SR_RXNE:              u32 = 1 << 8;
+const SR_RXORDDET:          u32 = 1 << 9;
+const SR_RXHRSTDET:         u32 = 1 << 10;
+const SR_RXOVR:             u32 = 1 << 11;
+const SR_RXMSGEND:          u32 = 1 << 12;
+const SR_RXERR:             u32 = 1 << 13;
+const SR_TYPECEVT1:         u32 = 1 << 14;
+const SR_TYPECEVT2:         u32 = 1 << 15;
+const SR_VSTATE_CC1_SHIFT:  u32 = 16;
+const SR_VSTATE_CC2_SHIFT:  u32 = 18;
+const ICR_TX:               u32 = SR_TXMSGDISC | SR_TXMSGSENT | SR_TXMSGABT | SR_HRSTDISC | SR_HRSTSENT | SR_TXUND;
+const ICR_RX:               u32 = SR_RXORDDET | SR_RXHRSTDET | SR_RXOVR | SR_RXMSGEND;
+
+/* Interrupts Used To Service The Port From The UCPD1 Interrupt */
+const IMR_RX:               u32 = SR_RXNE | SR_RXHRSTDET | SR_RXMSGEND | SR_TYPECEVT1 | SR_TYPECEVT2;
+
+/* Ordered Sets, Four K-Codes Of 5 Bits */
+const SYNC1:                u32 = 0x18;
+const SYNC2:                u32 = 0x11;
+pub const ORDSET_SOP:       u32 = SYNC1 | (SYNC1 << 5) | (SYNC1 << 10) | (SYNC2 << 15);
+const RX_ORDSET_SOP:        u32 = 0;
+
+/* Polls Before Giving Up On The Hardware */
+const TX_TIMEOUT:           u32 = 20000;    // Transmission Of The Longest Message
+const RX_TIMEOUT:           u32 = 20000;    // Bytes Of A Message Already Started
+
+/* Timers In Microseconds On The Port's Clock (PD 6.6) */
+const T_RECEIVE:            u32 = 1000;     // GoodCRC Has To Follow The Message (0.9 - 1.1 ms)
+
+/* Retries When No GoodCRC Arrives (nRetryCount) */
+const N_RETRY_COUNT:        u8 = 2;
+
+/* Clock Registers Used By clock_init And dead_battery_disable */
+const RCC_CR:               u32 = 0x00;
+const RCC_HSION:            u32 = 1 << 8;
+const RCC_HSIRDY:           u32 = 1 << 10;
+const RCC_APB1ENR2:         u32 = 0x5C;
+const RCC_UCPD1EN:          u32 = 1 << 23;
+const PWR_CR3:              u32 = 0x08;
+const PWR_UCPD_DBDIS:       u32 = 1 << 14;
+
+impl Ucpd {
+    pub fn init(base: u32) -> Ucpd {
+        return Ucpd {
+            cfg1:       (base + CFG1) as *mut u32,
+            cfg2:       (base + CFG2) as *mut u32,
+            cr:         (base + CR) as *mut u32,
+            imr:        (base + IMR) as *mut u32,
+            sr:         (base + SR) as *mut u32,
+            icr:        (base + ICR) as *mut u32,
+            tx_ordset:  (base + TX_ORDSET) as *mut u32,
+            tx_paysz:   (base + TX_PAYSZ) as *mut u32,
+            txdr:       (base + TXDR) as *mut u32,
+            rx_ordset:  (base + RX_ORDSET) as *mut u32,
+            rx_paysz:   (base + RX_PAYSZ) as *mut u32,
+            rxdr:       (base + RXDR) as *mut u32
+        };
+    }
+
+    /* Configure The Bit Timing And Enable, CFG1 Can Only Be Written While Disabled */
+    pub fn open(&self) {
+        write(self.cfg1, CFG1_HBITCLKDIV | CFG1_IFRGAP | CFG1_TRANSWIN | CFG1_RXORDSETEN);
+        write(self.cfg1, read(self.cfg1) | CFG1_UCPDEN);
+        write(self.cfg2, 0);
+    }
+
+    pub fn close(&self) {
+        write(self.imr, 0);
+        write(self.cr, 0);
+        write(self.cfg1, read(self.cfg1) & !CFG1_UCPDEN);
+    }
+
+    /* Present Rd On Both CC Lines And Watch Them For A Source */
+    pub fn sink(&self) {
+        write(self.cr, CR_ANAMODE | CR_CCENABLE);
+        write(self.icr, SR_TYPECEVT1 | SR_TYPECEVT2);
+    }
+
+    pub fn set_interrupt(&self) {
+        write(self.imr, IMR_RX);
+    }
+
+    pub fn clr_interrupt(&self) {
+        write(self.imr, 0);
+    }
+
+    pub fn get_cc(&self, line: CcLine) -> CcState {
+        let shift = match line {
+            CcLine::Cc1 => SR_VSTATE_CC1_SHIFT,
+            CcLine::Cc2 => SR_VSTATE_CC2_SHIFT
+        };
+        return match (read(self.sr) >> shift) & 0x3 {
+            0 => CcState::Open,
+            1 => CcState::Default,
+            2 => CcState::Power1A5,
+            _ => CcState::Power3A0
+        };
+    }
+
+    /* Type-C Event On Either CC Line, Cleared On Read */
+    pub fn get_cc_event(&self) -> bool {
+        let event = (read(self.sr) & (SR_TYPECEVT1 | SR_TYPECEVT2)) != 0;
+        write(self.icr, SR_TYPECEVT1 | SR_TYPECEVT2);
+        return event;
+    }
+
+    /* Route The PHY To The CC Line Carrying The Communication And Start Receiving */
+    pub fn set_phy(&self, line: CcLine) {
+        let cr = read(self.cr) & !(CR_PHYCCSEL | CR_PHYRXEN);
+        let sel = if line == CcLine::Cc2 { CR_PHYCCSEL } else { 0 };
+        write(self.icr, ICR_RX);
+        write(self.cr, cr | sel | CR_PHYRXEN);
+    }
+
+    pub fn clr_phy(&self) {
+        write(self.cr, read(self.cr) & !CR_PHYRXEN);
+    }
+
+    /* Send A Payload After The Given Ordered Set, The CRC Is Appended By The Hardware */
+    pub fn send(&self, ordset: u32, buf: &[u8]) -> TxStatus {
+        write(self.icr, ICR_TX);
+        write(self.tx_ordset, ordset);
+        write(self.tx_paysz, buf.len() as u32);
+        write(self.cr, (read(self.cr) & !CR_TXMODE) | CR_TXSEND);
+
+        let mut i = 0;
+        for _ in 0..TX_TIMEOUT {
+            let sr = read(self.sr);
+            if (sr & SR_TXMSGSENT) != 0 {
+                write(self.icr, ICR_TX);
+                return TxStatus::Sent;
+            } else if (sr & SR_TXMSGDISC) != 0 {
+                write(self.icr, ICR_TX);
+                return TxStatus::Discarded;
+            } else if (sr & (SR_TXMSGABT | SR_TXUND)) != 0 {
+                write(self.icr, ICR_TX);
+                return TxStatus::Aborted;
+            } else if (sr & SR_TXIS) != 0 && i < buf.len() {
+                write(self.txdr, buf[i] as u32);
+                i += 1;
+            }
+        }
+        return TxStatus::Aborted;
+    }
+
+    pub fn send_hard_reset(&self) -> bool {
+        write(self.icr, ICR_TX);
+        write(self.cr, read(self.cr) | CR_TXHRST);
+        for _ in 0..TX_TIMEOUT {
+            let sr = read(self.sr);
+            if (sr & (SR_HRSTSENT | SR_HRSTDISC)) != 0 {
+                write(self.icr, ICR_TX);
+                return (sr & SR_HRSTSENT) != 0;
+            }
+        }
+        return false;
+    }
+
+    /* Read A Message Once Its Ordered Set Has Been Detected, Returns Empty When Nothing Is Arriving */
+    pub fn receive(&self, buf: &mut [u8]) -> RxStatus {
+        let sr = read(self.sr);
+        if (sr & SR_RXHRSTDET) != 0 {
+            write(self.icr, ICR_RX);
+            return RxStatus::HardReset;
+        }
+        if (sr & (SR_RXORDDET | SR_RXNE | SR_RXMSGEND)) == 0 {
+            return RxStatus::Empty;
+        }
+
+        let mut len = 0;
+        for _ in 0..RX_TIMEOUT {
+            let sr = read(self.sr);
+            if (sr & SR_RXNE) != 0 {
+                let byte = read(self.rxdr) as u8;
+                if len < buf.len() {
+                    buf[len] = byte;
+                }
+                len += 1;
+            } else if (sr & SR_RXMSGEND) != 0 {
+                let error = (sr & (SR_RXERR | SR_RXOVR)) != 0;
+                let ordset = read(self.rx_ordset) & 0x7;
+                let size = read(self.rx_paysz) as usize;
+                write(self.icr, ICR_RX);
+                if error || ordset != RX_ORDSET_SOP || size > buf.len() {
+                    return RxStatus::Error;
+                }
+                return RxStatus::Message(size);
+            }
+        }
+        write(self.icr, ICR_RX);
+        return RxStatus::Error;
+    }
+}
+
+/* Protocol Layer Over The UCPD, Handles GoodCRC, Message IDs And Retries For The Policy Engine */
+pub struct UcpdPort {
+    ucpd:           Ucpd,                   // Peripheral Registers
+    cc:             Option<CcLine>,         // CC Line In Use While Attached
+    tx_id:          u8,                     // MessageIDCounter
+    rx_id:          Option<u8>,             // Last Received Message ID, Repeats Are Dropped
+    rx_pending:     Option<pd::Message>,    // Message Received While Waiting On A GoodCRC
+    hard_reset:     bool,                   // Hard Reset Received
+    clock:          fn() -> u32             // Free Running Microsecond Count, For The PD Timers
+}
+
+impl UcpdPort {
+    /* clock Is Any Microsecond Counter Wrapping At 2^32, A Free Running 32 Bit Timer At 1 MHz Say */
+    pub fn init(ucpd: Ucpd, clock: fn() -> u32) -> UcpdPort {
+        return UcpdPort {
+            ucpd,
+            cc:             None,
+            tx_id:          0,
+            rx_id:          None,
+            rx_pending:     None,
+            hard_reset:     false,
+            clock
+        };
+    }
+
+    pub fn open(&mut self) {
+        self.ucpd.open();
+        self.ucpd.sink();
+        self.cc = None;
+    }
+
+    pub fn get_cc(&self) -> Option<CcLine> {
+        return self.cc;
+    }
+
+    /* Current The Source Advertises Through Rp, Valid Without A PD Contract */
+    pub fn get_typec_current(&self) -> u32 {
+        let line = match self.cc {
+            Some(line) => line,
+            None => return 0
+        };
+        return match self.ucpd.get_cc(line) {
+            CcState::Open => 0,
+            CcState::Default => 500,
+            CcState::Power1A5 => 1500,
+            CcState::Power3A0 => 3000
+        };
+    }
+
+    /* Read One Message Off The Wire, Acknowledging Everything Except GoodCRC */
+    fn read_message(&mut self) -> Option<pd::Message> {
+        let mut buf = [0u8; pd::MAX_MESSAGE_SIZE];
+        match self.ucpd.receive(&mut buf) {
+            RxStatus::Message(len) => {
+                let msg = pd::Message::from_bytes(&buf[..len])?;
+                if msg.is_control(pd::ControlMsg::GoodCrc) {
+                    return Some(msg);
+                }
+
+                let mut good_crc = pd::Message::control(pd::ControlMsg::GoodCrc, msg.get_revision());
+                good_crc.set_id(msg.get_id());
+                let mut out = [0u8; pd::MAX_MESSAGE_SIZE];
+                let size = good_crc.to_bytes(&mut out);
+                self.ucpd.send(ORDSET_SOP, &out[..size]);
+
+                /* Soft Reset Restarts The Message IDs, Anything Else Repeated Is A Retry */
+                if msg.is_control(pd::ControlMsg::SoftReset) {
+                    self.tx_id = 0;
+                    self.rx_id = None;
+                } else if self.rx_id == Some(msg.get_id()) {
+                    return None;
+                }
+                self.rx_id = Some(msg.get_id());
+                return Some(msg);
+            } RxStatus::HardReset => {
+                self.hard_reset = true;
+                return None;
+            } _ => {
+                return None;
+            }
+        }
+    }
+}
+
+impl pd::PdPhy for UcpdPort {
+    /* A Sink Is Attached When One CC Line Sees Rp, The Other Stays Open (Or Ra For VCONN) */
+    fn get_attached(&mut self) -> bool {
+        let cc1 = self.ucpd.get_cc(CcLine::Cc1);
+        let cc2 = self.ucpd.get_cc(CcLine::Cc2);
+        let line = if cc1 != CcState::Open {
+            Some(CcLine::Cc1)
+        } else if cc2 != CcState::Open {
+            Some(CcLine::Cc2)
+        } else {
+            None
+        };
+
+        if line != self.cc {
+            self.cc = line;
+            match line {
+                Some(line) => self.ucpd.set_phy(line),
+                None => self.ucpd.clr_phy()
+            }
+        }
+        return self.cc.is_some();
+    }
+
+    fn transmit(&mut self, msg: &mut pd::Message) -> bool {
+        if self.cc.is_none() {
+            return false;
+        }
+
+        msg.set_id(self.tx_id);
+        let mut buf = [0u8; pd::MAX_MESSAGE_SIZE];
+        let size = msg.to_bytes(&mut buf);
+
+        for _ in 0..=N_RETRY_COUNT {
+            match self.ucpd.send(ORDSET_SOP, &buf[..size]) {
+                TxStatus::Sent => {
+                } TxStatus::Discarded => {
+                    /* The Partner Talked First, Its Message Takes Priority */
+                    if let Some(rx) = self.read_message() {
+                        if !rx.is_control(pd::ControlMsg::GoodCrc) {
+                            self.rx_pending = Some(rx);
+                        }
+                    }
+                    return false;
+                } TxStatus::Aborted => {
+                    continue;
+                }
+            }
+
+            let start = (self.clock)();
+            while (self.clock)().wrapping_sub(start) < T_RECEIVE {
+                if let Some(rx) = self.read_message() {
+                    if rx.is_control(pd::ControlMsg::GoodCrc) && rx.get_id() == self.tx_id {
+                        self.tx_id = (self.tx_id + 1) & 0x7;
+                        return true;
+                    } else if !rx.is_control(pd::ControlMsg::GoodCrc) {
+                        self.rx_pending = Some(rx);
+                        return false;
+                    }
+                }
+                if self.hard_reset {
+                    return false;
+                }
+            }
+        }
+
+        /* No GoodCRC After The Retries, The Message Counts As Sent And Lost */
+        self.tx_id = (self.tx_id + 1) & 0x7;
+        return false;
+    }
+
+    fn receive(&mut self) -> Option<pd::Message> {
+        if let Some(msg) = self.rx_pending.take() {
+            return Some(msg);
+        }
+        let msg = self.read_message()?;
+        if msg.is_control(pd::ControlMsg::GoodCrc) {
+            return None;
+        }
+        return Some(msg);
+    }
+
+    fn hard_reset(&mut self) {
+        self.ucpd.send_hard_reset();
+        self.tx_id = 0;
+        self.rx_id = None;
+    }
+
+    fn get_hard_reset(&mut self) -> bool {
+        let hard_reset = self.hard_reset;
+        self.hard_reset = false;
+        return hard_reset;
+    }
+
+    fn reset_protocol(&mut self) {
+        self.tx_id = 0;
+        self.rx_id = None;
+        self.rx_pending = None;
+    }
+}
+
+/* Clock The UCPD, The Kernel Clock Is HSI16 */
+pub fn clock_init(rcc_base: u32) {
+    let rcc_cr = (rcc_base + RCC_CR) as *mut u32;
+    let rcc_apb1enr2 = (rcc_base + RCC_APB1ENR2) as *mut u32;
+
+    write(rcc_cr, read(rcc_cr) | RCC_HSION);
+    while (read(rcc_cr) & RCC_HSIRDY) == 0 {}
+    write(rcc_apb1enr2, read(rcc_apb1enr2) | RCC_UCPD1EN);
+}
+
+/* Release The Dead Battery Pull Downs Once The UCPD Presents Rd Itself, PWR Has To Be Clocked */
+pub fn dead_battery_disable(pwr_base: u32) {
+    let pwr_cr3 = (pwr_base + PWR_CR3) as *mut u32;
+    write(pwr_cr3, read(pwr_cr3) | PWR_UCPD_DBDIS);
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
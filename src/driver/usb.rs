@@ -0,0 +1,997 @@
+use core::ptr;
+
+/* Universal Serial Bus Full Speed Device (USB) - Reference Manual pg 1963 */
+/* Register Level Access To The USB Peripheral And Its Packet Memory Area (PMA) */
+#[derive(Clone, Copy)]
+pub struct Usb {
+    epr:        *mut u32,       // Endpoint Register 0, Registers 1 - 7 Follow On Word Boundaries
+    cntr:       *mut u32,       // Control Register
+    istr:       *mut u32,       // Interrupt Status Register
+    fnr:        *mut u32,       // Frame Number Register
+    daddr:      *mut u32,       // Device Address Register
+    btable:     *mut u32,       // Buffer Table Address Register
+    bcdr:       *mut u32,       // Battery Charging Detector Register
+    pma:        *mut u16        // Packet Memory Area, 1024 Bytes Accessed As Half Words
+}
+
+/* Endpoint Type */
+#[derive(Clone, Copy, PartialEq)]
+pub enum EpType {
+    Bulk,
+    Control,
+    Iso,
+    Interrupt
+}
+
+/* Endpoint Status, Same Encoding For Transmit And Receive */
+#[derive(Clone, Copy, PartialEq)]
+pub enum EpStat {
+    Disabled,
+    Stall,
+    Nak,
+    Valid
+}
+
+/* Device State As Seen By The Host (USB 2.0 Chapter 9) */
+#[derive(Clone, Copy, PartialEq)]
+pub enum DeviceState {
+    Detached,
+    Default,
+    Addressed,
+    Configured,
+    Suspended
+}
+
+/* Stage Of The Control Transfer In Progress On Endpoint 0 */
+#[derive(Clone, Copy, PartialEq)]
+enum ControlStage {
+    Idle,
+    DataIn,
+    DataOut,
+    StatusIn,
+    StatusOut
+}
+
+/* Setup Packet Sent By The Host On Endpoint 0 */
+#[derive(Clone, Copy)]
+pub struct SetupPacket {
+    pub request_type:   u8,                 // Direction, Type And Recipient
+    pub request:        u8,                 // Request Code
+    pub value:          u16,                // Request Specific Value
+    pub index:          u16,                // Request Specific Index (Interface Or Endpoint)
+    pub length:         u16                 // Length Of The Data Stage
+}
+
+impl SetupPacket {
+    pub const fn init() -> SetupPacket {
+        return SetupPacket {
+            request_type:   0,
+            request:        0,
+            value:          0,
+            index:          0,
+            length:         0
+        };
+    }
+
+    fn parse(buf: &[u8; 8]) -> SetupPacket {
+        return SetupPacket {
+            request_type:   buf[0],
+            request:        buf[1],
+            value:          u16::from_le_bytes([buf[2], buf[3]]),
+            index:          u16::from_le_bytes([buf[4], buf[5]]),
+            length:         u16::from_le_bytes([buf[6], buf[7]])
+        };
+    }
+
+    pub fn is_in(&self) -> bool {
+        return (self.request_type & REQ_DIR_IN) != 0;
+    }
+
+    pub fn req_type(&self) -> u8 {
+        return self.request_type & REQ_TYPE_MASK;
+    }
+
+    pub fn recipient(&self) -> u8 {
+        return self.request_type & REQ_RECIPIENT_MASK;
+    }
+}
+
+/* Interface Between The Device State Machine And A Class (CDC, HID, ...) */
+pub trait UsbClass {
+    /* Descriptors Returned To The Host During Enumeration */
+    fn device_descriptor(&self) -> &[u8];
+    fn config_descriptor(&self) -> &[u8];
+    fn string_descriptor(&self, index: u8) -> Option<&str>;
+
+    /* Called On SET_CONFIGURATION, Open The Class Endpoints Here */
+    fn configure(&mut self, dev: &mut UsbDevice);
+    /* Called On Bus Reset Or When The Configuration Is Removed */
+    fn reset(&mut self);
+    /* Class Or Vendor Request With An IN Data Stage, Return The Length Written To buf Or None To Stall */
+    fn control_in(&mut self, setup: &SetupPacket, buf: &mut [u8]) -> Option<usize>;
+    /* Class Or Vendor Request With An OUT Or No Data Stage, Return false To Stall */
+    fn control_out(&mut self, setup: &SetupPacket, data: &[u8]) -> bool;
+    /* Data Received On An OUT Endpoint */
+    fn ep_out(&mut self, usb: &Usb, ep: u8);
+    /* Data Sent On An IN Endpoint */
+    fn ep_in(&mut self, usb: &Usb, ep: u8);
+
+    fn suspend(&mut self) {}
+    fn resume(&mut self) {}
+}
+
+/* Register Offsets */
+const CNTR:                 u32 = 0x40;
+const ISTR:                 u32 = 0x44;
+const FNR:                  u32 = 0x48;
+const DADDR:                u32 = 0x4C;
+const BTABLE:               u32 = 0x50;
+const BCDR:                 u32 = 0x58;
+const PMA:                  u32 = 0x400;
+
+/* Control Register */
+const CNTR_FRES:            u32 = 1 << 0;   // Force USB Reset
+const CNTR_PDWN:            u32 = 1 << 1;   // Power Down
+const CNTR_LP_MODE:         u32 = 1 << 2;   // Low Power Mode
+const CNTR_FSUSP:           u32 = 1 << 3;   // Force Suspend
+const CNTR_RESUME:          u32 = 1 << 4;   // Resume Request
+const CNTR_ESOFM:           u32 = 1 << 8;   // Expected Start Of Frame Interrupt Mask
+const CNTR_RESETM:          u32 = 1 << 10;  // Reset Interrupt Mask
+const CNTR_SUSPM:           u32 = 1 << 11;  // Suspend Interrupt Mask
+const CNTR_WKUPM:           u32 = 1 << 12;  // Wakeup Interrupt Mask
+const CNTR_ERRM:            u32 = 1 << 13;  // Error Interrupt Mask
+const CNTR_PMAOVRM:         u32 = 1 << 14;  // Packet Memory Overrun Mask
+const CNTR_CTRM:            u32 = 1 << 15;  // Correct Transfer Interrupt Mask
+
+/* Interrupt Status Register */
+pub const ISTR_EP_ID:       u32 = 0x000F;   // Endpoint Identifier
+pub const ISTR_ESOF:        u32 = 1 << 8;   // Expected Start Of Frame
+pub const ISTR_SOF:         u32 = 1 << 9;   // Start Of Frame
+pub const ISTR_RESET:       u32 = 1 << 10;  // USB Reset Request
+pub const ISTR_SUSP:        u32 = 1 << 11;  // Suspend Mode Request
+pub const ISTR_WKUP:        u32 = 1 << 12;  // Wakeup
+pub const ISTR_ERR:         u32 = 1 << 13;  // Error
+pub const ISTR_PMAOVR:      u32 = 1 << 14;  // Packet Memory Overrun
+pub const ISTR_CTR:         u32 = 1 << 15;  // Correct Transfer
+
+/* Device Address Register */
+const DADDR_EF:             u32 = 1 << 7;   // Enable Function
+
+/* Battery Charging Detector Register */
+const BCDR_DPPU:            u32 = 1 << 15;  // DP Pull Up Control
+
+/* Endpoint Register */
+const EP_EA:                u32 = 0x000F;   // Endpoint Address
+const EP_STAT_TX:           u32 = 0x0030;   // Status Bits For Transmission (Toggle)
+const EP_DTOG_TX:           u32 = 1 << 6;   // Data Toggle For Transmission (Toggle)
+const EP_CTR_TX:            u32 = 1 << 7;   // Correct Transfer For Transmission
+const EP_KIND:              u32 = 1 << 8;   // Endpoint Kind
+const EP_TYPE:              u32 = 0x0600;   // Endpoint Type
+const EP_SETUP:             u32 = 1 << 11;  // Setup Transaction Completed
+const EP_STAT_RX:           u32 = 0x3000;   // Status Bits For Reception (Toggle)
+const EP_DTOG_RX:           u32 = 1 << 14;  // Data Toggle For Reception (Toggle)
+const EP_CTR_RX:            u32 = 1 << 15;  // Correct Transfer For Reception
+/* Bits That Read Back As Written, Toggle Bits Are Written As Zero To Leave Them Unchanged */
+const EP_MASK:              u32 = EP_CTR_RX | EP_SETUP | EP_TYPE | EP_KIND | EP_CTR_TX | EP_EA;
+const EP_STAT_TX_SHIFT:     u32 = 4;
+const EP_STAT_RX_SHIFT:     u32 = 12;
+const EP_TYPE_SHIFT:        u32 = 9;
+
+/* Buffer Descriptor Table, Four Half Words Per Endpoint */
+const BT_ADDR_TX:           usize = 0;
+const BT_COUNT_TX:          usize = 2;
+const BT_ADDR_RX:           usize = 4;
+const BT_COUNT_RX:          usize = 6;
+const BT_ENTRY_SIZE:        usize = 8;
+const BT_COUNT_MASK:        u16 = 0x03FF;
+const BT_BL_SIZE:           u16 = 1 << 15;  // Block Size Of 32 Bytes
+const BT_NUM_BLOCK_SHIFT:   u16 = 10;
+
+/* Packet Memory */
+pub const PMA_SIZE:         u16 = 1024;
+pub const EP_COUNT:         u8 = 8;
+const PMA_BTABLE_SIZE:      u16 = (EP_COUNT as u16) * (BT_ENTRY_SIZE as u16);
+
+/* Endpoint 0 */
+pub const EP0_SIZE:         u16 = 64;
+
+/* Request Type Fields */
+pub const REQ_DIR_IN:           u8 = 0x80;
+pub const REQ_TYPE_MASK:        u8 = 0x60;
+pub const REQ_TYPE_STANDARD:    u8 = 0x00;
+pub const REQ_TYPE_CLASS:       u8 = 0x20;
+pub const REQ_TYPE_VENDOR:      u8 = 0x40;
+pub const REQ_RECIPIENT_MASK:   u8 = 0x1F;
+pub const REQ_RECIPIENT_DEVICE: u8 = 0x00;
+pub const REQ_RECIPIENT_INTF:   u8 = 0x01;
+pub const REQ_RECIPIENT_EP:     u8 = 0x02;
+
+/* Standard Requests */
+const REQ_GET_STATUS:       u8 = 0x00;
+const REQ_CLEAR_FEATURE:    u8 = 0x01;
+const REQ_SET_FEATURE:      u8 = 0x03;
+const REQ_SET_ADDRESS:      u8 = 0x05;
+const REQ_GET_DESCRIPTOR:   u8 = 0x06;
+const REQ_GET_CONFIG:       u8 = 0x08;
+const REQ_SET_CONFIG:       u8 = 0x09;
+const REQ_GET_INTERFACE:    u8 = 0x0A;
+const REQ_SET_INTERFACE:    u8 = 0x0B;
+
+/* Standard Features */
+const FEATURE_EP_HALT:      u16 = 0;
+const FEATURE_REMOTE_WAKE:  u16 = 1;
+
+/* Descriptor Types */
+pub const DESC_DEVICE:      u8 = 0x01;
+pub const DESC_CONFIG:      u8 = 0x02;
+pub const DESC_STRING:      u8 = 0x03;
+pub const DESC_INTERFACE:   u8 = 0x04;
+pub const DESC_ENDPOINT:    u8 = 0x05;
+pub const DESC_IAD:         u8 = 0x0B;
+
+/* Language ID For String Descriptor 0, English (United States) */
+const LANG_ID_EN_US:        u16 = 0x0409;
+
+/* Control Transfer Buffer, Large Enough For The Configuration And String Descriptors */
+const CTRL_BUF_SIZE:        usize = 256;
+
+/* Expected Start Of Frames To Hold Resume Signalling For A Remote Wakeup (1 - 15 ms) */
+const RESUME_ESOF:          u8 = 10;
+
+/* Clock Registers Used By clock_init */
+const RCC_CRRCR:            u32 = 0x98;
+const RCC_APB1ENR2:         u32 = 0x5C;
+const RCC_HSI48ON:          u32 = 1 << 0;
+const RCC_HSI48RDY:         u32 = 1 << 1;
+const RCC_USBFSEN:          u32 = 1 << 21;
+const PWR_CR2:              u32 = 0x04;
+const PWR_USV:              u32 = 1 << 10;
+const CRS_CEN:              u32 = 1 << 5;
+const CRS_AUTOTRIMEN:       u32 = 1 << 6;
+
+impl Usb {
+    pub fn init(base: u32) -> Usb {
+        return Usb {
+            epr:        base as *mut u32,
+            cntr:       (base + CNTR) as *mut u32,
+            istr:       (base + ISTR) as *mut u32,
+            fnr:        (base + FNR) as *mut u32,
+            daddr:      (base + DADDR) as *mut u32,
+            btable:     (base + BTABLE) as *mut u32,
+            bcdr:       (base + BCDR) as *mut u32,
+            pma:        (base + PMA) as *mut u16
+        };
+    }
+
+    /* Power Up The Transceiver, Unmask The Interrupts Used And Connect To The Bus */
+    pub fn open(&self) {
+        write(self.cntr, CNTR_FRES);
+        /* Transceiver Startup Time (tSTARTUP = 1 us) */
+        for _ in 0..100 {
+            unsafe { core::arch::asm!("nop") };
+        }
+        write(self.cntr, 0);
+        write(self.istr, 0);
+        write(self.btable, 0);
+        write(self.cntr, CNTR_CTRM | CNTR_RESETM | CNTR_SUSPM | CNTR_WKUPM | CNTR_ERRM | CNTR_PMAOVRM | CNTR_ESOFM);
+        self.connect();
+    }
+
+    /* Disconnect From The Bus And Power Down The Transceiver */
+    pub fn close(&self) {
+        self.disconnect();
+        write(self.cntr, CNTR_FRES | CNTR_PDWN);
+        write(self.istr, 0);
+    }
+
+    /* Enable The DP Pull Up, The Host Sees The Device Attach */
+    pub fn connect(&self) {
+        write(self.bcdr, read(self.bcdr) | BCDR_DPPU);
+    }
+
+    pub fn disconnect(&self) {
+        write(self.bcdr, read(self.bcdr) & !BCDR_DPPU);
+    }
+
+    pub fn get_istr(&self) -> u32 {
+        return read(self.istr);
+    }
+
+    /* Interrupt Flags Are Cleared By Writing 0, Writing 1 Leaves Them Unchanged */
+    pub fn clr_istr(&self, flags: u32) {
+        write(self.istr, !flags & 0xFFFF);
+    }
+
+    pub fn get_frame(&self) -> u16 {
+        return (read(self.fnr) & 0x07FF) as u16;
+    }
+
+    pub fn set_address(&self, addr: u8) {
+        write(self.daddr, DADDR_EF | (addr as u32 & 0x7F));
+    }
+
+    /* Suspend The Transceiver, Force Suspend Has To Be Set Before Low Power Mode */
+    pub fn suspend(&self) {
+        write(self.cntr, read(self.cntr) | CNTR_FSUSP);
+        write(self.cntr, read(self.cntr) | CNTR_LP_MODE);
+    }
+
+    pub fn wakeup(&self) {
+        write(self.cntr, read(self.cntr) & !(CNTR_LP_MODE | CNTR_FSUSP));
+    }
+
+    pub fn set_resume(&self) {
+        write(self.cntr, read(self.cntr) | CNTR_RESUME);
+    }
+
+    pub fn clr_resume(&self) {
+        write(self.cntr, read(self.cntr) & !CNTR_RESUME);
+    }
+
+    /* Configure An Endpoint Register And Its Buffer Descriptors, An Address Of 0 Leaves That Direction Unused */
+    pub fn ep_open(&self, ep: u8, ep_type: EpType, tx_addr: u16, rx_addr: u16, rx_size: u16) {
+        let reg = self.ep_read_reg(ep);
+        let val = (reg & (EP_MASK & !(EP_TYPE | EP_KIND | EP_EA))) | EP_CTR_RX | EP_CTR_TX | ((ep_type as u32) << EP_TYPE_SHIFT) | (ep as u32 & EP_EA);
+        self.ep_write_reg(ep, val);
+
+        if tx_addr != 0 {
+            self.bt_write(ep, BT_ADDR_TX, tx_addr);
+            self.bt_write(ep, BT_COUNT_TX, 0);
+            self.ep_clr_dtog_tx(ep);
+            self.ep_set_stat_tx(ep, EpStat::Nak);
+        }
+
+        if rx_addr != 0 {
+            self.bt_write(ep, BT_ADDR_RX, rx_addr);
+            self.bt_write(ep, BT_COUNT_RX, rx_count_block(rx_size));
+            self.ep_clr_dtog_rx(ep);
+            self.ep_set_stat_rx(ep, EpStat::Valid);
+        }
+    }
+
+    pub fn ep_close(&self, ep: u8) {
+        self.ep_set_stat_tx(ep, EpStat::Disabled);
+        self.ep_set_stat_rx(ep, EpStat::Disabled);
+    }
+
+    pub fn ep_get_stat_tx(&self, ep: u8) -> EpStat {
+        return stat((self.ep_read_reg(ep) & EP_STAT_TX) >> EP_STAT_TX_SHIFT);
+    }
+
+    pub fn ep_get_stat_rx(&self, ep: u8) -> EpStat {
+        return stat((self.ep_read_reg(ep) & EP_STAT_RX) >> EP_STAT_RX_SHIFT);
+    }
+
+    /* Status Bits Toggle When Written With 1, Write The Difference To Reach The Requested State */
+    pub fn ep_set_stat_tx(&self, ep: u8, ep_stat: EpStat) {
+        let reg = self.ep_read_reg(ep);
+        let val = (reg & EP_MASK) | EP_CTR_RX | EP_CTR_TX | ((reg & EP_STAT_TX) ^ ((ep_stat as u32) << EP_STAT_TX_SHIFT));
+        self.ep_write_reg(ep, val);
+    }
+
+    pub fn ep_set_stat_rx(&self, ep: u8, ep_stat: EpStat) {
+        let reg = self.ep_read_reg(ep);
+        let val = (reg & EP_MASK) | EP_CTR_RX | EP_CTR_TX | ((reg & EP_STAT_RX) ^ ((ep_stat as u32) << EP_STAT_RX_SHIFT));
+        self.ep_write_reg(ep, val);
+    }
+
+    pub fn ep_get_ctr_rx(&self, ep: u8) -> bool {
+        return (self.ep_read_reg(ep) & EP_CTR_RX) != 0;
+    }
+
+    pub fn ep_get_ctr_tx(&self, ep: u8) -> bool {
+        return (self.ep_read_reg(ep) & EP_CTR_TX) != 0;
+    }
+
+    pub fn ep_get_setup(&self, ep: u8) -> bool {
+        return (self.ep_read_reg(ep) & EP_SETUP) != 0;
+    }
+
+    pub fn ep_clr_ctr_rx(&self, ep: u8) {
+        let reg = self.ep_read_reg(ep);
+        self.ep_write_reg(ep, (reg & EP_MASK & !EP_CTR_RX) | EP_CTR_TX);
+    }
+
+    pub fn ep_clr_ctr_tx(&self, ep: u8) {
+        let reg = self.ep_read_reg(ep);
+        self.ep_write_reg(ep, (reg & EP_MASK & !EP_CTR_TX) | EP_CTR_RX);
+    }
+
+    pub fn ep_clr_dtog_tx(&self, ep: u8) {
+        let reg = self.ep_read_reg(ep);
+        if (reg & EP_DTOG_TX) != 0 {
+            self.ep_write_reg(ep, (reg & EP_MASK) | EP_CTR_RX | EP_CTR_TX | EP_DTOG_TX);
+        }
+    }
+
+    pub fn ep_clr_dtog_rx(&self, ep: u8) {
+        let reg = self.ep_read_reg(ep);
+        if (reg & EP_DTOG_RX) != 0 {
+            self.ep_write_reg(ep, (reg & EP_MASK) | EP_CTR_RX | EP_CTR_TX | EP_DTOG_RX);
+        }
+    }
+
+    /* Copy A Packet Into The Transmit Buffer And Hand It To The Host On The Next IN Token */
+    pub fn ep_write(&self, ep: u8, buf: &[u8]) {
+        let addr = self.bt_read(ep, BT_ADDR_TX);
+        self.pma_write(addr, buf);
+        self.bt_write(ep, BT_COUNT_TX, buf.len() as u16);
+        self.ep_set_stat_tx(ep, EpStat::Valid);
+    }
+
+    /* Copy A Received Packet Out Of The Receive Buffer, Returns The Packet Length */
+    pub fn ep_read(&self, ep: u8, buf: &mut [u8]) -> usize {
+        let addr = self.bt_read(ep, BT_ADDR_RX);
+        let count = (self.bt_read(ep, BT_COUNT_RX) & BT_COUNT_MASK) as usize;
+        let len = if count < buf.len() { count } else { buf.len() };
+        self.pma_read(addr, &mut buf[..len]);
+        return len;
+    }
+
+    /* Endpoint Is Ready To Accept A New Packet To Transmit */
+    pub fn ep_tx_ready(&self, ep: u8) -> bool {
+        return self.ep_get_stat_tx(ep) != EpStat::Valid;
+    }
+
+    fn ep_read_reg(&self, ep: u8) -> u32 {
+        return read(unsafe { self.epr.add(ep as usize) });
+    }
+
+    fn ep_write_reg(&self, ep: u8, val: u32) {
+        write(unsafe { self.epr.add(ep as usize) }, val);
+    }
+
+    fn bt_read(&self, ep: u8, offset: usize) -> u16 {
+        return unsafe { ptr::read_volatile(self.pma.add(((ep as usize) * BT_ENTRY_SIZE + offset) / 2)) };
+    }
+
+    fn bt_write(&self, ep: u8, offset: usize, val: u16) {
+        unsafe { ptr::write_volatile(self.pma.add(((ep as usize) * BT_ENTRY_SIZE + offset) / 2), val) };
+    }
+
+    /* The Packet Memory Only Supports Half Word Access, Bytes Are Packed Little Endian */
+    fn pma_write(&self, addr: u16, buf: &[u8]) {
+        let base = (addr as usize) / 2;
+        for (i, pair) in buf.chunks(2).enumerate() {
+            let hi = if pair.len() > 1 { pair[1] } else { 0 };
+            unsafe { ptr::write_volatile(self.pma.add(base + i), u16::from_le_bytes([pair[0], hi])) };
+        }
+    }
+
+    fn pma_read(&self, addr: u16, buf: &mut [u8]) {
+        let base = (addr as usize) / 2;
+        for (i, pair) in buf.chunks_mut(2).enumerate() {
+            let val = unsafe { ptr::read_volatile(self.pma.add(base + i)) }.to_le_bytes();
+            pair[0] = val[0];
+            if pair.len() > 1 {
+                pair[1] = val[1];
+            }
+        }
+    }
+}
+
+/* Device State Machine, Handles Enumeration On Endpoint 0 And Dispatches Everything Else To The Class */
+pub struct UsbDevice {
+    usb:            Usb,                        // Peripheral Registers
+    state:          DeviceState,                // Current Device State
+    suspend_state:  DeviceState,                // State To Return To On Resume
+    address:        u8,                         // Address Applied After The Status Stage
+    config:         u8,                         // Selected Configuration
+    remote_wakeup:  bool,                       // Remote Wakeup Enabled By The Host
+    resume_count:   u8,                         // Expected Start Of Frames Left Of Resume Signalling
+    pma_next:       u16,                        // Next Free Byte In The Packet Memory
+    pma_class:      u16,                        // First Byte After The Endpoint 0 Buffers, Where The Class's Start
+    stage:          ControlStage,               // Control Transfer Stage
+    setup:          SetupPacket,                // Last Setup Packet
+    ctrl_buf:       [u8; CTRL_BUF_SIZE],        // Control Data Stage Buffer
+    ctrl_len:       usize,                      // Length Of The Data Stage
+    ctrl_pos:       usize,                      // Bytes Of The Data Stage Transferred
+    ctrl_zlp:       bool                        // Data Stage Ends With A Zero Length Packet
+}
+
+impl UsbDevice {
+    pub fn init(usb: Usb) -> UsbDevice {
+        return UsbDevice {
+            usb,
+            state:          DeviceState::Detached,
+            suspend_state:  DeviceState::Detached,
+            address:        0,
+            config:         0,
+            remote_wakeup:  false,
+            resume_count:   0,
+            pma_next:       PMA_BTABLE_SIZE,
+            pma_class:      PMA_BTABLE_SIZE,
+            stage:          ControlStage::Idle,
+            setup:          SetupPacket::init(),
+            ctrl_buf:       [0; CTRL_BUF_SIZE],
+            ctrl_len:       0,
+            ctrl_pos:       0,
+            ctrl_zlp:       false
+        };
+    }
+
+    pub fn open(&mut self) {
+        self.state = DeviceState::Detached;
+        self.usb.open();
+    }
+
+    pub fn close<C: UsbClass>(&mut self, class: &mut C) {
+        self.usb.close();
+        class.reset();
+        self.state = DeviceState::Detached;
+    }
+
+    pub fn get_state(&self) -> DeviceState {
+        return self.state;
+    }
+
+    pub fn get_usb(&self) -> Usb {
+        return self.usb;
+    }
+
+    /* Allocate A Buffer In The Packet Memory, Released On The Next Bus Reset Or SET_CONFIGURATION */
+    pub fn pma_alloc(&mut self, size: u16) -> Option<u16> {
+        let size = (size + 1) & !1;
+        if self.pma_next + size > PMA_SIZE {
+            return None;
+        }
+        let addr = self.pma_next;
+        self.pma_next += size;
+        return Some(addr);
+    }
+
+    /* Open An Endpoint With Buffers In The Packet Memory, A Size Of 0 Leaves That Direction Unused */
+    pub fn ep_open(&mut self, ep: u8, ep_type: EpType, tx_size: u16, rx_size: u16) -> bool {
+        if ep == 0 || ep >= EP_COUNT {
+            return false;
+        }
+
+        let tx_addr = if tx_size > 0 {
+            match self.pma_alloc(tx_size) {
+                Some(addr) => addr,
+                None => return false
+            }
+        } else {
+            0
+        };
+
+        let rx_addr = if rx_size > 0 {
+            match self.pma_alloc(rx_block_size(rx_size)) {
+                Some(addr) => addr,
+                None => return false
+            }
+        } else {
+            0
+        };
+
+        self.usb.ep_open(ep, ep_type, tx_addr, rx_addr, rx_size);
+        return true;
+    }
+
+    /* Signal Resume To A Suspended Host, Only Allowed When The Host Enabled Remote Wakeup */
+    pub fn remote_wakeup<C: UsbClass>(&mut self, class: &mut C) -> bool {
+        if self.state != DeviceState::Suspended || !self.remote_wakeup {
+            return false;
+        }
+        self.usb.wakeup();
+        self.usb.set_resume();
+        self.resume_count = RESUME_ESOF;
+        self.state = self.suspend_state;
+        class.resume();
+        return true;
+    }
+
+    /* Service The Peripheral, Call From The USB_FS Interrupt Or Periodically From The Main Loop */
+    pub fn poll<C: UsbClass>(&mut self, class: &mut C) {
+        let istr = self.usb.get_istr();
+
+        if (istr & ISTR_RESET) != 0 {
+            self.usb.clr_istr(ISTR_RESET);
+            self.bus_reset(class);
+        }
+
+        if (istr & ISTR_WKUP) != 0 {
+            self.usb.wakeup();
+            self.usb.clr_istr(ISTR_WKUP);
+            if self.state == DeviceState::Suspended {
+                self.state = self.suspend_state;
+                class.resume();
+            }
+        }
+
+        if (istr & ISTR_SUSP) != 0 {
+            self.usb.suspend();
+            self.usb.clr_istr(ISTR_SUSP);
+            if self.state != DeviceState::Suspended && self.state != DeviceState::Detached {
+                self.suspend_state = self.state;
+                self.state = DeviceState::Suspended;
+                class.suspend();
+            }
+        }
+
+        if (istr & ISTR_ESOF) != 0 {
+            self.usb.clr_istr(ISTR_ESOF);
+            if self.resume_count > 0 {
+                self.resume_count -= 1;
+                if self.resume_count == 0 {
+                    self.usb.clr_resume();
+                }
+            }
+        }
+
+        if (istr & (ISTR_ERR | ISTR_PMAOVR | ISTR_SOF)) != 0 {
+            self.usb.clr_istr(ISTR_ERR | ISTR_PMAOVR | ISTR_SOF);
+        }
+
+        /* Correct Transfers Are Queued, Service Them Until The Flag Stays Clear */
+        while (self.usb.get_istr() & ISTR_CTR) != 0 {
+            let ep = (self.usb.get_istr() & ISTR_EP_ID) as u8;
+
+            if ep == 0 {
+                self.ep0(class);
+            } else {
+                if self.usb.ep_get_ctr_rx(ep) {
+                    self.usb.ep_clr_ctr_rx(ep);
+                    class.ep_out(&self.usb, ep);
+                }
+                if self.usb.ep_get_ctr_tx(ep) {
+                    self.usb.ep_clr_ctr_tx(ep);
+                    class.ep_in(&self.usb, ep);
+                }
+            }
+        }
+    }
+
+    /* Bus Reset, Back To The Default State With Only Endpoint 0 Open At Address 0 */
+    fn bus_reset<C: UsbClass>(&mut self, class: &mut C) {
+        self.ep_release();
+        class.reset();
+
+        self.pma_next = PMA_BTABLE_SIZE;
+        let tx_addr = self.pma_alloc(EP0_SIZE).unwrap_or(0);
+        let rx_addr = self.pma_alloc(EP0_SIZE).unwrap_or(0);
+        self.pma_class = self.pma_next;
+        self.usb.ep_open(0, EpType::Control, tx_addr, rx_addr, EP0_SIZE);
+        self.usb.set_address(0);
+
+        self.state = DeviceState::Default;
+        self.address = 0;
+        self.config = 0;
+        self.remote_wakeup = false;
+        self.stage = ControlStage::Idle;
+    }
+
+    /* Close The Class Endpoints And Free Their Packet Memory, Endpoint 0 Keeps Its Buffers */
+    fn ep_release(&mut self) {
+        for ep in 1..EP_COUNT {
+            self.usb.ep_close(ep);
+        }
+        self.pma_next = self.pma_class;
+    }
+
+    fn ep0<C: UsbClass>(&mut self, class: &mut C) {
+        if self.usb.ep_get_ctr_tx(0) {
+            self.usb.ep_clr_ctr_tx(0);
+            self.ep0_in();
+        }
+
+        if self.usb.ep_get_ctr_rx(0) {
+            let setup = self.usb.ep_get_setup(0);
+            if setup {
+                let mut buf = [0u8; 8];
+                self.usb.ep_read(0, &mut buf);
+                self.usb.ep_clr_ctr_rx(0);
+                self.setup = SetupPacket::parse(&buf);
+                self.handle_setup(class);
+            } else {
+                self.usb.ep_clr_ctr_rx(0);
+                self.ep0_out(class);
+            }
+        }
+    }
+
+    /* IN Transaction Completed On Endpoint 0 */
+    fn ep0_in(&mut self) {
+        match self.stage {
+            ControlStage::DataIn => {
+                if self.ctrl_pos < self.ctrl_len || self.ctrl_zlp {
+                    self.ep0_send_next();
+                } else {
+                    self.stage = ControlStage::StatusOut;
+                    self.usb.ep_set_stat_rx(0, EpStat::Valid);
+                }
+            } ControlStage::StatusIn => {
+                /* The New Address Only Applies Once The Status Stage Of SET_ADDRESS Completes */
+                if self.address != 0 {
+                    self.usb.set_address(self.address);
+                    self.address = 0;
+                    if self.state == DeviceState::Default {
+                        self.state = DeviceState::Addressed;
+                    }
+                }
+                self.stage = ControlStage::Idle;
+                self.usb.ep_set_stat_rx(0, EpStat::Valid);
+            } _ => {
+                self.stage = ControlStage::Idle;
+            }
+        }
+    }
+
+    /* OUT Transaction Completed On Endpoint 0 */
+    fn ep0_out<C: UsbClass>(&mut self, class: &mut C) {
+        match self.stage {
+            ControlStage::DataOut => {
+                let pos = self.ctrl_pos;
+                let len = self.ctrl_len;
+                let count = self.usb.ep_read(0, &mut self.ctrl_buf[pos..len]);
+                self.ctrl_pos += count;
+
+                if self.ctrl_pos >= self.ctrl_len || count < EP0_SIZE as usize {
+                    let setup = self.setup;
+                    if class.control_out(&setup, &self.ctrl_buf[..self.ctrl_pos]) {
+                        self.ep0_status_in();
+                    } else {
+                        self.ep0_stall();
+                    }
+                } else {
+                    self.usb.ep_set_stat_rx(0, EpStat::Valid);
+                }
+            } _ => {
+                /* Status Stage Of An IN Transfer Or An Aborted Transfer */
+                self.stage = ControlStage::Idle;
+                self.usb.ep_set_stat_rx(0, EpStat::Valid);
+            }
+        }
+    }
+
+    fn handle_setup<C: UsbClass>(&mut self, class: &mut C) {
+        let setup = self.setup;
+        self.ctrl_pos = 0;
+        self.ctrl_len = 0;
+        self.ctrl_zlp = false;
+
+        if setup.req_type() == REQ_TYPE_STANDARD {
+            self.standard_request(class, &setup);
+            return;
+        }
+
+        if setup.is_in() {
+            match class.control_in(&setup, &mut self.ctrl_buf) {
+                Some(len) => self.ep0_send(len),
+                None => self.ep0_stall()
+            }
+        } else if setup.length > 0 {
+            self.ep0_receive(setup.length as usize);
+        } else if class.control_out(&setup, &[]) {
+            self.ep0_status_in();
+        } else {
+            self.ep0_stall();
+        }
+    }
+
+    fn standard_request<C: UsbClass>(&mut self, class: &mut C, setup: &SetupPacket) {
+        match setup.request {
+            REQ_GET_STATUS => {
+                let mut status = 0u8;
+                if setup.recipient() == REQ_RECIPIENT_DEVICE && self.remote_wakeup {
+                    status |= 1 << 1;
+                } else if setup.recipient() == REQ_RECIPIENT_EP {
+                    let ep = (setup.index & 0x0F) as u8;
+                    let halted = if (setup.index as u8 & REQ_DIR_IN) != 0 {
+                        self.usb.ep_get_stat_tx(ep) == EpStat::Stall
+                    } else {
+                        self.usb.ep_get_stat_rx(ep) == EpStat::Stall
+                    };
+                    status = halted as u8;
+                }
+                self.ctrl_buf[0] = status;
+                self.ctrl_buf[1] = 0;
+                self.ep0_send(2);
+            } REQ_CLEAR_FEATURE | REQ_SET_FEATURE => {
+                let set = setup.request == REQ_SET_FEATURE;
+                if setup.recipient() == REQ_RECIPIENT_DEVICE && setup.value == FEATURE_REMOTE_WAKE {
+                    self.remote_wakeup = set;
+                    self.ep0_status_in();
+                } else if setup.recipient() == REQ_RECIPIENT_EP && setup.value == FEATURE_EP_HALT && (setup.index & 0x0F) != 0 {
+                    let ep = (setup.index & 0x0F) as u8;
+                    if (setup.index as u8 & REQ_DIR_IN) != 0 {
+                        if !set {
+                            self.usb.ep_clr_dtog_tx(ep);
+                        }
+                        self.usb.ep_set_stat_tx(ep, if set { EpStat::Stall } else { EpStat::Nak });
+                    } else {
+                        if !set {
+                            self.usb.ep_clr_dtog_rx(ep);
+                        }
+                        self.usb.ep_set_stat_rx(ep, if set { EpStat::Stall } else { EpStat::Valid });
+                    }
+                    self.ep0_status_in();
+                } else {
+                    self.ep0_stall();
+                }
+            } REQ_SET_ADDRESS => {
+                self.address = (setup.value & 0x7F) as u8;
+                if self.address == 0 {
+                    self.state = DeviceState::Default;
+                }
+                self.ep0_status_in();
+            } REQ_GET_DESCRIPTOR => {
+                match self.get_descriptor(class, setup) {
+                    Some(len) => self.ep0_send(len),
+                    None => self.ep0_stall()
+                }
+            } REQ_GET_CONFIG => {
+                self.ctrl_buf[0] = self.config;
+                self.ep0_send(1);
+            } REQ_SET_CONFIG => {
+                let config = (setup.value & 0xFF) as u8;
+                if self.state == DeviceState::Default {
+                    self.ep0_stall();
+                } else if config == 0 {
+                    self.ep_release();
+                    class.reset();
+                    self.config = 0;
+                    self.state = DeviceState::Addressed;
+                    self.ep0_status_in();
+                } else if config == 1 {
+                    if self.config != config {
+                        self.ep_release();
+                        self.config = config;
+                        class.configure(self);
+                    }
+                    self.state = DeviceState::Configured;
+                    self.ep0_status_in();
+                } else {
+                    self.ep0_stall();
+                }
+            } REQ_GET_INTERFACE => {
+                self.ctrl_buf[0] = 0;
+                self.ep0_send(1);
+            } REQ_SET_INTERFACE => {
+                /* Only The Default Alternate Setting Is Supported */
+                if setup.value == 0 {
+                    self.ep0_status_in();
+                } else {
+                    self.ep0_stall();
+                }
+            } _ => {
+                self.ep0_stall();
+            }
+        }
+    }
+
+    /* Copy The Requested Descriptor Into The Control Buffer, Returns Its Length */
+    fn get_descriptor<C: UsbClass>(&mut self, class: &mut C, setup: &SetupPacket) -> Option<usize> {
+        let desc_type = (setup.value >> 8) as u8;
+        let desc_index = (setup.value & 0xFF) as u8;
+
+        match desc_type {
+            DESC_DEVICE => {
+                return Some(copy_into(&mut self.ctrl_buf, class.device_descriptor()));
+            } DESC_CONFIG => {
+                return Some(copy_into(&mut self.ctrl_buf, class.config_descriptor()));
+            } DESC_STRING => {
+                if desc_index == 0 {
+                    let lang = LANG_ID_EN_US.to_le_bytes();
+                    return Some(copy_into(&mut self.ctrl_buf, &[4, DESC_STRING, lang[0], lang[1]]));
+                }
+                let string = class.string_descriptor(desc_index)?;
+                /* Strings Are Sent As UTF-16LE, Only The ASCII Range Is Supported */
+                let mut len = 2;
+                for c in string.bytes() {
+                    if len + 2 > self.ctrl_buf.len() || len + 2 > 0xFF {
+                        break;
+                    }
+                    self.ctrl_buf[len] = c;
+                    self.ctrl_buf[len + 1] = 0;
+                    len += 2;
+                }
+                self.ctrl_buf[0] = len as u8;
+                self.ctrl_buf[1] = DESC_STRING;
+                return Some(len);
+            } _ => {
+                /* Device Qualifier And Other Speed Descriptors Do Not Exist On A Full Speed Only Device */
+                return None;
+            }
+        }
+    }
+
+    /* Start An IN Data Stage From The Control Buffer, Trimmed To What The Host Asked For */
+    fn ep0_send(&mut self, len: usize) {
+        let requested = self.setup.length as usize;
+        self.ctrl_len = if len < requested { len } else { requested };
+        self.ctrl_pos = 0;
+        self.ctrl_zlp = self.ctrl_len < requested && self.ctrl_len % (EP0_SIZE as usize) == 0;
+        self.stage = ControlStage::DataIn;
+        self.ep0_send_next();
+    }
+
+    fn ep0_send_next(&mut self) {
+        let remaining = self.ctrl_len - self.ctrl_pos;
+        let count = if remaining > EP0_SIZE as usize { EP0_SIZE as usize } else { remaining };
+        if count == 0 {
+            self.ctrl_zlp = false;
+        }
+        self.usb.ep_write(0, &self.ctrl_buf[self.ctrl_pos..self.ctrl_pos + count]);
+        self.ctrl_pos += count;
+    }
+
+    /* Start An OUT Data Stage Into The Control Buffer */
+    fn ep0_receive(&mut self, len: usize) {
+        if len > self.ctrl_buf.len() {
+            self.ep0_stall();
+            return;
+        }
+        self.ctrl_len = len;
+        self.ctrl_pos = 0;
+        self.stage = ControlStage::DataOut;
+        self.usb.ep_set_stat_rx(0, EpStat::Valid);
+    }
+
+    /* Zero Length Status Stage For Requests Without An IN Data Stage */
+    fn ep0_status_in(&mut self) {
+        self.stage = ControlStage::StatusIn;
+        self.usb.ep_write(0, &[]);
+    }
+
+    fn ep0_stall(&mut self) {
+        self.stage = ControlStage::Idle;
+        self.usb.ep_set_stat_tx(0, EpStat::Stall);
+        self.usb.ep_set_stat_rx(0, EpStat::Stall);
+    }
+}
+
+/* Clock The USB From HSI48, Trimmed To The Host Start Of Frame By The Clock Recovery System */
+/* PWR And CRS Have To Be Clocked Before This Is Called */
+pub fn clock_init(rcc_base: u32, pwr_base: u32, crs_base: u32) {
+    let rcc_crrcr = (rcc_base + RCC_CRRCR) as *mut u32;
+    let rcc_apb1enr2 = (rcc_base + RCC_APB1ENR2) as *mut u32;
+    let pwr_cr2 = (pwr_base + PWR_CR2) as *mut u32;
+    let crs_cr = crs_base as *mut u32;
+
+    write(rcc_crrcr, read(rcc_crrcr) | RCC_HSI48ON);
+    while (read(rcc_crrcr) & RCC_HSI48RDY) == 0 {}
+
+    /* USB Transceiver Supply Is Isolated Until Declared Valid */
+    write(pwr_cr2, read(pwr_cr2) | PWR_USV);
+    write(rcc_apb1enr2, read(rcc_apb1enr2) | RCC_USBFSEN);
+    write(crs_cr, read(crs_cr) | CRS_AUTOTRIMEN | CRS_CEN);
+}
+
+/* Receive Buffers Are Sized In Blocks, 2 Bytes Up To 62 Bytes, 32 Bytes Above */
+fn rx_block_size(size: u16) -> u16 {
+    if size > 62 {
+        return (size + 31) & !31;
+    }
+    return (size + 1) & !1;
+}
+
+fn rx_count_block(size: u16) -> u16 {
+    if size > 62 {
+        return BT_BL_SIZE | ((((size + 31) / 32) - 1) << BT_NUM_BLOCK_SHIFT);
+    }
+    return ((size + 1) / 2) << BT_NUM_BLOCK_SHIFT;
+}
+
+fn stat(val: u32) -> EpStat {
+    return match val {
+        0 => EpStat::Disabled,
+        1 => EpStat::Stall,
+        2 => EpStat::Nak,
+        _ => EpStat::Valid
+    };
+}
+
+fn copy_into(buf: &mut [u8], src: &[u8]) -> usize {
+    let len = if src.len() < buf.len() { src.len() } else { buf.len() };
+    buf[..len].copy_from_slice(&src[..len]);
+    return len;
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
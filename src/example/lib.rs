@@ -3,12 +3,15 @@
 use core::panic::PanicInfo;
 
 mod board;
+mod startup;
 mod hal;
 mod axis;
+mod sync;
 
 const CLK:                  hal::common::MsiRange = hal::common::MsiRange::Clk16MHz;
 
-static mut MOTORSTRUCT:     axis::MotorControl = axis::MotorControl::init();
+/* Shared With TIM3_IRQHandler, Only Reached Through lock */
+static MOTORSTRUCT:         sync::Mutex<axis::MotorControl> = sync::Mutex::init(axis::MotorControl::init());
 
 #[no_mangle]
 pub extern "C" fn _system_init() {
@@ -28,7 +31,6 @@ pub extern "C" fn _system_init() {
 #[no_mangle]
 pub extern "C" fn _start() {
     let freq = hal::common::range(CLK);
-    let motors = unsafe {&mut MOTORSTRUCT};
     // Initialize the LED on L432KC board
     let gpioa =     hal::gpio::Gpio::init(board::l552ze::GPIOA_BASE);
     let gpiob =     hal::gpio::Gpio::init(board::l552ze::GPIOB_BASE);
@@ -62,13 +64,15 @@ pub extern "C" fn _start() {
     pwm_timer.set_interrupt();
     nvic.set_interrupt(board::l552ze::NvicIrq::TIM3_IRQ as u32);
 
-    motors.set_motor_count(axis::Motors::Motor1, 15);
-    motors.set_motor_state(axis::Motors::Motor1, axis::MotorState::PreOperational);
-    motors.set_motor_count(axis::Motors::Motor2, 10);
-    motors.set_motor_state(axis::Motors::Motor2, axis::MotorState::PreOperational);
-    motors.set_motor_count(axis::Motors::Motor3, 5);
-    motors.set_motor_state(axis::Motors::Motor3, axis::MotorState::PreOperational);
-    motors.set_state(axis::MotorState::Stopped);
+    MOTORSTRUCT.lock(|motors| {
+        motors.set_motor_count(axis::Motors::Motor1, 15);
+        motors.set_motor_count(axis::Motors::Motor2, 10);
+        motors.set_motor_count(axis::Motors::Motor3, 5);
+        for &motor in axis::MOTORS.iter() {
+            transition(motors, &pwm_timer, motor, axis::MotorState::PreOperational);
+            transition(motors, &pwm_timer, motor, axis::MotorState::Stopped);
+        }
+    });
 
     let mut i = 0;
     let mut j = 0;
@@ -85,45 +89,55 @@ pub extern "C" fn _start() {
                 i = 0;  
             }
 
-            if motors.check_stopped(motors.get_state()) {
-                if j == 0 {
-                    motors.clr_count();
-                    pwm_timer.set_scl(1000, freq, freq);
-                    pwm_timer.set_pwm_ccr1(600);
-                    pwm_timer.set_pwm_ccr2(500);
-                    pwm_timer.set_pwm_ccr3(400);
-                    motors.set_state(axis::MotorState::Operational);
-                    pwm_timer.start();
-                    j += 1;
-                } else if j == 1 {
-                    motors.clr_count();
-                    pwm_timer.set_scl(800, freq, freq);
-                    pwm_timer.set_pwm_ccr1(300);
-                    pwm_timer.set_pwm_ccr2(400);
-                    pwm_timer.set_pwm_ccr3(500);
-                    motors.set_state(axis::MotorState::Operational);
-                    pwm_timer.start();
-                    j += 1;
-                } else if j == 2 {
-                    motors.clr_count();
-                    pwm_timer.set_scl(500, freq, freq);
-                    pwm_timer.set_pwm_ccr1(300);
-                    pwm_timer.set_pwm_ccr2(250);
-                    pwm_timer.set_pwm_ccr3(200);
-                    motors.set_state(axis::MotorState::Operational);
-                    pwm_timer.start();
-                    j += 1;
-                } else {
-                    motors.clr_count();
-                    pwm_timer.set_scl(400, freq, freq);
-                    pwm_timer.set_pwm_ccr1(200);
-                    pwm_timer.set_pwm_ccr2(200);
-                    pwm_timer.set_pwm_ccr3(200);
-                    motors.set_state(axis::MotorState::Operational);
-                    pwm_timer.start();
-                    j = 0;
+            MOTORSTRUCT.lock(|motors| {
+                if motors.check_stopped(motors.get_state()) {
+                    if j == 0 {
+                        motors.clr_count();
+                        pwm_timer.set_scl(1000, freq, freq);
+                        pwm_timer.set_pwm_ccr1(600);
+                        pwm_timer.set_pwm_ccr2(500);
+                        pwm_timer.set_pwm_ccr3(400);
+                        transition(motors, &pwm_timer, axis::Motors::Motor1, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor2, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor3, axis::MotorState::Operational);
+                        pwm_timer.start();
+                        j += 1;
+                    } else if j == 1 {
+                        motors.clr_count();
+                        pwm_timer.set_scl(800, freq, freq);
+                        pwm_timer.set_pwm_ccr1(300);
+                        pwm_timer.set_pwm_ccr2(400);
+                        pwm_timer.set_pwm_ccr3(500);
+                        transition(motors, &pwm_timer, axis::Motors::Motor1, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor2, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor3, axis::MotorState::Operational);
+                        pwm_timer.start();
+                        j += 1;
+                    } else if j == 2 {
+                        motors.clr_count();
+                        pwm_timer.set_scl(500, freq, freq);
+                        pwm_timer.set_pwm_ccr1(300);
+                        pwm_timer.set_pwm_ccr2(250);
+                        pwm_timer.set_pwm_ccr3(200);
+                        transition(motors, &pwm_timer, axis::Motors::Motor1, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor2, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor3, axis::MotorState::Operational);
+                        pwm_timer.start();
+                        j += 1;
+                    } else {
+                        motors.clr_count();
+                        pwm_timer.set_scl(400, freq, freq);
+                        pwm_timer.set_pwm_ccr1(200);
+                        pwm_timer.set_pwm_ccr2(200);
+                        pwm_timer.set_pwm_ccr3(200);
+                        transition(motors, &pwm_timer, axis::Motors::Motor1, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor2, axis::MotorState::Operational);
+                        transition(motors, &pwm_timer, axis::Motors::Motor3, axis::MotorState::Operational);
+                        pwm_timer.start();
+                        j = 0;
+                    }
                 }
-            }
+            });
 
             i += 1;
             seq_timer.clr_flag();
@@ -136,55 +150,73 @@ pub extern "C" fn __aeabi_unwind_cpp_pr0() {
     loop {}
 }
 
-#[no_mangle]
-pub extern "C" fn TIM3_IRQHandler() {
+/* Motor State Change, One state::check Rejects (A Faulted Motor Say) Leaves The Motor Be With Its PWM Channel Off */
+fn transition(motors: &mut axis::MotorControl, pwm: &hal::timer::Timer, motor: axis::Motors, motor_state: axis::MotorState) {
+    if motors.set_motor_state(motor, motor_state).is_ok() {
+        return;
+    }
+    match motor {
+        axis::Motors::Motor1 => {
+            pwm.set_pwm_ccr1(0);
+        } axis::Motors::Motor2 => {
+            pwm.set_pwm_ccr2(0);
+        } axis::Motors::Motor3 => {
+            pwm.set_pwm_ccr3(0);
+        } axis::Motors::Motor4 => {
+        }
+    }
+}
+
+interrupt!(TIM3_IRQ => tim3_handler);
+
+fn tim3_handler() {
     let gpioa =     hal::gpio::Gpio::init(board::l552ze::GPIOA_BASE);
-    let motors =    unsafe{&mut MOTORSTRUCT};
     let pwm =       hal::timer::Timer::init(board::l552ze::TIMER3_BASE);
 
     pwm.clr_flag();
 
-    motors.add_count();
+    MOTORSTRUCT.lock(|motors| {
+        motors.add_count();
 
-    let count =     motors.get_count();
+        let count =     motors.get_count();
 
-    if motors.get_toggle() {
-        gpioa.clr_pin(board::l552ze::LED_RED);
-        motors.clr_toggle();
-    } else {
-        gpioa.set_pin(board::l552ze::LED_RED);
-        motors.set_toggle();
-    }
+        if motors.get_toggle() {
+            gpioa.clr_pin(board::l552ze::LED_RED);
+            motors.clr_toggle();
+        } else {
+            gpioa.set_pin(board::l552ze::LED_RED);
+            motors.set_toggle();
+        }
 
-    if count < motors.get_motor_count(axis::Motors::Motor1) {
-        motors.set_motor_state(axis::Motors::Motor1, axis::MotorState::Operational);
-    } else {
-        pwm.set_pwm_ccr1(0);
-        motors.set_motor_state(axis::Motors::Motor1, axis::MotorState::Stopped);
-    }
+        if count < motors.get_motor_count(axis::Motors::Motor1) {
+            transition(motors, &pwm, axis::Motors::Motor1, axis::MotorState::Operational);
+        } else {
+            pwm.set_pwm_ccr1(0);
+            transition(motors, &pwm, axis::Motors::Motor1, axis::MotorState::Stopped);
+        }
 
-    if count < motors.get_motor_count(axis::Motors::Motor2) {
-        motors.set_motor_state(axis::Motors::Motor2, axis::MotorState::Operational);
-    } else {
-        pwm.set_pwm_ccr2(0);
-        motors.set_motor_state(axis::Motors::Motor2, axis::MotorState::Stopped);
-    }
+        if count < motors.get_motor_count(axis::Motors::Motor2) {
+            transition(motors, &pwm, axis::Motors::Motor2, axis::MotorState::Operational);
+        } else {
+            pwm.set_pwm_ccr2(0);
+            transition(motors, &pwm, axis::Motors::Motor2, axis::MotorState::Stopped);
+        }
 
-    if count < motors.get_motor_count(axis::Motors::Motor3) {
-        motors.set_motor_state(axis::Motors::Motor3, axis::MotorState::Operational);
-    } else {
-        pwm.set_pwm_ccr3(0);
-        motors.set_motor_state(axis::Motors::Motor3, axis::MotorState::Stopped);
-    }
+        if count < motors.get_motor_count(axis::Motors::Motor3) {
+            transition(motors, &pwm, axis::Motors::Motor3, axis::MotorState::Operational);
+        } else {
+            pwm.set_pwm_ccr3(0);
+            transition(motors, &pwm, axis::Motors::Motor3, axis::MotorState::Stopped);
+        }
 
 
-    if motors.check_stopped(motors.get_motor_state(axis::Motors::Motor1)) && motors.check_stopped(motors.get_motor_state(axis::Motors::Motor2)) && motors.check_stopped(motors.get_motor_state(axis::Motors::Motor3)) {
-        motors.set_state(axis::MotorState::Stopped);
-        gpioa.clr_pin(board::l552ze::LED_RED);
-        motors.clr_toggle();
-    } else {
-        pwm.start();
-    }
+        if motors.check_stopped(motors.get_motor_state(axis::Motors::Motor1)) && motors.check_stopped(motors.get_motor_state(axis::Motors::Motor2)) && motors.check_stopped(motors.get_motor_state(axis::Motors::Motor3)) {
+            gpioa.clr_pin(board::l552ze::LED_RED);
+            motors.clr_toggle();
+        } else {
+            pwm.start();
+        }
+    });
 }
 
 #[panic_handler]
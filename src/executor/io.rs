@@ -0,0 +1,262 @@
+/* Async Peripherals For The Executor */
+/* Each Holds A &'static Signal Or WakerSlot Its Interrupt Handler Also Reaches, The Handler Calls on_interrupt: */
+/*     static USART3_RX: WakerSlot = WakerSlot::init(); */
+/*     static USART3_TX: WakerSlot = WakerSlot::init(); */
+/*     interrupt!(USART3_IRQ => usart3_handler); */
+/*     fn usart3_handler() { AsyncUsart::init(USART3_BASE, &USART3_RX, &USART3_TX).on_interrupt(); } */
+/* The Peripheral Is Opened As Usual First, Only Its Interrupt Enables Are Touched Here */
+
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr;
+use core::task::{Context, Poll};
+use crate::driver::exti;
+use crate::stm32hal::timer;
+use crate::sync;
+use super::{Signal, WakerSlot};
+
+/* Timer Update Events, The Timer Is Opened, Given set_interrupt And Started As Usual */
+pub struct AsyncTimer {
+    timer:          timer::Timer,
+    signal:         &'static Signal
+}
+
+pub struct AsyncUsart {
+    cr1:            *mut u32,       // Control Register 1
+    isr:            *mut u32,       // Interrupt And Status Register
+    icr:            *mut u32,       // Interrupt Flag Clear Register
+    rdr:            *mut u32,       // Receive Data Register
+    tdr:            *mut u32,       // Transmit Data Register
+    rx:             &'static WakerSlot,
+    tx:             &'static WakerSlot  // Apart So A Read And A Write Can Wait Together
+}
+
+/* Full Duplex 8 Bit Transfers */
+pub struct AsyncSpi {
+    cr2:            *mut u32,       // Control Register 2
+    sr:             *mut u32,       // Status Register
+    dr:             *mut u8,        // Data Register, Byte Access So Only One Frame Is Moved
+    slot:           &'static WakerSlot
+}
+
+/* Edges On One EXTI Line */
+pub struct AsyncExti {
+    exti:           exti::Exti,
+    line:           u32,
+    signal:         &'static Signal
+}
+
+/* Status Flag Set, Enabling Its Interrupt While Waiting */
+struct FlagWait<'a> {
+    status:         *mut u32,
+    flag:           u32,
+    enable:         *mut u32,
+    interrupt:      u32,
+    slot:           &'a WakerSlot
+}
+
+/* USART Register Offsets */
+const USART_CR1:            u32 = 0x00;
+const USART_ISR:            u32 = 0x1C;
+const USART_ICR:            u32 = 0x20;
+const USART_RDR:            u32 = 0x24;
+const USART_TDR:            u32 = 0x28;
+
+/* USART Interrupt Enables (CR1) And Flags (ISR), FIFO Disabled */
+const USART_RXNE:           u32 = 1 << 5;
+const USART_TC:             u32 = 1 << 6;
+const USART_TXE:            u32 = 1 << 7;
+const USART_ORE:            u32 = 1 << 3;   // Overrun, Cleared Through ICR
+const USART_TX:             u32 = USART_TC | USART_TXE;
+
+/* SPI Register Offsets */
+const SPI_CR2:              u32 = 0x04;
+const SPI_SR:               u32 = 0x08;
+const SPI_DR:               u32 = 0x0C;
+
+/* SPI Control Register 2 And Status Register */
+const SPI_CR2_RXNEIE:       u32 = 1 << 6;
+const SPI_CR2_TXEIE:        u32 = 1 << 7;
+const SPI_CR2_FRXTH:        u32 = 1 << 12;  // RXNE At One Byte
+const SPI_SR_RXNE:          u32 = 1 << 0;
+const SPI_SR_TXE:           u32 = 1 << 1;
+
+impl AsyncTimer {
+    pub fn init(base: u32, signal: &'static Signal) -> AsyncTimer {
+        return AsyncTimer {
+            timer:      timer::Timer::init(base),
+            signal
+        };
+    }
+
+    /* Completes On The Next Update Event, Events Missed While Not Waiting Count As One */
+    pub async fn wait(&self) {
+        self.signal.wait().await;
+    }
+
+    /* From The Timer's Interrupt Handler */
+    pub fn on_interrupt(&self) {
+        self.timer.clr_flag();
+        self.signal.signal();
+    }
+}
+
+impl AsyncUsart {
+    pub fn init(base: u32, rx: &'static WakerSlot, tx: &'static WakerSlot) -> AsyncUsart {
+        return AsyncUsart {
+            cr1:        (base + USART_CR1) as *mut u32,
+            isr:        (base + USART_ISR) as *mut u32,
+            icr:        (base + USART_ICR) as *mut u32,
+            rdr:        (base + USART_RDR) as *mut u32,
+            tdr:        (base + USART_TDR) as *mut u32,
+            rx,
+            tx
+        };
+    }
+
+    /* Completes Once The Last Byte Has Left The Shift Register */
+    pub async fn write(&self, buf: &[u8]) {
+        for &byte in buf.iter() {
+            self.wait_flag(USART_TXE).await;
+            write(self.tdr, byte as u32);
+        }
+        self.wait_flag(USART_TC).await;
+    }
+
+    /* Fill buf, An Overrun Before The Read Started Is Dropped */
+    pub async fn read(&self, buf: &mut [u8]) {
+        write(self.icr, USART_ORE);
+        for byte in buf.iter_mut() {
+            self.wait_flag(USART_RXNE).await;
+            *byte = read(self.rdr) as u8;
+        }
+    }
+
+    /* From The USART's Interrupt Handler, Masks Only The Interrupts That Fired Until Their Next Wait */
+    /* RXNEIE Also Raises The Interrupt On An Overrun, Which Is Cleared Here So It Cannot Fire Again At Once, */
+    /* The Byte Lost To It Is Dropped And The Reader Goes On Waiting For The Next */
+    pub fn on_interrupt(&self) {
+        let enabled = read(self.cr1);
+        let status = read(self.isr);
+        if (status & USART_ORE) != 0 {
+            write(self.icr, USART_ORE);
+        }
+        let mut fired = 0;
+        if (enabled & USART_RXNE) != 0 && (status & (USART_RXNE | USART_ORE)) != 0 {
+            fired |= USART_RXNE;
+        }
+        fired |= enabled & status & USART_TX;
+        if fired == 0 {
+            return;
+        }
+
+        write(self.cr1, enabled & !fired);
+        if (fired & USART_RXNE) != 0 {
+            self.rx.wake();
+        }
+        if (fired & USART_TX) != 0 {
+            self.tx.wake();
+        }
+    }
+
+    /* RXNE, TC And TXE Sit At The Same Bit In CR1 And ISR */
+    fn wait_flag(&self, flag: u32) -> FlagWait<'_> {
+        return FlagWait {
+            status:     self.isr,
+            flag,
+            enable:     self.cr1,
+            interrupt:  flag,
+            slot:       if flag == USART_RXNE { self.rx } else { self.tx }
+        };
+    }
+}
+
+impl AsyncSpi {
+    pub fn init(base: u32, slot: &'static WakerSlot) -> AsyncSpi {
+        return AsyncSpi {
+            cr2:        (base + SPI_CR2) as *mut u32,
+            sr:         (base + SPI_SR) as *mut u32,
+            dr:         (base + SPI_DR) as *mut u8,
+            slot
+        };
+    }
+
+    /* Send buf And Replace It With The Bytes Received, The SPI Must Be Enabled */
+    pub async fn transfer(&self, buf: &mut [u8]) {
+        sync::free(|_cs| write(self.cr2, read(self.cr2) | SPI_CR2_FRXTH));
+        for byte in buf.iter_mut() {
+            self.wait_flag(SPI_SR_TXE, SPI_CR2_TXEIE).await;
+            unsafe { ptr::write_volatile(self.dr, *byte) };
+            self.wait_flag(SPI_SR_RXNE, SPI_CR2_RXNEIE).await;
+            *byte = unsafe { ptr::read_volatile(self.dr) };
+        }
+    }
+
+    /* From The SPI's Interrupt Handler, Masks The Interrupts Until The Next Wait */
+    pub fn on_interrupt(&self) {
+        write(self.cr2, read(self.cr2) & !(SPI_CR2_RXNEIE | SPI_CR2_TXEIE));
+        self.slot.wake();
+    }
+
+    fn wait_flag(&self, flag: u32, interrupt: u32) -> FlagWait<'_> {
+        return FlagWait {
+            status:     self.sr,
+            flag,
+            enable:     self.cr2,
+            interrupt,
+            slot:       self.slot
+        };
+    }
+}
+
+impl AsyncExti {
+    /* The Line Must Be Set Up With exti::Exti::open */
+    pub fn init(base: u32, line: u32, signal: &'static Signal) -> AsyncExti {
+        return AsyncExti {
+            exti:       exti::Exti::init(base),
+            line,
+            signal
+        };
+    }
+
+    /* Completes On The Next Edge After The Call, Earlier Ones Are Dropped */
+    pub async fn wait_edge(&self) {
+        self.signal.clr();
+        self.exti.set_mask(self.line, true);
+        self.signal.wait().await;
+    }
+
+    /* From The Line's EXTIn_IRQ Handler */
+    pub fn on_interrupt(&self) {
+        if self.exti.get_pending(self.line) {
+            self.exti.clr_pending(self.line);
+            self.signal.signal();
+        }
+    }
+}
+
+impl<'a> Future for FlagWait<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if (read(self.status) & self.flag) != 0 {
+            return Poll::Ready(());
+        }
+
+        /* The Handler Clears The Enable, So Set It Again Each Time, Then Check Again In Case The Flag Beat It */
+        self.slot.register(cx.waker());
+        sync::free(|_cs| write(self.enable, read(self.enable) | self.interrupt));
+        if (read(self.status) & self.flag) != 0 {
+            return Poll::Ready(());
+        }
+        return Poll::Pending;
+    }
+}
+
+fn read(reg: *mut u32) -> u32 {
+    return unsafe { ptr::read_volatile(reg) };
+}
+
+fn write(reg: *mut u32, val: u32) {
+    unsafe { ptr::write_volatile(reg, val) };
+}
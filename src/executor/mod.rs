@@ -0,0 +1,212 @@
+/* Cooperative Async Executor */
+/* Tasks Are Futures Pinned Where They Can Never Move, Usually In The Frame Of _start, So Nothing Is Allocated */
+/* A Waker Sets Its Task's Bit In READY, Interrupt Handlers Reach Them Through Signal Or WakerSlot, */
+/* And The Core Sleeps In WFI While No Task Is Ready */
+
+use core::arch::asm;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use crate::sync;
+
+/* Public Modules */
+pub mod io;
+
+/* One Ready Bit Per Task */
+pub const MAX_TASKS:        usize = 32;
+
+/* Tasks Woken Since They Were Last Polled, Shared By Every Executor So Only Run One */
+static READY:               AtomicU32 = AtomicU32::new(0);
+
+static VTABLE:              RawWakerVTable = RawWakerVTable::new(waker_clone, waker_wake, waker_wake, waker_drop);
+
+pub struct Executor<'a> {
+    tasks:          [Option<Pin<&'a mut dyn Future<Output = ()>>>; MAX_TASKS]
+}
+
+/* Waker Handed Over From An Interrupt Handler, Which Wakes It When The Event Comes */
+pub struct WakerSlot {
+    waker:          sync::Mutex<Option<Waker>>
+}
+
+/* Event Raised By An Interrupt Handler And Awaited By One Task, Raising It Twice Before The Wait Counts Once */
+pub struct Signal {
+    flag:           sync::AtomicFlag,
+    slot:           WakerSlot
+}
+
+/* Future Of Signal::wait */
+pub struct SignalWait<'a> {
+    signal:         &'a Signal
+}
+
+/* Future Of yield_now */
+pub struct Yield {
+    yielded:        bool
+}
+
+impl<'a> Executor<'a> {
+    pub fn init() -> Executor<'a> {
+        return Executor {
+            tasks:      core::array::from_fn(|_| None)
+        };
+    }
+
+    /* Add A Task, Polled For The First Time On The Next Pass, False When Every Slot Is Taken */
+    /* let task = core::pin::pin!(blink()); executor.spawn(task); */
+    pub fn spawn(&mut self, task: Pin<&'a mut dyn Future<Output = ()>>) -> bool {
+        for (i, slot) in self.tasks.iter_mut().enumerate() {
+            if slot.is_none() {
+                *slot = Some(task);
+                READY.fetch_or(1 << i, Ordering::AcqRel);
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /* Tasks Not Yet Finished */
+    pub fn get_tasks(&self) -> usize {
+        return self.tasks.iter().filter(|task| task.is_some()).count();
+    }
+
+    /* Poll Every Woken Task Once, For Calling From An Existing Main Loop, True While Tasks Remain */
+    pub fn poll(&mut self) -> bool {
+        let mut ready = READY.swap(0, Ordering::AcqRel);
+        while ready != 0 {
+            let i = ready.trailing_zeros() as usize;
+            ready &= !(1 << i);
+
+            if let Some(task) = self.tasks[i].as_mut() {
+                let waker = unsafe { Waker::from_raw(RawWaker::new(i as *const (), &VTABLE)) };
+                let mut cx = Context::from_waker(&waker);
+                if task.as_mut().poll(&mut cx).is_ready() {
+                    self.tasks[i] = None;
+                }
+            }
+        }
+        return self.get_tasks() != 0;
+    }
+
+    /* Run The Tasks Forever, Sleeping Until An Interrupt Whenever None Is Ready */
+    pub fn run(&mut self) -> ! {
+        loop {
+            self.poll();
+            sleep();
+        }
+    }
+}
+
+impl WakerSlot {
+    pub const fn init() -> WakerSlot {
+        return WakerSlot {
+            waker:      sync::Mutex::init(None)
+        };
+    }
+
+    /* Keep The Waker Of The Task Now Waiting, Replacing Any Earlier One */
+    pub fn register(&self, waker: &Waker) {
+        self.waker.lock(|slot| {
+            match slot {
+                Some(old) if old.will_wake(waker) => {
+                } _ => {
+                    *slot = Some(waker.clone());
+                }
+            }
+        });
+    }
+
+    /* Wake The Waiting Task, If Any, Safe From An Interrupt Handler */
+    pub fn wake(&self) {
+        if let Some(waker) = self.waker.lock(|slot| slot.take()) {
+            waker.wake();
+        }
+    }
+}
+
+impl Signal {
+    pub const fn init() -> Signal {
+        return Signal {
+            flag:       sync::AtomicFlag::init(),
+            slot:       WakerSlot::init()
+        };
+    }
+
+    /* Raise The Event, From An Interrupt Handler */
+    pub fn signal(&self) {
+        self.flag.set();
+        self.slot.wake();
+    }
+
+    /* Drop An Event Raised Before The Task Started Waiting */
+    pub fn clr(&self) {
+        self.flag.clr();
+    }
+
+    pub fn get(&self) -> bool {
+        return self.flag.get();
+    }
+
+    /* Completes Once The Event Has Been Raised, Taking It */
+    pub fn wait(&self) -> SignalWait<'_> {
+        return SignalWait {
+            signal:     self
+        };
+    }
+}
+
+impl<'a> Future for SignalWait<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        /* Register Before Checking, So A Signal Between The Two Still Wakes The Task */
+        self.signal.slot.register(cx.waker());
+        if self.signal.flag.take() {
+            return Poll::Ready(());
+        }
+        return Poll::Pending;
+    }
+}
+
+impl Future for Yield {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        return Poll::Pending;
+    }
+}
+
+/* Let The Other Ready Tasks Run Before Carrying On */
+pub fn yield_now() -> Yield {
+    return Yield {
+        yielded:    false
+    };
+}
+
+/* WFI Unless A Task Was Woken, Interrupts Are Masked Around The Check So A Wake In Between Is Not Slept Through */
+/* A Masked Interrupt Still Ends WFI, Its Handler Runs As Soon As The Mask Is Lifted */
+fn sleep() {
+    sync::free(|_cs| {
+        if READY.load(Ordering::Acquire) == 0 {
+            unsafe { asm!("dsb", "wfi") };
+        }
+    });
+}
+
+/* The Waker Data Is The Task Index, Nothing To Clone Or Free */
+unsafe fn waker_clone(data: *const ()) -> RawWaker {
+    return RawWaker::new(data, &VTABLE);
+}
+
+unsafe fn waker_wake(data: *const ()) {
+    READY.fetch_or(1 << (data as usize), Ordering::AcqRel);
+}
+
+unsafe fn waker_drop(_data: *const ()) {
+}
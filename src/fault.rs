@@ -0,0 +1,330 @@
+/* Fault Handlers, Replace The Weak HardFault / MemManage / BusFault / UsageFault / SecureFault Loops In The Startup */
+/* Each Finds The Stacked Frame, Decodes The Fault Status Registers And Reports Them Before Halting Or Resetting */
+
+use core::arch::{asm, global_asm};
+use core::fmt;
+use core::fmt::Write;
+use core::ptr;
+use crate::board::{l552ze, sau, scb};
+use crate::driver::serial;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    HardFault = 0,
+    MemManage,
+    BusFault,
+    UsageFault,
+    SecureFault
+}
+
+/* What Happens Once The Report Has Gone Out */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Halt,
+    Reset
+}
+
+/* Everything Known About A Fault, Addresses Are None Unless The Hardware Marked Them Valid */
+#[derive(Clone, Copy)]
+pub struct FaultReport {
+    pub kind:       Kind,
+    pub r0:         u32,
+    pub r1:         u32,
+    pub r2:         u32,
+    pub r3:         u32,
+    pub r12:        u32,
+    pub lr:         u32,
+    pub pc:         u32,
+    pub xpsr:       u32,
+    pub sp:         u32,            // Stack Pointer Before The Exception
+    pub exc_return: u32,
+    pub cfsr:       u32,
+    pub hfsr:       u32,
+    pub mmfar:      Option<u32>,
+    pub bfar:       Option<u32>,
+    pub sfsr:       u32,
+    pub sfar:       Option<u32>
+}
+
+/* Serial Port Behind A Plain Function, So The Handlers Need No Driver Instance */
+struct Port(fn(&[u8]));
+
+/* Exception Return Value */
+const EXC_RETURN_SPSEL:     u32 = 1 << 2;   // Process Stack
+const EXC_RETURN_MODE:      u32 = 1 << 3;   // Thread Mode
+const EXC_RETURN_FTYPE:     u32 = 1 << 4;   // Clear When The Frame Includes The FPU Registers
+const EXC_RETURN_DCRS:      u32 = 1 << 5;   // Clear When The Callee Registers Were Stacked Too
+const EXC_RETURN_S:         u32 = 1 << 6;   // Secure Stack
+
+/* Stack Frame Sizes In Bytes */
+const FRAME_BASIC:          u32 = 32;
+const FRAME_EXTENDED:       u32 = 104;
+const FRAME_ADDITIONAL:     u32 = 40;       // Integrity Signature, Reserved And R4 - R11
+const XPSR_ALIGN:           u32 = 1 << 9;   // Frame Was Padded By 4 Bytes To Align It
+
+/* Configurable Fault Status Register, MemManage / BusFault / UsageFault Status */
+const CFSR_MMARVALID:       u32 = 1 << 7;
+const CFSR_BFARVALID:       u32 = 1 << 15;
+const CFSR_BITS: [(u32, &str); 18] = [
+    (1 << 0,    "IACCVIOL instruction access violation"),
+    (1 << 1,    "DACCVIOL data access violation"),
+    (1 << 3,    "MUNSTKERR MemManage on unstacking"),
+    (1 << 4,    "MSTKERR MemManage on stacking"),
+    (1 << 5,    "MLSPERR MemManage on lazy FP state"),
+    (1 << 8,    "IBUSERR instruction bus error"),
+    (1 << 9,    "PRECISERR precise data bus error"),
+    (1 << 10,   "IMPRECISERR imprecise data bus error"),
+    (1 << 11,   "UNSTKERR BusFault on unstacking"),
+    (1 << 12,   "STKERR BusFault on stacking"),
+    (1 << 13,   "LSPERR BusFault on lazy FP state"),
+    (1 << 16,   "UNDEFINSTR undefined instruction"),
+    (1 << 17,   "INVSTATE invalid state (Thumb bit)"),
+    (1 << 18,   "INVPC invalid EXC_RETURN"),
+    (1 << 19,   "NOCP no coprocessor"),
+    (1 << 20,   "STKOF stack overflow (stack limit)"),
+    (1 << 24,   "UNALIGNED unaligned access"),
+    (1 << 25,   "DIVBYZERO divide by zero")
+];
+
+/* HardFault Status Register */
+const HFSR_BITS: [(u32, &str); 3] = [
+    (1 << 1,    "VECTTBL vector table read"),
+    (1 << 30,   "FORCED escalated from a configurable fault"),
+    (1 << 31,   "DEBUGEVT debug event")
+];
+
+/* Secure Fault Status Register */
+const SFSR_SFARVALID:       u32 = 1 << 6;
+const SFSR_BITS: [(u32, &str); 7] = [
+    (1 << 0,    "INVEP invalid entry point"),
+    (1 << 1,    "INVIS invalid integrity signature"),
+    (1 << 2,    "INVER invalid exception return"),
+    (1 << 3,    "AUVIOL attribution unit violation"),
+    (1 << 4,    "INVTRAN invalid transition"),
+    (1 << 5,    "LSPERR lazy FP state error"),
+    (1 << 7,    "LSERR lazy FP state activation error")
+];
+
+/* Application Interrupt And Reset Control Register */
+const AIRCR_VECTKEY:        u32 = 0x05FA << 16;
+const AIRCR_PRIGROUP:       u32 = 0x7 << 8;
+const AIRCR_SYSRESETREQ:    u32 = 1 << 2;
+
+/* System Handler Control And State Register, Fault Handler Enables */
+const SHCSR_MEMFAULTENA:    u32 = 1 << 16;
+const SHCSR_BUSFAULTENA:    u32 = 1 << 17;
+const SHCSR_USGFAULTENA:    u32 = 1 << 18;
+const SHCSR_SECUREFAULTENA: u32 = 1 << 19;
+
+/* Report Port And Action, Set Once At Start Up */
+static mut REPORT:          Option<fn(&[u8])> = None;
+static mut ACTION:          Action = Action::Halt;
+
+/* Entry Stubs, Pick The Stack The Frame Went To From EXC_RETURN And Pass It On With The Fault */
+global_asm!(
+    ".section .text.fault_handlers,\"ax\",%progbits",
+    ".macro FAULT_HANDLER name, fault",
+    ".global \\name",
+    ".type \\name, %function",
+    ".thumb_func",
+    "\\name:",
+    "tst lr, #0x40",
+    "beq 3f",
+    "tst lr, #0x04",
+    "ite eq",
+    "mrseq r0, msp",
+    "mrsne r0, psp",
+    "b 4f",
+    "3:",
+    "tst lr, #0x04",
+    "ite eq",
+    "mrseq r0, msp_ns",
+    "mrsne r0, psp_ns",
+    "4:",
+    "mov r1, lr",
+    "movs r2, #\\fault",
+    "b {entry}",
+    ".endm",
+    "FAULT_HANDLER HardFault_Handler, 0",
+    "FAULT_HANDLER MemManage_Handler, 1",
+    "FAULT_HANDLER BusFault_Handler, 2",
+    "FAULT_HANDLER UsageFault_Handler, 3",
+    "FAULT_HANDLER SecureFault_Handler, 4",
+    ".purgem FAULT_HANDLER",
+    entry = sym fault_entry
+);
+
+impl Kind {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            Kind::HardFault => "HardFault",
+            Kind::MemManage => "MemManage",
+            Kind::BusFault => "BusFault",
+            Kind::UsageFault => "UsageFault",
+            Kind::SecureFault => "SecureFault"
+        };
+    }
+
+    fn from(val: u32) -> Kind {
+        return match val {
+            1 => Kind::MemManage,
+            2 => Kind::BusFault,
+            3 => Kind::UsageFault,
+            4 => Kind::SecureFault,
+            _ => Kind::HardFault
+        };
+    }
+}
+
+impl FaultReport {
+    /* Frame Words Are r0, r1, r2, r3, r12, lr, pc, xpsr, sp Is The Stack Pointer The Handler Found */
+    /* Status Is cfsr, hfsr, mmfar, bfar, sfsr, sfar */
+    pub fn init(kind: Kind, frame: &[u32; 8], sp: u32, exc_return: u32, status: [u32; 6]) -> FaultReport {
+        let [cfsr, hfsr, mmfar, bfar, sfsr, sfar] = status;
+        return FaultReport {
+            kind,
+            r0:         frame[0],
+            r1:         frame[1],
+            r2:         frame[2],
+            r3:         frame[3],
+            r12:        frame[4],
+            lr:         frame[5],
+            pc:         frame[6],
+            xpsr:       frame[7],
+            sp:         sp + frame_size(exc_return, frame[7]),
+            exc_return,
+            cfsr,
+            hfsr,
+            mmfar:      if (cfsr & CFSR_MMARVALID) != 0 { Some(mmfar) } else { None },
+            bfar:       if (cfsr & CFSR_BFARVALID) != 0 { Some(bfar) } else { None },
+            sfsr,
+            sfar:       if (sfsr & SFSR_SFARVALID) != 0 { Some(sfar) } else { None }
+        };
+    }
+
+    /* Readable Multi-Line Report */
+    pub fn write<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "\r\n*** {} ***\r\n", self.kind.get_name())?;
+        write!(w, "PC   0x{:08X}  LR   0x{:08X}  xPSR 0x{:08X}\r\n", self.pc, self.lr, self.xpsr)?;
+        write!(w, "R0   0x{:08X}  R1   0x{:08X}  R2   0x{:08X}  R3   0x{:08X}\r\n", self.r0, self.r1, self.r2, self.r3)?;
+        write!(w, "R12  0x{:08X}  SP   0x{:08X}  EXC_RETURN 0x{:08X}\r\n", self.r12, self.sp, self.exc_return)?;
+        write!(w, "     {} {} stack, {} mode{}\r\n",
+            if (self.exc_return & EXC_RETURN_S) != 0 { "secure" } else { "non-secure" },
+            if (self.exc_return & EXC_RETURN_SPSEL) != 0 { "process" } else { "main" },
+            if (self.exc_return & EXC_RETURN_MODE) != 0 { "thread" } else { "handler" },
+            if (self.exc_return & EXC_RETURN_FTYPE) == 0 { ", FPU frame" } else { "" })?;
+
+        write!(w, "CFSR 0x{:08X}\r\n", self.cfsr)?;
+        write_bits(w, self.cfsr, &CFSR_BITS)?;
+        write!(w, "HFSR 0x{:08X}\r\n", self.hfsr)?;
+        write_bits(w, self.hfsr, &HFSR_BITS)?;
+        if let Some(mmfar) = self.mmfar {
+            write!(w, "MMFAR 0x{:08X}\r\n", mmfar)?;
+        }
+        if let Some(bfar) = self.bfar {
+            write!(w, "BFAR 0x{:08X}\r\n", bfar)?;
+        }
+        if self.sfsr != 0 {
+            write!(w, "SFSR 0x{:08X}\r\n", self.sfsr)?;
+            write_bits(w, self.sfsr, &SFSR_BITS)?;
+        }
+        if let Some(sfar) = self.sfar {
+            write!(w, "SFAR 0x{:08X}\r\n", sfar)?;
+        }
+        return Ok(());
+    }
+}
+
+impl serial::Serial for Port {
+    fn write(&self, buf: &[u8]) {
+        (self.0)(buf);
+    }
+}
+
+/* Where The Report Goes, For Example A Function Writing To The Debug USART, And What To Do After */
+/* Also Enables The Configurable Fault Handlers, Otherwise Every Fault Escalates To HardFault */
+pub fn init(report: fn(&[u8]), action: Action) {
+    let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+    unsafe {
+        REPORT = Some(report);
+        ACTION = action;
+        let shcsr = ptr::read_volatile(ptr::addr_of!((*scb).shcsr));
+        let enable = SHCSR_MEMFAULTENA | SHCSR_BUSFAULTENA | SHCSR_USGFAULTENA | SHCSR_SECUREFAULTENA;
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).shcsr), shcsr | enable);
+    }
+}
+
+/* Formatted Text To The Report Port, Dropped If None Is Set */
+pub fn report(args: fmt::Arguments) {
+    if let Some(port) = unsafe { REPORT } {
+        let _ = serial::Writer::init(&Port(port)).write_fmt(args);
+    }
+}
+
+/* Bytes Taken By The Exception Entry, So The Stack Pointer Before It Can Be Found */
+pub fn frame_size(exc_return: u32, xpsr: u32) -> u32 {
+    let mut size = if (exc_return & EXC_RETURN_FTYPE) == 0 { FRAME_EXTENDED } else { FRAME_BASIC };
+    if (xpsr & XPSR_ALIGN) != 0 {
+        size += 4;
+    }
+    if (exc_return & EXC_RETURN_DCRS) == 0 {
+        size += FRAME_ADDITIONAL;
+    }
+    return size;
+}
+
+/* Request A System Reset */
+pub fn reset() -> ! {
+    let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+    unsafe {
+        asm!("dsb");
+        let aircr = ptr::read_volatile(ptr::addr_of!((*scb).aircr)) & AIRCR_PRIGROUP;
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).aircr), AIRCR_VECTKEY | aircr | AIRCR_SYSRESETREQ);
+        asm!("dsb");
+    }
+    loop {}
+}
+
+fn write_bits<W: fmt::Write>(w: &mut W, val: u32, bits: &[(u32, &str)]) -> fmt::Result {
+    for &(bit, name) in bits.iter() {
+        if (val & bit) != 0 {
+            write!(w, "     {}\r\n", name)?;
+        }
+    }
+    return Ok(());
+}
+
+/* Gather The Fault Status From The SCB And SAU, The SAU Reads As Zero From The Non-Secure World */
+fn read_status() -> [u32; 6] {
+    let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+    let sau = l552ze::SAU_BASE as *mut sau::SAUReg;
+    unsafe {
+        return [
+            ptr::read_volatile(ptr::addr_of!((*scb).cfsr)),
+            ptr::read_volatile(ptr::addr_of!((*scb).hfsr)),
+            ptr::read_volatile(ptr::addr_of!((*scb).mmfar)),
+            ptr::read_volatile(ptr::addr_of!((*scb).bfar)),
+            ptr::read_volatile(ptr::addr_of!((*sau).sfsr)),
+            ptr::read_volatile(ptr::addr_of!((*sau).sfar))
+        ];
+    }
+}
+
+/* Frame Starts After The Additional State Context When The Callee Registers Were Stacked */
+extern "C" fn fault_entry(sp: u32, exc_return: u32, fault: u32) -> ! {
+    let frame_addr = if (exc_return & EXC_RETURN_DCRS) == 0 { sp + FRAME_ADDITIONAL } else { sp };
+    let mut frame = [0u32; 8];
+    for (i, word) in frame.iter_mut().enumerate() {
+        *word = unsafe { ptr::read_volatile((frame_addr as *const u32).add(i)) };
+    }
+
+    let report = FaultReport::init(Kind::from(fault), &frame, sp, exc_return, read_status());
+    if let Some(port) = unsafe { REPORT } {
+        let _ = report.write(&mut serial::Writer::init(&Port(port)));
+    }
+
+    if unsafe { ACTION } == Action::Reset {
+        reset();
+    }
+    loop {}
+}
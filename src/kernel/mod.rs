@@ -0,0 +1,481 @@
+/* Preemptive Kernel, Fixed Priority Threads On Their Own Stacks */
+/* Priority 0 Is The Highest As On The NVIC, Threads Of Equal Priority Share The Core In SysTick Slices */
+/* PendSV Switches Threads, Saving s16-s31 Only For Threads That Have Used The FPU (Lazy Stacking Does The Rest), */
+/* PSPLIM Is Loaded With The Running Thread's Stack Bottom So An Overflow Faults Instead Of Corrupting Memory */
+/* The Kernel Owns SVC_Handler, PendSV_Handler And SysTick_Handler */
+
+use core::arch::{asm, global_asm};
+use core::ptr;
+use crate::board::{l552ze, scb, systick};
+use crate::sync;
+
+/* Public Modules */
+pub mod semaphore;
+pub mod mutex;
+pub mod queue;
+
+/* Thread Slots, Slot 0 Is The Idle Thread */
+pub const MAX_THREADS:      usize = 8;
+
+/* Lowest Priority A Thread Can Have, Only The Idle Thread Is Below It */
+pub const PRIO_LOWEST:      u8 = 254;
+const PRIO_IDLE:            u8 = 255;
+
+/* Smallest Stack spawn Accepts, In u64 Words */
+pub const MIN_STACK:        usize = 32;
+const IDLE_STACK:           usize = 32;
+const IDLE:                 usize = 0;
+
+/* Words Below The Exception Frame Saved By PendSV, EXC_RETURN Then r4-r11 */
+const SAVED_WORDS:          usize = 9;
+const FRAME_WORDS:          usize = 8;
+
+/* Initial Exception Frame */
+const XPSR_THUMB:           u32 = 1 << 24;
+
+/* EXC_RETURN, Returning To Thread Mode On PSP Without FPU State */
+const EXC_RETURN_THREAD:    u32 = 0xFFFFFFFD;   // Secure, Until start Has Seen The Real One
+const EXC_RETURN_SPSEL:     u32 = 1 << 2;
+const EXC_RETURN_MODE:      u32 = 1 << 3;
+const EXC_RETURN_FTYPE:     u32 = 1 << 4;
+
+/* Interrupt Control and State Register */
+const ICSR_PENDSVSET:       u32 = 1 << 28;
+
+/* SysTick Control and Status Register */
+const SYST_CTRL_ENABLE:     u32 = 1 << 0;
+const SYST_CTRL_TICKINT:    u32 = 1 << 1;
+const SYST_CTRL_CLKSOURCE:  u32 = 1 << 2;   // Core Clock
+
+/* System Handler Priority Bytes, Exception Number - 4 */
+const SHPR_SVC:             usize = 7;
+const SHPR_PENDSV:          usize = 10;
+const SHPR_SYSTICK:         usize = 11;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum State {
+    Free,
+    Ready,
+    Sleeping,
+    Blocked,
+    Finished
+}
+
+#[derive(Clone, Copy)]
+struct Thread {
+    sp:             u32,            // Saved PSP, EXC_RETURN Is At The Bottom
+    limit:          u32,            // Lowest Stack Address, Loaded Into PSPLIM
+    state:          State,
+    priority:       u8,             // Running Priority, Raised By Priority Inheritance
+    base:           u8,             // Priority Given To spawn
+    held:           u8,             // Kernel Mutexes Held
+    object:         usize,          // Address Of What The Thread Is Blocked On
+    deadline:       Option<u32>     // Tick It Wakes At Regardless
+}
+
+struct Kernel {
+    threads:        [Thread; MAX_THREADS],
+    current:        usize,
+    next:           usize,          // Thread PendSV Switches To
+    ticks:          u32,
+    started:        bool,
+    exc_return:     u32             // EXC_RETURN Of A New Thread, Keeps The Security State Of start
+}
+
+static KERNEL:              sync::Mutex<Kernel> = sync::Mutex::init(Kernel::init());
+
+static mut IDLE_STACK_MEM:  [u64; IDLE_STACK] = [0; IDLE_STACK];
+
+impl Thread {
+    const fn init() -> Thread {
+        return Thread {
+            sp:         0,
+            limit:      0,
+            state:      State::Free,
+            priority:   PRIO_IDLE,
+            base:       PRIO_IDLE,
+            held:       0,
+            object:     0,
+            deadline:   None
+        };
+    }
+}
+
+impl Kernel {
+    const fn init() -> Kernel {
+        return Kernel {
+            threads:    [Thread::init(); MAX_THREADS],
+            current:    IDLE,
+            next:       IDLE,
+            ticks:      0,
+            started:    false,
+            exc_return: EXC_RETURN_THREAD
+        };
+    }
+
+    /* Lay Out The Frame PendSV Expects At The Top Of stack */
+    fn create(&mut self, id: usize, entry: fn(), stack: &'static mut [u64], priority: u8) {
+        let bottom = stack.as_mut_ptr() as *mut u32;
+        let words = stack.len() * 2;
+        unsafe {
+            let frame = bottom.add(words - FRAME_WORDS);
+            for i in 0..FRAME_WORDS {
+                ptr::write_volatile(frame.add(i), 0);
+            }
+            ptr::write_volatile(frame.add(5), (thread_exit as fn()) as usize as u32);  // LR
+            ptr::write_volatile(frame.add(6), (entry as usize as u32) & !1);     // PC
+            ptr::write_volatile(frame.add(7), XPSR_THUMB);
+
+            let saved = frame.sub(SAVED_WORDS);
+            for i in 1..SAVED_WORDS {
+                ptr::write_volatile(saved.add(i), 0);
+            }
+            ptr::write_volatile(saved, self.exc_return);
+
+            self.threads[id] = Thread {
+                sp:         saved as u32,
+                limit:      bottom as u32,
+                state:      State::Ready,
+                priority,
+                base:       priority,
+                held:       0,
+                object:     0,
+                deadline:   None
+            };
+        }
+    }
+
+    /* Highest Priority Ready Thread, With rotate The Search Starts After The Current One So Equal Priorities Take Turns */
+    fn pick(&self, rotate: bool) -> usize {
+        let mut best = IDLE;
+        if !rotate && self.threads[self.current].state == State::Ready {
+            best = self.current;
+        }
+        for k in 1..=MAX_THREADS {
+            let i = (self.current + k) % MAX_THREADS;
+            if self.threads[i].state == State::Ready && self.threads[i].priority < self.threads[best].priority {
+                best = i;
+            }
+        }
+        return best;
+    }
+
+    /* Pend PendSV If Another Thread Should Run, It Switches Once Nothing Higher Is Active */
+    fn reschedule(&mut self, rotate: bool) {
+        if !self.started {
+            return;
+        }
+        self.next = self.pick(rotate);
+        if self.next != self.current {
+            let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+            unsafe { ptr::write_volatile(ptr::addr_of_mut!((*scb).icsr), ICSR_PENDSVSET) };
+        }
+    }
+
+    fn block(&mut self, object: usize, deadline: Option<u32>) {
+        let thread = &mut self.threads[self.current];
+        thread.state = if object == 0 { State::Sleeping } else { State::Blocked };
+        thread.object = object;
+        thread.deadline = deadline;
+        self.reschedule(false);
+    }
+
+    /* Highest Priority Thread Blocked On object, The Lowest Slot Among Equals */
+    fn waiter(&self, object: usize) -> Option<usize> {
+        let mut found: Option<usize> = None;
+        for (i, thread) in self.threads.iter().enumerate() {
+            if thread.state == State::Blocked && thread.object == object {
+                match found {
+                    Some(best) if self.threads[best].priority <= thread.priority => {
+                    } _ => {
+                        found = Some(i);
+                    }
+                }
+            }
+        }
+        return found;
+    }
+
+    fn wake(&mut self, object: usize) -> Option<usize> {
+        let id = self.waiter(object)?;
+        let thread = &mut self.threads[id];
+        thread.state = State::Ready;
+        thread.object = 0;
+        thread.deadline = None;
+        self.reschedule(false);
+        return Some(id);
+    }
+
+    /* Wake Sleepers And Timed Out Waiters, Then Give The Next Thread Of Equal Priority A Turn */
+    fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+        let ticks = self.ticks;
+        for thread in self.threads.iter_mut() {
+            if let Some(deadline) = thread.deadline {
+                if (thread.state == State::Sleeping || thread.state == State::Blocked) && expired(ticks, deadline) {
+                    thread.state = State::Ready;
+                    thread.object = 0;
+                    thread.deadline = None;
+                }
+            }
+        }
+        self.reschedule(true);
+    }
+}
+
+/* Add A Thread, Runnable Once start Is Called Or At Once If It Already Has Been */
+/* stack Must Hold At Least MIN_STACK Words, priority Is Capped At PRIO_LOWEST, None When Every Slot Is Taken */
+/* Returning From entry Ends The Thread And Frees Its Slot */
+pub fn spawn(entry: fn(), stack: &'static mut [u64], priority: u8) -> Option<usize> {
+    if stack.len() < MIN_STACK {
+        return None;
+    }
+    return KERNEL.lock(move |kernel| {
+        let id = (1..MAX_THREADS).find(|&i| {
+            let state = kernel.threads[i].state;
+            return state == State::Free || (state == State::Finished && i != kernel.current);
+        })?;
+        kernel.create(id, entry, stack, core::cmp::min(priority, PRIO_LOWEST));
+        kernel.reschedule(false);
+        return Some(id);
+    });
+}
+
+/* Start SysTick At tick_hz And Switch To The Highest Priority Thread, main's Stack Is Left To The Handlers */
+pub fn start(core_clock: u32, tick_hz: u32) -> ! {
+    let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+    let syst = l552ze::SYSTICK_BASE as *mut systick::SysTickReg;
+    KERNEL.lock(|kernel| {
+        let stack = unsafe { &mut *ptr::addr_of_mut!(IDLE_STACK_MEM) };
+        kernel.create(IDLE, idle, stack, PRIO_IDLE);
+    });
+
+    unsafe {
+        /* PendSV And SysTick Below Every Interrupt, SVC Above So Nothing Runs Between It And The First Thread */
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).shpr[SHPR_SVC]), 0);
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).shpr[SHPR_PENDSV]), 0xFF);
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).shpr[SHPR_SYSTICK]), 0xFF);
+
+        ptr::write_volatile(ptr::addr_of_mut!((*syst).load), (core_clock / tick_hz) - 1);
+        ptr::write_volatile(ptr::addr_of_mut!((*syst).val), 0);
+        ptr::write_volatile(ptr::addr_of_mut!((*syst).ctrl), SYST_CTRL_CLKSOURCE | SYST_CTRL_TICKINT | SYST_CTRL_ENABLE);
+
+        asm!("cpsie i", "svc 0");
+    }
+    loop {}
+}
+
+/* Give Up The Core To Another Ready Thread Of The Same Priority */
+pub fn yield_now() {
+    KERNEL.lock(|kernel| kernel.reschedule(true));
+}
+
+/* Block For ticks SysTick Periods, 0 Only Yields */
+pub fn sleep(ticks: u32) {
+    if ticks == 0 {
+        yield_now();
+        return;
+    }
+    KERNEL.lock(|kernel| {
+        let deadline = kernel.ticks.wrapping_add(ticks);
+        kernel.block(0, Some(deadline));
+    });
+}
+
+pub fn get_ticks() -> u32 {
+    return KERNEL.lock(|kernel| kernel.ticks);
+}
+
+/* Slot Of The Running Thread */
+pub fn get_current() -> usize {
+    return KERNEL.lock(|kernel| kernel.current);
+}
+
+pub fn get_state(id: usize) -> State {
+    return KERNEL.lock(|kernel| kernel.threads[id].state);
+}
+
+/* Running Priority, Above The Spawned One While Priority Inheritance Applies */
+pub fn get_priority(id: usize) -> u8 {
+    return KERNEL.lock(|kernel| kernel.threads[id].priority);
+}
+
+/* Tick A Timeout Of ticks From Now Ends At, None Waits Forever */
+pub(crate) fn deadline(timeout: Option<u32>) -> Option<u32> {
+    return timeout.map(|ticks| get_ticks().wrapping_add(ticks));
+}
+
+/* True Once deadline Has Passed, Never For None */
+pub(crate) fn is_expired(deadline: Option<u32>) -> bool {
+    return match deadline {
+        Some(deadline) => expired(get_ticks(), deadline),
+        None => false
+    };
+}
+
+/* Block The Running Thread On object Until wake Or The Deadline, The Switch Happens When The Caller's Critical Section Ends */
+/* Callers Check Again Afterwards, Waking Only Means The Object May Be Ready */
+pub(crate) fn block(_cs: &sync::CriticalSection, object: usize, deadline: Option<u32>) {
+    KERNEL.lock(|kernel| kernel.block(object, deadline));
+}
+
+/* Make The Highest Priority Thread Blocked On object Ready, Safe From An Interrupt Handler */
+pub(crate) fn wake(_cs: &sync::CriticalSection, object: usize) -> Option<usize> {
+    return KERNEL.lock(|kernel| kernel.wake(object));
+}
+
+/* Priority Of The Highest Thread Blocked On object */
+pub(crate) fn get_waiter_priority(_cs: &sync::CriticalSection, object: usize) -> Option<u8> {
+    return KERNEL.lock(|kernel| kernel.waiter(object).map(|id| kernel.threads[id].priority));
+}
+
+/* A Kernel Mutex Was Taken By id */
+pub(crate) fn hold(_cs: &sync::CriticalSection, id: usize) {
+    KERNEL.lock(|kernel| kernel.threads[id].held += 1);
+}
+
+/* A Kernel Mutex Was Given Up By id, Its Inherited Priority Is Dropped Once It Holds None */
+pub(crate) fn release(_cs: &sync::CriticalSection, id: usize) {
+    KERNEL.lock(|kernel| {
+        let thread = &mut kernel.threads[id];
+        thread.held -= 1;
+        if thread.held == 0 {
+            thread.priority = thread.base;
+        }
+        kernel.reschedule(false);
+    });
+}
+
+/* Raise id To priority While It Holds A Mutex A Higher Thread Waits On */
+pub(crate) fn inherit(_cs: &sync::CriticalSection, id: usize, priority: u8) {
+    KERNEL.lock(|kernel| {
+        if priority < kernel.threads[id].priority {
+            kernel.threads[id].priority = priority;
+            kernel.reschedule(false);
+        }
+    });
+}
+
+/* Deadlines Up To Half The Tick Range Ahead Survive The Counter Wrapping */
+fn expired(ticks: u32, deadline: u32) -> bool {
+    return (ticks.wrapping_sub(deadline) as i32) >= 0;
+}
+
+/* Where A Thread Returning From Its Entry Function Ends Up */
+fn thread_exit() {
+    KERNEL.lock(|kernel| {
+        kernel.threads[kernel.current].state = State::Finished;
+        kernel.reschedule(false);
+    });
+    loop {}
+}
+
+/* Runs When No Thread Is Ready */
+fn idle() {
+    loop {
+        unsafe { asm!("wfi") };
+    }
+}
+
+fn tick() {
+    KERNEL.lock(|kernel| kernel.tick());
+}
+
+crate::exception!(SysTick => tick);
+
+/* From PendSV_Handler With The Outgoing PSP, Returns The Incoming PSP (r0) And Stack Limit (r1) */
+extern "C" fn kernel_switch(sp: u32) -> u64 {
+    return KERNEL.lock(|kernel| {
+        let current = kernel.current;
+        kernel.threads[current].sp = sp;
+
+        if kernel.threads[kernel.next].state != State::Ready {
+            kernel.next = kernel.pick(false);
+        }
+        kernel.current = kernel.next;
+        if kernel.threads[current].state == State::Finished {
+            kernel.threads[current].state = State::Free;
+        }
+
+        let thread = &kernel.threads[kernel.current];
+        return ((thread.limit as u64) << 32) | (thread.sp as u64);
+    });
+}
+
+/* From SVC_Handler With Its EXC_RETURN, Returns The First Thread Like kernel_switch, Or 0 Once Started */
+extern "C" fn kernel_first(exc_return: u32) -> u64 {
+    return KERNEL.lock(|kernel| {
+        if kernel.started {
+            return 0;
+        }
+
+        /* Return To Thread Mode On PSP Without FPU State, In The Security State SVC Was Taken From */
+        kernel.exc_return = exc_return | EXC_RETURN_FTYPE | EXC_RETURN_MODE | EXC_RETURN_SPSEL;
+        for thread in kernel.threads.iter() {
+            if thread.state == State::Ready {
+                unsafe { ptr::write_volatile(thread.sp as *mut u32, kernel.exc_return) };
+            }
+        }
+
+        kernel.started = true;
+        kernel.current = kernel.pick(false);
+        kernel.next = kernel.current;
+
+        let thread = &kernel.threads[kernel.current];
+        return ((thread.limit as u64) << 32) | (thread.sp as u64);
+    });
+}
+
+/* PSPLIM Is Cleared Before PSP Moves, So The New PSP Is Never Checked Against The Old Thread's Limit */
+global_asm!(
+    ".fpu fpv5-sp-d16",
+
+    ".section .text.PendSV_Handler,\"ax\",%progbits",
+    ".global PendSV_Handler",
+    ".type PendSV_Handler, %function",
+    ".thumb_func",
+    "PendSV_Handler:",
+    "    mrs r0, psp",
+    "    isb",
+    "    tst lr, #0x10",                // FType Clear, The Thread Has FPU State
+    "    it eq",
+    "    vstmdbeq r0!, {{s16-s31}}",
+    "    mov r2, lr",
+    "    stmdb r0!, {{r2, r4-r11}}",
+    "    bl {switch}",
+    "    ldmia r0!, {{r2, r4-r11}}",
+    "    tst r2, #0x10",
+    "    it eq",
+    "    vldmiaeq r0!, {{s16-s31}}",
+    "    movs r3, #0",
+    "    msr psplim, r3",
+    "    msr psp, r0",
+    "    msr psplim, r1",
+    "    isb",
+    "    bx r2",
+    ".size PendSV_Handler, . - PendSV_Handler",
+
+    ".section .text.SVC_Handler,\"ax\",%progbits",
+    ".global SVC_Handler",
+    ".type SVC_Handler, %function",
+    ".thumb_func",
+    "SVC_Handler:",
+    "    push {{r4, lr}}",
+    "    mov r0, lr",
+    "    bl {first}",
+    "    pop {{r4, lr}}",
+    "    cmp r0, #0",                   // Already Started, Nothing To Do
+    "    it eq",
+    "    bxeq lr",
+    "    ldmia r0!, {{r2, r4-r11}}",
+    "    movs r3, #0",
+    "    msr psplim, r3",
+    "    msr psp, r0",
+    "    msr psplim, r1",
+    "    isb",
+    "    bx r2",
+    ".size SVC_Handler, . - SVC_Handler",
+
+    switch = sym kernel_switch,
+    first = sym kernel_first
+);
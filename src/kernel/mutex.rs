@@ -0,0 +1,126 @@
+/* Mutex Between Threads, Blocking Rather Than Masking Interrupts Like sync::Mutex */
+/* A Thread Waiting On It Lends Its Priority To The Owner, Which Keeps The Highest Lent Priority Until It Holds No Mutex */
+/* Unlocking Hands It Straight To The Highest Waiter, So A Lower Thread Cannot Take It In Between */
+/* Only From Threads, Never From Interrupt Handlers */
+
+use core::cell::{Cell, UnsafeCell};
+use core::ops::{Deref, DerefMut};
+use crate::sync;
+
+/* owner When Nobody Holds It */
+const NO_OWNER:             usize = usize::MAX;
+
+pub struct Mutex<T> {
+    data:           UnsafeCell<T>,
+    owner:          Cell<usize>,            // Thread Slot
+    guarded:        Cell<bool>              // A Guard Is Out, Clear While Handed Over To A Waiter Not Yet Running
+}
+
+/* Access While Locked, Dropping It Unlocks */
+pub struct MutexGuard<'a, T> {
+    mutex:          &'a Mutex<T>
+}
+
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn init(data: T) -> Mutex<T> {
+        return Mutex {
+            data:       UnsafeCell::new(data),
+            owner:      Cell::new(NO_OWNER),
+            guarded:    Cell::new(false)
+        };
+    }
+
+    /* Block Until The Mutex Is Ours, Locking It Again From The Same Thread Panics */
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        return self.lock_timeout(None).unwrap();
+    }
+
+    /* As lock, Giving Up After timeout Ticks, Some(0) Never Blocks */
+    pub fn lock_timeout(&self, timeout: Option<u32>) -> Option<MutexGuard<'_, T>> {
+        let deadline = super::deadline(timeout);
+        loop {
+            let locked = sync::free(|cs| {
+                let me = super::get_current();
+                let owner = self.owner.get();
+                if owner == NO_OWNER {
+                    self.owner.set(me);
+                    self.guarded.set(true);
+                    super::hold(cs, me);
+                    return Some(true);
+                }
+                if owner == me {
+                    if self.guarded.replace(true) {
+                        panic!("Kernel Mutex Locked Twice");
+                    }
+                    return Some(true);      // Handed Over By unlock
+                }
+                if timeout == Some(0) || super::is_expired(deadline) {
+                    return Some(false);
+                }
+                super::inherit(cs, owner, super::get_priority(me));
+                super::block(cs, self.object(), deadline);
+                return None;
+            });
+            match locked {
+                Some(true) => {
+                    return Some(MutexGuard { mutex: self });
+                } Some(false) => {
+                    return None;
+                } None => {
+                }
+            }
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        return self.owner.get() != NO_OWNER;
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        return self.data.get_mut();
+    }
+
+    fn unlock(&self) {
+        sync::free(|cs| {
+            self.guarded.set(false);
+            super::release(cs, self.owner.get());
+            match super::wake(cs, self.object()) {
+                Some(next) => {
+                    self.owner.set(next);
+                    super::hold(cs, next);
+                    if let Some(priority) = super::get_waiter_priority(cs, self.object()) {
+                        super::inherit(cs, next, priority);
+                    }
+                } None => {
+                    self.owner.set(NO_OWNER);
+                }
+            }
+        });
+    }
+
+    fn object(&self) -> usize {
+        return self as *const Mutex<T> as usize;
+    }
+}
+
+impl<'a, T> Deref for MutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        return unsafe { &*self.mutex.data.get() };
+    }
+}
+
+impl<'a, T> DerefMut for MutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        return unsafe { &mut *self.mutex.data.get() };
+    }
+}
+
+impl<'a, T> Drop for MutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
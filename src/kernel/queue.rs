@@ -0,0 +1,120 @@
+/* Fixed Size Message Queue, Items Are Copied In And Out */
+/* Blocking send And recv Are For Threads, try_send And try_recv Are Also Safe From Interrupt Handlers */
+
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use crate::sync;
+
+pub struct Queue<T: Copy, const N: usize> {
+    items:          UnsafeCell<[MaybeUninit<T>; N]>,
+    head:           Cell<usize>,            // Oldest Item
+    len:            Cell<usize>
+}
+
+unsafe impl<T: Copy + Send, const N: usize> Sync for Queue<T, N> {}
+
+impl<T: Copy, const N: usize> Queue<T, N> {
+    pub const fn init() -> Queue<T, N> {
+        return Queue {
+            items:      UnsafeCell::new(unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() }),
+            head:       Cell::new(0),
+            len:        Cell::new(0)
+        };
+    }
+
+    /* Add item At The Back, Waiting Up To timeout Ticks For Room, None Waits Forever */
+    pub fn send(&self, item: T, timeout: Option<u32>) -> bool {
+        let deadline = super::deadline(timeout);
+        loop {
+            let sent = sync::free(|cs| {
+                if self.push(cs, item) {
+                    return Some(true);
+                }
+                if timeout == Some(0) || super::is_expired(deadline) {
+                    return Some(false);
+                }
+                super::block(cs, self.senders(), deadline);
+                return None;
+            });
+            if let Some(sent) = sent {
+                return sent;
+            }
+        }
+    }
+
+    /* Take The Front Item, Waiting Up To timeout Ticks For One, None Waits Forever */
+    pub fn recv(&self, timeout: Option<u32>) -> Option<T> {
+        let deadline = super::deadline(timeout);
+        loop {
+            let received = sync::free(|cs| {
+                if let Some(item) = self.pop(cs) {
+                    return Some(Some(item));
+                }
+                if timeout == Some(0) || super::is_expired(deadline) {
+                    return Some(None);
+                }
+                super::block(cs, self.receivers(), deadline);
+                return None;
+            });
+            if let Some(received) = received {
+                return received;
+            }
+        }
+    }
+
+    /* False When Full */
+    pub fn try_send(&self, item: T) -> bool {
+        return sync::free(|cs| self.push(cs, item));
+    }
+
+    /* None When Empty */
+    pub fn try_recv(&self) -> Option<T> {
+        return sync::free(|cs| self.pop(cs));
+    }
+
+    pub fn get_len(&self) -> usize {
+        return self.len.get();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len.get() == 0;
+    }
+
+    pub fn is_full(&self) -> bool {
+        return self.len.get() == N;
+    }
+
+    fn push(&self, cs: &sync::CriticalSection, item: T) -> bool {
+        let len = self.len.get();
+        if len == N {
+            return false;
+        }
+        let items = unsafe { &mut *self.items.get() };
+        items[(self.head.get() + len) % N] = MaybeUninit::new(item);
+        self.len.set(len + 1);
+        super::wake(cs, self.receivers());
+        return true;
+    }
+
+    fn pop(&self, cs: &sync::CriticalSection) -> Option<T> {
+        let len = self.len.get();
+        if len == 0 {
+            return None;
+        }
+        let items = unsafe { &*self.items.get() };
+        let item = unsafe { items[self.head.get()].assume_init() };
+        self.head.set((self.head.get() + 1) % N);
+        self.len.set(len - 1);
+        super::wake(cs, self.senders());
+        return Some(item);
+    }
+
+    /* Blocked Receivers Wait On The Queue's Address, Blocked Senders On The Byte After */
+    fn receivers(&self) -> usize {
+        return self as *const Queue<T, N> as usize;
+    }
+
+    fn senders(&self) -> usize {
+        return self.receivers() + 1;
+    }
+}
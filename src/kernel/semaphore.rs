@@ -0,0 +1,61 @@
+/* Counting Semaphore, give Is Safe From Interrupt Handlers, take Only From Threads */
+
+use core::cell::Cell;
+use crate::sync;
+
+pub struct Semaphore {
+    count:          Cell<u32>,
+    max:            u32
+}
+
+unsafe impl Sync for Semaphore {}
+
+impl Semaphore {
+    pub const fn init(count: u32, max: u32) -> Semaphore {
+        return Semaphore {
+            count:      Cell::new(count),
+            max
+        };
+    }
+
+    /* Add One And Wake The Highest Waiting Thread, False When Already At max */
+    pub fn give(&self) -> bool {
+        return sync::free(|cs| {
+            if self.count.get() >= self.max {
+                return false;
+            }
+            self.count.set(self.count.get() + 1);
+            super::wake(cs, self.object());
+            return true;
+        });
+    }
+
+    /* Take One, Waiting Up To timeout Ticks, None Waits Forever And Some(0) Never Blocks */
+    pub fn take(&self, timeout: Option<u32>) -> bool {
+        let deadline = super::deadline(timeout);
+        loop {
+            let taken = sync::free(|cs| {
+                if self.count.get() > 0 {
+                    self.count.set(self.count.get() - 1);
+                    return Some(true);
+                }
+                if timeout == Some(0) || super::is_expired(deadline) {
+                    return Some(false);
+                }
+                super::block(cs, self.object(), deadline);
+                return None;
+            });
+            if let Some(taken) = taken {
+                return taken;
+            }
+        }
+    }
+
+    pub fn get_count(&self) -> u32 {
+        return self.count.get();
+    }
+
+    fn object(&self) -> usize {
+        return self as *const Semaphore as usize;
+    }
+}
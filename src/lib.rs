@@ -1,15 +1,56 @@
-#![no_std] // EMBEDDED PROJECT CORE LIBRARY TO BE USED
+#![cfg_attr(not(test), no_std)] // EMBEDDED PROJECT CORE LIBRARY TO BE USED
+#![cfg_attr(test, allow(dead_code))] // Host Tests Build The Modules Without The Firmware That Uses Them
 
+/* Host Tests, cargo test --target <host triple> Builds Only The Modules Free Of Cortex-M Instructions */
+/* Everything Below Marked cfg(not(test)) Is The Firmware Itself */
+
+#[cfg(not(test))]
 use core::panic::PanicInfo;
+#[cfg(not(test))]
+use driver::serial::Serial;
 
-mod board;
 mod stm32hal;
 mod axis;
 mod driver;
-
+mod swtimer;
+mod canopen;
+#[cfg(not(test))]
+mod board;
+#[cfg(not(test))]
+mod startup;
+#[cfg(not(test))]
+mod trustzone;
+#[cfg(not(test))]
+mod mpu;
+#[cfg(not(test))]
+mod fault;
+#[cfg(not(test))]
+mod crash;
+#[cfg(not(test))]
+mod sync;
+#[cfg(not(test))]
+mod executor;
+#[cfg(not(test))]
+mod kernel;
+
+#[cfg(not(test))]
 const CLK:                  stm32hal::common::MsiRange = stm32hal::common::MsiRange::Clk16MHz;
 
+/* Software Timers, Ticked By TIM3 */
+#[cfg(not(test))]
+const TICK_HZ:              u32 = 1000;
+#[cfg(not(test))]
+const LED_PERIOD:           u32 = 1;    // Ticks
+#[cfg(not(test))]
+const SEQ_PERIOD:           u32 = 1;
+
+#[cfg(not(test))]
+static TIMERS:              sync::Mutex<swtimer::TimerWheel> = sync::Mutex::init(swtimer::TimerWheel::init());
+#[cfg(not(test))]
+static SEQ_STEP:            sync::AtomicFlag = sync::AtomicFlag::init();
+
 
+#[cfg(not(test))]
 #[no_mangle]
 pub extern "C" fn _system_init() {
     /* RCC Enabling of the bus */
@@ -20,13 +61,16 @@ pub extern "C" fn _system_init() {
     rcc.write_ahb2_enr(board::l552ze::RCC_GPIOB_AHB2EN);
     rcc.write_ahb2_enr(board::l552ze::RCC_GPIOC_AHB2EN);
     rcc.write_ahb2_enr(board::l552ze::RCC_GPIOD_AHB2EN);
-    rcc.write_apb1_enr1(board::l552ze::TIMER2_RCC_APB1R1_ENABLE);
     rcc.write_apb1_enr1(board::l552ze::TIMER3_RCC_APB1R1_ENABLE);
     rcc.write_apb2_enr(board::l552ze::SPI1_RCC_APB2R_ENABLE);
     rcc.write_apb1_enr1(board::l552ze::USART3_RCC_APB1R1_ENABLE);
+    rcc.write_apb1_enr1(board::l552ze::PWR_RCC_APB1R1_ENABLE);
+    rcc.write_apb1_enr1(board::l552ze::CRS_RCC_APB1R1_ENABLE);
+    driver::usb::clock_init(board::l552ze::RCC_BASE, board::l552ze::PWR_BASE, board::l552ze::CRS_BASE);
 }
 
 
+#[cfg(not(test))]
 #[no_mangle]
 pub extern "C" fn _start() {
     let freq = stm32hal::common::range(CLK);
@@ -35,16 +79,28 @@ pub extern "C" fn _start() {
     let gpiob =     stm32hal::gpio::Gpio::init(board::l552ze::GPIOB_BASE);
     let gpioc =     stm32hal::gpio::Gpio::init(board::l552ze::GPIOC_BASE);
     let gpiod =     stm32hal::gpio::Gpio::init(board::l552ze::GPIOD_BASE);
-    let seq_timer = stm32hal::timer::Timer::init(board::l552ze::TIMER2_BASE);
-    let int_timer = stm32hal::timer::Timer::init(board::l552ze::TIMER3_BASE);
-    let mut nvic =  stm32hal::nvic::Nvic::init(board::l552ze::NVIC_BASE);
+    let ticker =    stm32hal::timer::Timer::init(board::l552ze::TIMER3_BASE);
+    let nvic =      board::nvic::Nvic::init(board::l552ze::NVIC_BASE, board::l552ze::SCB_BASE);
     let spi =       stm32hal::spi::Spi::init(board::l552ze::SPI1_BASE);
     let usart =     stm32hal::usart::Usart::init(board::l552ze::USART3_BASE);
+    let usb =       driver::usb::Usb::init(board::l552ze::USB_BASE);
+    let mut usb_dev = driver::usb::UsbDevice::init(usb);
+    let mut cdc =   driver::cdc::Cdc::init(usb);
     
     /* USART */
     gpiod.otype(board::l552ze::USART3_TX, board::l552ze::USART_MODE, board::l552ze::USART_OTYPE, board::l552ze::USART_AF);
     gpiod.otype(board::l552ze::USART3_RX, board::l552ze::USART_MODE, board::l552ze::USART_OTYPE, board::l552ze::USART_AF);
     usart.open(stm32hal::usart::WordLen::Bits8, stm32hal::usart::StopLen::StopBit1, stm32hal::usart::BaudRate::Baud921600, 16000, stm32hal::usart::OverSample::Oversample16);
+    fault::init(fault_report, fault::Action::Halt);
+    crash::init();
+    if let Some(record) = crash::take() {
+        let _ = record.write(&mut driver::serial::Writer::init(&usart));
+    }
+
+    /* USB CDC Virtual COM Port */
+    gpioa.otype(board::l552ze::USB_DM, board::l552ze::USB_MODE, board::l552ze::USB_OTYPE, board::l552ze::USB_AF);
+    gpioa.otype(board::l552ze::USB_DP, board::l552ze::USB_MODE, board::l552ze::USB_OTYPE, board::l552ze::USB_AF);
+    usb_dev.open();
 
     /* SPI 1 Setup */
     gpiob.otype(board::l552ze::SPI1_MISO, board::l552ze::SPI_MODE, board::l552ze::SPI_OTYPE, board::l552ze::SPI_AF);
@@ -58,16 +114,22 @@ pub extern "C" fn _start() {
     gpiob.otype(board::l552ze::LED_BLU_PIN, board::l552ze::USER_LED_MODE, board::l552ze::USER_LED_OTYPE, board::l552ze::USER_LED_AF);
     gpioc.otype(board::l552ze::LED_GRN_PIN, board::l552ze::USER_LED_MODE, board::l552ze::USER_LED_OTYPE, board::l552ze::USER_LED_AF);
     
-    seq_timer.open(stm32hal::timer::TimerType::Cont, stm32hal::timer::Direction::Upcount);
-    seq_timer.set_scl(1000, freq, freq);
-    seq_timer.start();
+    /* LED Blink And Sequencing Share The TIM3 Tick */
+    TIMERS.lock(|timers| {
+        if let Some(led) = timers.create(led_toggle, swtimer::Dispatch::Interrupt) {
+            timers.start_periodic(led, LED_PERIOD);
+        }
+        if let Some(seq) = timers.create(seq_step, swtimer::Dispatch::Interrupt) {
+            timers.start_periodic(seq, SEQ_PERIOD);
+        }
+    });
 
-    int_timer.open(stm32hal::timer::TimerType::Cont, stm32hal::timer::Direction::Upcount);
-    int_timer.set_scl(1000, freq, freq);
-    int_timer.set_interrupt();
-    int_timer.start();
+    ticker.open(stm32hal::timer::TimerType::Cont, stm32hal::timer::Direction::Upcount);
+    ticker.set_scl(TICK_HZ, freq, freq);
+    ticker.set_interrupt();
+    ticker.start();
 
-    nvic.set_interrupt(board::l552ze::NvicIrq::TIM3_IRQ as u32);
+    nvic.enable(board::l552ze::NvicIrq::TIM3_IRQ);
 
     let mut i = 0;
     let mut buf:[u8; 8] = [0x03, 0x01, 0x02, 0x03 ,0x04, 0x05, 0x06, 0x0D];
@@ -75,7 +137,9 @@ pub extern "C" fn _start() {
     let mut spi_ibuf:[u8; 4] = [0x00, 0x00, 0x00, 0x00];
 
     loop {
-        if seq_timer.get_flag() { 
+        usb_dev.poll(&mut cdc);
+
+        if SEQ_STEP.take() {
             if i == 1 {
                 gpiob.set_pin(board::l552ze::LED_BLU);
             } else if i == 2 {
@@ -98,25 +162,34 @@ pub extern "C" fn _start() {
 
             usart.write(&spi_ibuf);
             usart.write(&buf);
+            cdc.write(&buf);
 
 
             i += 1;
-            seq_timer.clr_flag();
         }
     }
 }
 
+#[cfg(not(test))]
 #[no_mangle]
 pub extern "C" fn __aeabi_unwind_cpp_pr0() {
     loop {}
 }
 
-#[no_mangle]
-pub extern "C" fn TIM3_IRQHandler() {
-    let gpioa =     stm32hal::gpio::Gpio::init(board::l552ze::GPIOA_BASE);
-    let int_timer = stm32hal::timer::Timer::init(board::l552ze::TIMER3_BASE);
+#[cfg(not(test))]
+interrupt!(TIM3_IRQ => tim3_handler);
+
+#[cfg(not(test))]
+fn tim3_handler() {
+    let ticker =    stm32hal::timer::Timer::init(board::l552ze::TIMER3_BASE);
 
-    int_timer.clr_flag();
+    ticker.clr_flag();
+    TIMERS.lock(|timers| timers.tick()).run();
+}
+
+#[cfg(not(test))]
+fn led_toggle(_timer: swtimer::Handle) {
+    let gpioa =     stm32hal::gpio::Gpio::init(board::l552ze::GPIOA_BASE);
 
     if gpioa.get_pin(board::l552ze::LED_RED) {
         gpioa.clr_pin(board::l552ze::LED_RED);
@@ -125,7 +198,21 @@ pub extern "C" fn TIM3_IRQHandler() {
     }
 }
 
+/* The Sequence Itself Runs In The Main Loop */
+#[cfg(not(test))]
+fn seq_step(_timer: swtimer::Handle) {
+    SEQ_STEP.set();
+}
+
+/* Fault Reports Go Out On The Debug USART */
+#[cfg(not(test))]
+fn fault_report(buf: &[u8]) {
+    stm32hal::usart::Usart::init(board::l552ze::USART3_BASE).write(buf);
+}
+
+#[cfg(not(test))]
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
-    loop {}
+fn panic(info: &PanicInfo) -> ! {
+    let pc = crash::caller();
+    crash::panic(info, pc);
 }
@@ -0,0 +1,259 @@
+/* ARMv8-M Memory Protection Unit */
+/* Regions Are Built Into A Layout, Checked For 32 Byte Alignment And Overlap, Then Loaded In One Go */
+
+use core::arch::asm;
+use core::ptr;
+use crate::board::{l552ze, mpu, scb};
+
+/* Number Of Regions Per Security State On The Cortex-M33 */
+pub const MAX_REGIONS:      usize = 8;
+
+/* Region Base And Size Granularity */
+pub const ALIGN:            u32 = 32;
+
+/* Size Of The Guard Laid Below Each Stack */
+pub const GUARD_SIZE:       u32 = 32;
+
+/* Access Permissions (AP Field) */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Access {
+    PrivRw = 0,                 // Read / Write, Privileged Only
+    Rw,                         // Read / Write, Any Privilege
+    PrivRo,                     // Read Only, Privileged Only
+    Ro                          // Read Only, Any Privilege
+}
+
+/* Memory Types, Each Is An Attribute Index Into MAIR0 */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Attr {
+    Device = 0,                 // Device nGnRE, Peripherals
+    Flash,                      // Normal, Write Through, Read Allocate
+    Sram,                       // Normal, Write Back, Read / Write Allocate
+    NonCacheable                // Normal, Non-Cacheable, Buffers Shared With DMA
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MpuError {
+    Alignment,                  // Start Or Size Not A Multiple Of 32 Bytes, Or Size Zero
+    Overlap,                    // Overlaps A Region Of The Same Kind
+    Uncovered,                  // Guard Not Inside A Region Added Before It
+    Full                        // No Region Left
+}
+
+#[derive(Clone, Copy)]
+pub struct Region {
+    pub start:  u32,
+    pub size:   u32,
+    pub access: Access,
+    pub attr:   Attr,
+    pub xn:     bool,           // Execute Never
+    pub guard:  bool            // No Access, See Region::guard
+}
+
+/* Regions Checked And Ready To Load */
+pub struct Layout {
+    regions:    [Region; MAX_REGIONS],
+    count:      usize
+}
+
+pub struct Mpu {
+    mpu:        *mut mpu::MPUReg,
+    scb:        *mut scb::SCBReg
+}
+
+/* Control Register */
+const CTRL_ENABLE:          u32 = 1 << 0;
+const CTRL_PRIVDEFENA:      u32 = 1 << 2;   // Default Memory Map For Privileged Accesses Outside Every Region
+
+/* Region Registers */
+const RBAR_XN:              u32 = 1 << 0;
+const RBAR_AP:              u32 = 1;
+const RLAR_EN:              u32 = 1 << 0;
+const RLAR_ATTRINDX:        u32 = 1;
+const ADDR_MASK:            u32 = !(ALIGN - 1);
+
+/* MAIR0, One Byte Per Attr In Order */
+const MAIR_DEVICE_NGNRE:    u32 = 0x04;
+const MAIR_NORMAL_WT:       u32 = 0xAA;
+const MAIR_NORMAL_WB:       u32 = 0xFF;
+const MAIR_NORMAL_NC:       u32 = 0x44;
+const MAIR0:                u32 = MAIR_DEVICE_NGNRE | (MAIR_NORMAL_WT << 8) | (MAIR_NORMAL_WB << 16) | (MAIR_NORMAL_NC << 24);
+
+/* Use MemManage Rather Than HardFault For MPU Violations */
+const SHCSR_MEMFAULTENA:    u32 = 1 << 16;
+
+/* Default Layout Address Ranges, Both Security Aliases */
+const FLASH_START:          u32 = 0x08000000;
+const FLASH_END:            u32 = 0x0C080000;
+const SRAM_START:           u32 = 0x20000000;
+const SRAM_END:             u32 = 0x30040000;
+const PERIPH_START:         u32 = 0x40000000;
+const PERIPH_END:           u32 = 0x60000000;
+
+extern "C" {
+    /* Linker Script, Bottom Of The Main Stack */
+    static __StackLimit: u32;
+}
+
+impl Region {
+    pub const fn init(start: u32, size: u32, access: Access, attr: Attr, xn: bool) -> Region {
+        return Region {
+            start,
+            size,
+            access,
+            attr,
+            xn,
+            guard:  false
+        };
+    }
+
+    /* ARMv8-M Has No No-Access Permission, But An Address Hit By Two Enabled Regions Always Faults, */
+    /* So A Guard Is Laid Over Part Of A Region Added Before It */
+    pub const fn guard(start: u32, size: u32) -> Region {
+        return Region {
+            start,
+            size,
+            access: Access::PrivRo,
+            attr:   Attr::Sram,
+            xn:     true,
+            guard:  true
+        };
+    }
+
+    pub fn get_end(&self) -> u32 {
+        return self.start + (self.size - 1);
+    }
+
+    fn overlaps(&self, other: &Region) -> bool {
+        return self.start <= other.get_end() && other.start <= self.get_end();
+    }
+
+    fn contains(&self, other: &Region) -> bool {
+        return self.start <= other.start && other.get_end() <= self.get_end();
+    }
+
+    fn rbar(&self) -> u32 {
+        let mut rbar = (self.start & ADDR_MASK) | ((self.access as u32) << RBAR_AP);
+        if self.xn {
+            rbar |= RBAR_XN;
+        }
+        return rbar;
+    }
+
+    fn rlar(&self) -> u32 {
+        return (self.get_end() & ADDR_MASK) | ((self.attr as u32) << RLAR_ATTRINDX) | RLAR_EN;
+    }
+}
+
+impl Layout {
+    pub const fn init() -> Layout {
+        return Layout {
+            regions:    [Region::guard(0, ALIGN); MAX_REGIONS],
+            count:      0
+        };
+    }
+
+    pub fn add(&mut self, region: Region) -> Result<(), MpuError> {
+        if region.size == 0 || region.start % ALIGN != 0 || region.size % ALIGN != 0 ||
+            region.start.checked_add(region.size - 1).is_none() {
+            return Err(MpuError::Alignment);
+        } else if self.count == MAX_REGIONS {
+            return Err(MpuError::Full);
+        }
+
+        for other in self.get_regions().iter() {
+            if other.guard == region.guard && other.overlaps(&region) {
+                return Err(MpuError::Overlap);
+            }
+        }
+        if region.guard && !self.get_regions().iter().any(|other| !other.guard && other.contains(&region)) {
+            return Err(MpuError::Uncovered);
+        }
+
+        self.regions[self.count] = region;
+        self.count += 1;
+        return Ok(());
+    }
+
+    /* Guard The GUARD_SIZE Bytes Below A Stack Whose Lowest Address Is limit */
+    pub fn add_stack_guard(&mut self, limit: u32) -> Result<(), MpuError> {
+        if limit < GUARD_SIZE {
+            return Err(MpuError::Alignment);
+        }
+        return self.add(Region::guard(limit - GUARD_SIZE, GUARD_SIZE));
+    }
+
+    pub fn get_regions(&self) -> &[Region] {
+        return &self.regions[..self.count];
+    }
+
+    /* Flash Read Only And Executable, SRAM Read / Write But Never Executed, Peripherals Device Memory And Never */
+    /* Executed, Then A Guard Below The Main Stack And Each Stack In stacks (Lowest Address Of Each) */
+    pub fn standard(stacks: &[u32]) -> Result<Layout, MpuError> {
+        let mut layout = Layout::init();
+        layout.add(Region::init(FLASH_START, FLASH_END - FLASH_START, Access::Ro, Attr::Flash, false))?;
+        layout.add(Region::init(SRAM_START, SRAM_END - SRAM_START, Access::Rw, Attr::Sram, true))?;
+        layout.add(Region::init(PERIPH_START, PERIPH_END - PERIPH_START, Access::Rw, Attr::Device, true))?;
+        layout.add_stack_guard(main_stack_limit())?;
+        for &limit in stacks.iter() {
+            layout.add_stack_guard(limit)?;
+        }
+        return Ok(layout);
+    }
+}
+
+impl Mpu {
+    pub fn init(base: u32, scb_base: u32) -> Mpu {
+        return Mpu {
+            mpu:        base as *mut mpu::MPUReg,
+            scb:        scb_base as *mut scb::SCBReg
+        };
+    }
+
+    /* Number Of Regions The MPU Implements */
+    pub fn get_regions(&self) -> usize {
+        return ((unsafe { ptr::read_volatile(ptr::addr_of!((*self.mpu).typer)) } >> 8) & 0xFF) as usize;
+    }
+
+    /* Load layout And Turn The MPU On, Privileged Code Keeps The Default Map Outside The Regions */
+    pub fn open(&self, layout: &Layout) {
+        self.close();
+        unsafe {
+            ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).mair0), MAIR0);
+            for i in 0..core::cmp::min(self.get_regions(), MAX_REGIONS) {
+                ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).rnr), i as u32);
+                if i < layout.count {
+                    ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).rbar), layout.regions[i].rbar());
+                    ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).rlar), layout.regions[i].rlar());
+                } else {
+                    ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).rlar), 0);
+                }
+            }
+
+            let shcsr = ptr::read_volatile(ptr::addr_of!((*self.scb).shcsr));
+            ptr::write_volatile(ptr::addr_of_mut!((*self.scb).shcsr), shcsr | SHCSR_MEMFAULTENA);
+            ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).ctrl), CTRL_ENABLE | CTRL_PRIVDEFENA);
+            asm!("dsb", "isb");
+        }
+    }
+
+    pub fn close(&self) {
+        unsafe {
+            asm!("dmb");
+            ptr::write_volatile(ptr::addr_of_mut!((*self.mpu).ctrl), 0);
+            asm!("dsb", "isb");
+        }
+    }
+}
+
+/* Lowest Address Of The Main Stack */
+pub fn main_stack_limit() -> u32 {
+    return ptr::addr_of!(__StackLimit) as u32;
+}
+
+/* Default Layout With Only The Main Stack Guarded, Loaded Into The MPU */
+pub fn open_default() -> Result<(), MpuError> {
+    let layout = Layout::standard(&[])?;
+    Mpu::init(l552ze::MPU_BASE, l552ze::SCB_BASE).open(&layout);
+    return Ok(());
+}
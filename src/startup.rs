@@ -0,0 +1,219 @@
+/* Vector Table And Reset Handler */
+/* The Core Exceptions Are Laid Out Here, The Device Interrupts Come From board::l552ze::NvicIrq (device_interrupts!) */
+/* Handlers Are Given With interrupt! And exception!, Which Only Accept Names That Have A Slot In The Table, */
+/* Slots Without One Go To Default_Handler Through The PROVIDE Lines In linker/vectors.ld */
+
+use core::arch::asm;
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/* One Vector Table Slot */
+#[derive(Clone, Copy)]
+pub union Vector {
+    pub handler:    unsafe extern "C" fn(),
+    pub reset:      unsafe extern "C" fn() -> !,
+    pub stack:      *const u32,
+    pub reserved:   u32
+}
+
+/* Core Exceptions That Take A Handler From exception!, The Fault Handlers Are Defined By fault.rs */
+/* Named As In The Handler Symbols */
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Exception {
+    NMI = 2,
+    SVC = 11,
+    DebugMon = 12,
+    PendSV = 14,
+    SysTick = 15
+}
+
+/* Linker Script Copy Table Entry, Words From Flash Into RAM */
+#[repr(C)]
+struct CopyEntry {
+    src:            *const u32,
+    dst:            *mut u32,
+    words:          u32
+}
+
+/* Linker Script Zero Table Entry */
+#[repr(C)]
+struct ZeroEntry {
+    dst:            *mut u32,
+    words:          u32
+}
+
+unsafe impl Sync for Vector {}
+
+extern "C" {
+    /* Linker Script */
+    static __StackTop: u32;
+    static __StackLimit: u32;
+    static __copy_table_start__: CopyEntry;
+    static __copy_table_end__: CopyEntry;
+    static __zero_table_start__: ZeroEntry;
+    static __zero_table_end__: ZeroEntry;
+
+    /* Exception Handlers, Default_Handler Unless Defined */
+    fn NMI_Handler();
+    fn HardFault_Handler();
+    fn MemManage_Handler();
+    fn BusFault_Handler();
+    fn UsageFault_Handler();
+    fn SecureFault_Handler();
+    fn SVC_Handler();
+    fn DebugMon_Handler();
+    fn PendSV_Handler();
+    fn SysTick_Handler();
+
+    /* Application, Clock Set Up Then The Main Loop */
+    fn _system_init();
+    fn _start();
+}
+
+/* Start Of The Vector Table, The Device Interrupts Follow In .vectors.interrupts */
+#[link_section = ".vectors"]
+#[export_name = "__Vectors"]
+static EXCEPTIONS:          [Vector; 16] = [
+    Vector { stack: ptr::addr_of!(__StackTop) },                // Initial Stack Pointer
+    Vector { reset: Reset_Handler },
+    Vector { handler: NMI_Handler },                            // -14
+    Vector { handler: HardFault_Handler },                      // -13
+    Vector { handler: MemManage_Handler },                      // -12
+    Vector { handler: BusFault_Handler },                       // -11
+    Vector { handler: UsageFault_Handler },                     // -10
+    Vector { handler: SecureFault_Handler },                    // -9
+    Vector { reserved: 0 },
+    Vector { reserved: 0 },
+    Vector { reserved: 0 },
+    Vector { handler: SVC_Handler },                            // -5
+    Vector { handler: DebugMon_Handler },                       // -4
+    Vector { reserved: 0 },
+    Vector { handler: PendSV_Handler },                         // -2
+    Vector { handler: SysTick_Handler }                         // -1
+];
+
+/* Stack Limits, .data Copied From Flash And .bss Zeroed, Then _system_init And _start */
+#[no_mangle]
+unsafe extern "C" fn Reset_Handler() -> ! {
+    asm!(
+        "msr msplim, {limit}",
+        "msr psplim, {limit}",
+        "msr psp, {top}",
+        limit = in(reg) ptr::addr_of!(__StackLimit),
+        top = in(reg) ptr::addr_of!(__StackTop),
+        options(nostack, preserves_flags)
+    );
+
+    let mut copy = ptr::addr_of!(__copy_table_start__);
+    while copy < ptr::addr_of!(__copy_table_end__) {
+        let entry = ptr::read_volatile(copy);
+        for i in 0..entry.words as usize {
+            ptr::write_volatile(entry.dst.add(i), ptr::read_volatile(entry.src.add(i)));
+        }
+        copy = copy.add(1);
+    }
+
+    let mut zero = ptr::addr_of!(__zero_table_start__);
+    while zero < ptr::addr_of!(__zero_table_end__) {
+        let entry = ptr::read_volatile(zero);
+        for i in 0..entry.words as usize {
+            ptr::write_volatile(entry.dst.add(i), 0);
+        }
+        zero = zero.add(1);
+    }
+    compiler_fence(Ordering::SeqCst);
+
+    _system_init();
+    _start();
+    loop {}
+}
+
+/* Any Vector Without A Handler, IPSR Holds Its Exception Number For The Debugger */
+#[no_mangle]
+extern "C" fn Default_Handler() -> ! {
+    loop {}
+}
+
+/* Compile Time String Compare, For Checking Handler Names */
+pub const fn str_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    return true;
+}
+
+/* NvicIrq Enum And The Device Part Of The Vector Table From One List Of Interrupt = Number => Handler */
+/* Numbers Not Listed Are Reserved And Left Zero */
+#[macro_export]
+macro_rules! device_interrupts {
+    ($($irq:ident = $n:literal => $handler:ident,)*) => {
+        #[derive(Clone, Copy, PartialEq)]
+        pub enum NvicIrq {
+            $($irq = $n,)*
+        }
+
+        /* Highest Interrupt Number Plus One */
+        pub const IRQ_COUNT:    usize = {
+            let mut count = 0;
+            $(if $n + 1 > count { count = $n + 1; })*
+            count
+        };
+
+        extern "C" {
+            $(fn $handler();)*
+        }
+
+        #[link_section = ".vectors.interrupts"]
+        #[used]
+        static INTERRUPTS:      [$crate::startup::Vector; IRQ_COUNT] = {
+            let mut table = [$crate::startup::Vector { reserved: 0 }; IRQ_COUNT];
+            $(table[$n] = $crate::startup::Vector { handler: $handler };)*
+            table
+        };
+
+        /* interrupt! Builds The Handler Name From The Interrupt Name, So Every Pair Must Follow It */
+        $(const _: () = assert!($crate::startup::str_eq(concat!(stringify!($irq), "Handler"), stringify!($handler)));)*
+    };
+}
+
+/* Handler For A Device Interrupt, interrupt!(TIM3_IRQ => tim3_handler) */
+/* Fails To Compile If The Name Is Not In NvicIrq, Defining One Twice Fails To Link */
+#[macro_export]
+macro_rules! interrupt {
+    ($irq:ident => $func:path) => {
+        const _: () = {
+            const _: $crate::board::l552ze::NvicIrq = $crate::board::l552ze::NvicIrq::$irq;
+
+            #[export_name = concat!(stringify!($irq), "Handler")]
+            extern "C" fn handler() {
+                $func();
+            }
+        };
+    };
+}
+
+/* Handler For A Core Exception, exception!(SysTick => tick) */
+/* Fails To Compile If The Name Is Not In startup::Exception */
+#[macro_export]
+macro_rules! exception {
+    ($name:ident => $func:path) => {
+        const _: () = {
+            const _: $crate::startup::Exception = $crate::startup::Exception::$name;
+
+            #[export_name = concat!(stringify!($name), "_Handler")]
+            extern "C" fn handler() {
+                $func();
+            }
+        };
+    };
+}
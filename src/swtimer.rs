@@ -0,0 +1,346 @@
+/* Software Timers On One Hardware Tick */
+/* A Hashed Wheel, Each Slot Holds The Timers Expiring On Ticks That Fall In It, So A Tick Only Looks At One Slot */
+/* Nothing Here Touches Hardware, The Tick Comes From Whoever Calls tick, A Timer Interrupt On Target Or A Test On The Host */
+/* Share It With The Tick Handler Through sync::Mutex, And Run The Expired Callbacks After The Lock Is Released: */
+/*     static TIMERS: sync::Mutex<TimerWheel> = sync::Mutex::init(TimerWheel::init()); */
+/*     fn tick_handler() { TIMERS.lock(|timers| timers.tick()).run(); } */
+/*     loop { TIMERS.lock(|timers| timers.take_deferred()).run(); } */
+
+/* One Bit Per Timer In Each Slot */
+pub const MAX_TIMERS:       usize = 32;
+
+/* Power Of Two, Timers Further Out Than This Sit In Their Slot For More Than One Turn */
+pub const SLOTS:            usize = 64;
+
+pub type Callback = fn(Handle);
+
+/* Where The Callback Runs */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dispatch {
+    Interrupt,      // From tick, Keep It Short
+    Deferred        // From take_deferred In The Main Loop, Expiries Before It Runs Count As One
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Handle(usize);
+
+#[derive(Clone, Copy)]
+struct SoftTimer {
+    callback:       Option<Callback>,       // None While The Slot Is Free
+    dispatch:       Dispatch,
+    expires:        u32,                    // Tick It Runs On
+    period:         u32,                    // 0 For One-Shot
+    active:         bool
+}
+
+pub struct TimerWheel {
+    timers:         [SoftTimer; MAX_TIMERS],
+    slots:          [u32; SLOTS],           // Active Timers By expires % SLOTS
+    now:            u32,
+    deferred:       u32                     // Deferred Timers Expired But Not Yet Run
+}
+
+/* Callbacks Due, Taken Out Of The Wheel So They Can Run Without Holding It */
+pub struct Expired {
+    mask:           u32,
+    callbacks:      [Option<Callback>; MAX_TIMERS]
+}
+
+impl Handle {
+    pub fn get_id(&self) -> usize {
+        return self.0;
+    }
+}
+
+impl SoftTimer {
+    const fn init() -> SoftTimer {
+        return SoftTimer {
+            callback:   None,
+            dispatch:   Dispatch::Deferred,
+            expires:    0,
+            period:     0,
+            active:     false
+        };
+    }
+}
+
+impl TimerWheel {
+    pub const fn init() -> TimerWheel {
+        return TimerWheel {
+            timers:     [SoftTimer::init(); MAX_TIMERS],
+            slots:      [0; SLOTS],
+            now:        0,
+            deferred:   0
+        };
+    }
+
+    /* Take A Free Timer, Stopped Until start Or start_periodic, None When All Are Taken */
+    pub fn create(&mut self, callback: Callback, dispatch: Dispatch) -> Option<Handle> {
+        let id = self.timers.iter().position(|timer| timer.callback.is_none())?;
+        self.timers[id] = SoftTimer {
+            callback:   Some(callback),
+            dispatch,
+            expires:    0,
+            period:     0,
+            active:     false
+        };
+        return Some(Handle(id));
+    }
+
+    /* Stop The Timer And Give Its Slot Back */
+    pub fn delete(&mut self, handle: Handle) {
+        self.cancel(handle);
+        self.timers[handle.0] = SoftTimer::init();
+    }
+
+    /* Run Once After delay Ticks, At Least 1, Restarting It If Already Running */
+    pub fn start(&mut self, handle: Handle, delay: u32) {
+        self.arm(handle, delay, 0);
+    }
+
+    /* Run Every period Ticks, At Least 1, The First Time period Ticks From Now */
+    pub fn start_periodic(&mut self, handle: Handle, period: u32) {
+        let period = core::cmp::max(period, 1);
+        self.arm(handle, period, period);
+    }
+
+    /* Move The Next Expiry To delay Ticks From Now, Keeping A Periodic Timer's Period */
+    pub fn reschedule(&mut self, handle: Handle, delay: u32) {
+        let period = self.timers[handle.0].period;
+        self.arm(handle, delay, period);
+    }
+
+    /* Stop The Timer, Dropping A Deferred Run Not Yet Taken, True If It Was Running */
+    pub fn cancel(&mut self, handle: Handle) -> bool {
+        let timer = self.timers[handle.0];
+        let bit = 1 << handle.0;
+        self.deferred &= !bit;
+        if !timer.active {
+            return false;
+        }
+        self.slots[slot(timer.expires)] &= !bit;
+        self.timers[handle.0].active = false;
+        return true;
+    }
+
+    pub fn is_active(&self, handle: Handle) -> bool {
+        return self.timers[handle.0].active;
+    }
+
+    /* Ticks Until The Next Run, None When Stopped */
+    pub fn get_remaining(&self, handle: Handle) -> Option<u32> {
+        let timer = &self.timers[handle.0];
+        if !timer.active {
+            return None;
+        }
+        return Some(timer.expires.wrapping_sub(self.now));
+    }
+
+    /* Ticks Since init, Wrapping */
+    pub fn get_now(&self) -> u32 {
+        return self.now;
+    }
+
+    /* Advance One Tick, Returns The Interrupt Callbacks Due And Sets Aside The Deferred Ones */
+    pub fn tick(&mut self) -> Expired {
+        self.now = self.now.wrapping_add(1);
+        let mut expired = Expired::init();
+
+        let mut pending = self.slots[slot(self.now)];
+        while pending != 0 {
+            let id = pending.trailing_zeros() as usize;
+            let bit = 1 << id;
+            pending &= !bit;
+
+            /* Timers Due On A Later Turn Share The Slot */
+            let timer = self.timers[id];
+            if timer.expires != self.now {
+                continue;
+            }
+
+            self.slots[slot(timer.expires)] &= !bit;
+            if timer.period != 0 {
+                let expires = timer.expires.wrapping_add(timer.period);
+                self.timers[id].expires = expires;
+                self.slots[slot(expires)] |= bit;
+            } else {
+                self.timers[id].active = false;
+            }
+
+            match timer.dispatch {
+                Dispatch::Interrupt => {
+                    expired.add(id, timer.callback);
+                } Dispatch::Deferred => {
+                    self.deferred |= bit;
+                }
+            }
+        }
+        return expired;
+    }
+
+    /* Deferred Callbacks Due Since The Last Call, For The Main Loop */
+    pub fn take_deferred(&mut self) -> Expired {
+        let mut expired = Expired::init();
+        let mut pending = self.deferred;
+        self.deferred = 0;
+        while pending != 0 {
+            let id = pending.trailing_zeros() as usize;
+            pending &= !(1 << id);
+            expired.add(id, self.timers[id].callback);
+        }
+        return expired;
+    }
+
+    fn arm(&mut self, handle: Handle, delay: u32, period: u32) {
+        self.cancel(handle);
+        let expires = self.now.wrapping_add(core::cmp::max(delay, 1));
+        let timer = &mut self.timers[handle.0];
+        timer.expires = expires;
+        timer.period = period;
+        timer.active = true;
+        self.slots[slot(expires)] |= 1 << handle.0;
+    }
+}
+
+impl Expired {
+    const fn init() -> Expired {
+        return Expired {
+            mask:       0,
+            callbacks:  [None; MAX_TIMERS]
+        };
+    }
+
+    /* One Bit Per Timer Id */
+    pub fn get_mask(&self) -> u32 {
+        return self.mask;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.mask == 0;
+    }
+
+    /* Call Each Callback, Lowest Timer Id First */
+    pub fn run(self) {
+        let mut pending = self.mask;
+        while pending != 0 {
+            let id = pending.trailing_zeros() as usize;
+            pending &= !(1 << id);
+            if let Some(callback) = self.callbacks[id] {
+                callback(Handle(id));
+            }
+        }
+    }
+
+    fn add(&mut self, id: usize, callback: Option<Callback>) {
+        self.mask |= 1 << id;
+        self.callbacks[id] = callback;
+    }
+}
+
+fn slot(tick: u32) -> usize {
+    return (tick as usize) & (SLOTS - 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nothing(_timer: Handle) {}
+
+    /* Ticks Until handle Next Shows Up In An Expired Mask, Up To limit */
+    fn ticks_to_expiry(wheel: &mut TimerWheel, handle: Handle, limit: u32) -> Option<u32> {
+        return (1..=limit).find(|_| (wheel.tick().get_mask() & (1 << handle.get_id())) != 0);
+    }
+
+    #[test]
+    fn delay_past_one_turn_waits_for_its_own_turn() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Interrupt).unwrap();
+        wheel.start(timer, (3 * SLOTS as u32) + 5);
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 1000), Some((3 * SLOTS as u32) + 5));
+        assert!(!wheel.is_active(timer));
+    }
+
+    #[test]
+    fn tick_count_wraps() {
+        let mut wheel = TimerWheel::init();
+        wheel.now = u32::MAX - 2;
+        let timer = wheel.create(nothing, Dispatch::Interrupt).unwrap();
+        wheel.start_periodic(timer, 4);
+        assert_eq!(wheel.get_remaining(timer), Some(4));
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 10), Some(4));
+        assert_eq!(wheel.get_now(), 1);
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 10), Some(4));
+    }
+
+    #[test]
+    fn cancel_while_pending() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Interrupt).unwrap();
+        wheel.start(timer, 10);
+        for _ in 0..5 {
+            wheel.tick();
+        }
+        assert!(wheel.cancel(timer));
+        assert!(!wheel.cancel(timer));
+        assert_eq!(wheel.get_remaining(timer), None);
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 2 * SLOTS as u32), None);
+    }
+
+    #[test]
+    fn cancel_drops_deferred_run() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Deferred).unwrap();
+        wheel.start_periodic(timer, 2);
+        wheel.tick();
+        assert!(wheel.tick().is_empty());
+        wheel.cancel(timer);
+        assert!(wheel.take_deferred().is_empty());
+    }
+
+    #[test]
+    fn deferred_expiries_count_once() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Deferred).unwrap();
+        wheel.start_periodic(timer, 1);
+        for _ in 0..3 {
+            assert!(wheel.tick().is_empty());
+        }
+        assert_eq!(wheel.take_deferred().get_mask(), 1 << timer.get_id());
+        assert!(wheel.take_deferred().is_empty());
+    }
+
+    #[test]
+    fn reschedule_keeps_period() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Interrupt).unwrap();
+        wheel.start_periodic(timer, 10);
+        for _ in 0..3 {
+            wheel.tick();
+        }
+        wheel.reschedule(timer, 2);
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 20), Some(2));
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 20), Some(10));
+    }
+
+    #[test]
+    fn reschedule_one_shot_across_slots() {
+        let mut wheel = TimerWheel::init();
+        let timer = wheel.create(nothing, Dispatch::Interrupt).unwrap();
+        wheel.start(timer, 5);
+        wheel.reschedule(timer, SLOTS as u32 + 5);
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 2 * SLOTS as u32), Some(SLOTS as u32 + 5));
+        assert_eq!(ticks_to_expiry(&mut wheel, timer, 2 * SLOTS as u32), None);
+    }
+
+    #[test]
+    fn delete_frees_the_timer() {
+        let mut wheel = TimerWheel::init();
+        let timers: Vec<Handle> = (0..MAX_TIMERS).map(|_| wheel.create(nothing, Dispatch::Interrupt).unwrap()).collect();
+        assert!(wheel.create(nothing, Dispatch::Interrupt).is_none());
+        wheel.start(timers[7], 3);
+        wheel.delete(timers[7]);
+        assert_eq!(ticks_to_expiry(&mut wheel, timers[7], 10), None);
+        assert!(wheel.create(nothing, Dispatch::Interrupt) == Some(timers[7]));
+    }
+}
@@ -0,0 +1,174 @@
+/* Sharing State Between Main And Interrupt Handlers */
+/* Mutex Masks Every Interrupt While Borrowed, PriorityLock Only Those At Or Below Its Ceiling Through BASEPRI, */
+/* AtomicFlag Signals From A Handler Without Masking Anything */
+
+use core::arch::asm;
+use core::cell::{Cell, UnsafeCell};
+use core::sync::atomic::{AtomicBool, Ordering};
+use crate::board::nvic;
+
+/* Proof Interrupts Are Masked, Only Handed Out By free */
+pub struct CriticalSection {
+    _private:       ()
+}
+
+/* Data Only Reached With Interrupts Masked */
+pub struct Mutex<T> {
+    data:           UnsafeCell<T>,
+    locked:         Cell<bool>              // Set While lock Has Handed Out The Data
+}
+
+/* Data Shared By Handlers No Higher Than ceiling, Locking Raises BASEPRI To It So Higher Handlers Keep Running */
+/* Every User Must Run At Or Below The Ceiling Priority, Thread Mode Counts As The Lowest */
+pub struct PriorityLock<T> {
+    data:           UnsafeCell<T>,
+    locked:         Cell<bool>,
+    ceiling:        u8                      // NVIC Priority, 0 (Highest) To nvic::PRIO_LOWEST
+}
+
+/* One Bit Event From A Handler, No Masking Needed */
+pub struct AtomicFlag {
+    flag:           AtomicBool
+}
+
+/* Interrupt Mask Register */
+const PRIMASK_PM:           u32 = 1 << 0;
+
+unsafe impl<T: Send> Sync for Mutex<T> {}
+unsafe impl<T: Send> Sync for PriorityLock<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn init(data: T) -> Mutex<T> {
+        return Mutex {
+            data:       UnsafeCell::new(data),
+            locked:     Cell::new(false)
+        };
+    }
+
+    /* Shared Access For As Long As The Critical Section Lasts */
+    pub fn borrow<'cs>(&'cs self, _cs: &'cs CriticalSection) -> &'cs T {
+        return unsafe { &*self.data.get() };
+    }
+
+    /* Exclusive Access Inside Its Own Critical Section, Locking The Same Mutex Again From f Panics */
+    pub fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        return free(|_cs| {
+            if self.locked.replace(true) {
+                panic!("Mutex Locked Twice");
+            }
+            let result = f(unsafe { &mut *self.data.get() });
+            self.locked.set(false);
+            return result;
+        });
+    }
+
+    /* No Lock Needed While Holding The Only Reference */
+    pub fn get_mut(&mut self) -> &mut T {
+        return self.data.get_mut();
+    }
+}
+
+impl<T> PriorityLock<T> {
+    pub const fn init(data: T, ceiling: u8) -> PriorityLock<T> {
+        return PriorityLock {
+            data:       UnsafeCell::new(data),
+            locked:     Cell::new(false),
+            ceiling
+        };
+    }
+
+    pub fn get_ceiling(&self) -> u8 {
+        return self.ceiling;
+    }
+
+    /* Exclusive Access With BASEPRI At The Ceiling, Locking The Same Lock Again From f Panics */
+    /* A Ceiling Of 0 Cannot Be Reached By BASEPRI, So Every Interrupt Is Masked Instead */
+    pub fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        if self.ceiling == 0 {
+            return free(|_cs| self.enter(f));
+        }
+
+        let basepri = get_basepri();
+        raise_basepri(self.ceiling);
+        let result = self.enter(f);
+        set_basepri(basepri);
+        return result;
+    }
+
+    fn enter<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        if self.locked.replace(true) {
+            panic!("PriorityLock Locked Twice");
+        }
+        let result = f(unsafe { &mut *self.data.get() });
+        self.locked.set(false);
+        return result;
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        return self.data.get_mut();
+    }
+}
+
+impl AtomicFlag {
+    pub const fn init() -> AtomicFlag {
+        return AtomicFlag {
+            flag:       AtomicBool::new(false)
+        };
+    }
+
+    pub fn set(&self) {
+        self.flag.store(true, Ordering::Release);
+    }
+
+    pub fn clr(&self) {
+        self.flag.store(false, Ordering::Release);
+    }
+
+    pub fn get(&self) -> bool {
+        return self.flag.load(Ordering::Acquire);
+    }
+
+    /* Read And Clear In One Step, So An Event Raised Between The Two Is Never Lost */
+    pub fn take(&self) -> bool {
+        return self.flag.swap(false, Ordering::AcqRel);
+    }
+}
+
+/* Run f With Every Configurable Interrupt Masked, Nests And Leaves PRIMASK As It Found It */
+pub fn free<R, F: FnOnce(&CriticalSection) -> R>(f: F) -> R {
+    let primask: u32;
+    unsafe {
+        asm!("mrs {0}, primask", out(reg) primask, options(nomem, nostack, preserves_flags));
+        asm!("cpsid i", options(nostack, preserves_flags));
+    }
+    let result = f(&CriticalSection { _private: () });
+    if (primask & PRIMASK_PM) == 0 {
+        unsafe { asm!("cpsie i", options(nostack, preserves_flags)) };
+    }
+    return result;
+}
+
+/* Current BASEPRI As An NVIC Priority, None When Nothing Is Masked */
+pub fn get_basepri() -> Option<u8> {
+    let basepri: u32;
+    unsafe { asm!("mrs {0}, basepri", out(reg) basepri, options(nomem, nostack, preserves_flags)) };
+    if basepri == 0 {
+        return None;
+    }
+    return Some((basepri >> (8 - nvic::PRIO_BITS)) as u8);
+}
+
+/* Mask Interrupts At priority And Below, None Unmasks Them All, Priority 0 Cannot Be Masked This Way */
+pub fn set_basepri(priority: Option<u8>) {
+    let basepri = match priority {
+        Some(priority) => (core::cmp::min(priority, nvic::PRIO_LOWEST) as u32) << (8 - nvic::PRIO_BITS),
+        None => 0
+    };
+    unsafe { asm!("msr basepri, {0}", in(reg) basepri, options(nostack, preserves_flags)) };
+}
+
+/* Raise BASEPRI To priority, Never Lowers It, Priority 0 Has No Effect */
+pub fn raise_basepri(priority: u8) {
+    let basepri = (core::cmp::min(priority, nvic::PRIO_LOWEST) as u32) << (8 - nvic::PRIO_BITS);
+    unsafe { asm!("msr basepri_max, {0}", in(reg) basepri, options(nostack, preserves_flags)) };
+}
@@ -0,0 +1,194 @@
+/* TrustZone Secure / Non-Secure Split */
+/* Needs The TZEN Option Bit Set, Everything Here Runs In The Secure World Before The Non-Secure Image Starts */
+/* Memory Addresses With Bit 28 Set (0x0C000000 Flash, 0x30000000 SRAM, 0x50000000 Peripherals) Are Secure In The IDAU */
+/* An Address Takes The More Secure Of Its SAU And IDAU Attributes, So The SAU Opens Up The Non-Secure Aliases */
+/* The Non-Secure Flash Area Itself Is Set By The SECWM Option Bytes, The GTZC Does The SRAM And Peripherals */
+
+use core::arch::asm;
+use core::ptr;
+use crate::board::{l552ze, nvic, sau, scb};
+
+/* SAU Region Attribute */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Attr {
+    NonSecure,
+    NonSecureCallable           // Secure, But Holds Entry Points (SG) The Non-Secure World Can Call
+}
+
+/* SAU Control Register */
+const SAU_CTRL_ENABLE:      u32 = 1 << 0;
+const SAU_CTRL_ALLNS:       u32 = 1 << 1;   // With The SAU Disabled, Everything Non-Secure
+
+/* SAU Region Registers */
+const SAU_RLAR_ENABLE:      u32 = 1 << 0;
+const SAU_RLAR_NSC:         u32 = 1 << 1;
+const SAU_ADDR_MASK:        u32 = !0x1F;    // Regions Are 32 Byte Granular
+const SAU_TYPE_SREGION:     u32 = 0xFF;
+
+/* Application Interrupt And Reset Control Register */
+const AIRCR_VECTKEY:        u32 = 0x05FA << 16;
+const AIRCR_VECTKEY_MASK:   u32 = 0xFFFF << 16;
+const AIRCR_SYSRESETREQS:   u32 = 1 << 3;   // Only The Secure World Can Request A System Reset
+const AIRCR_BFHFNMINS:      u32 = 1 << 13;  // BusFault, HardFault And NMI Target The Non-Secure World
+const AIRCR_PRIS:           u32 = 1 << 14;  // Non-Secure Exceptions Get The Lower Half Of The Priorities
+
+/* Non-Secure Access Control Register, FPU Coprocessors */
+const NSACR_CP10:           u32 = 1 << 10;
+const NSACR_CP11:           u32 = 1 << 11;
+
+/* Test Target Response */
+const TT_SRVALID:           u32 = 1 << 17;
+const TT_SREGION:           u32 = 0xFF << 8;
+const TT_NSR:               u32 = 1 << 20;  // Readable From The Non-Secure World
+const TT_NSRW:              u32 = 1 << 21;  // Read / Writable From The Non-Secure World
+
+extern "C" {
+    /* Linker Script, Bounds Of The Secure Gateway Veneers In .gnu.sgstubs */
+    static __sg_start__: u32;
+    static __sg_end__: u32;
+}
+
+/* Number Of SAU Regions The Core Implements */
+pub fn sau_regions() -> u32 {
+    let sau = l552ze::SAU_BASE as *mut sau::SAUReg;
+    return unsafe { ptr::read_volatile(ptr::addr_of!((*sau).typer)) } & SAU_TYPE_SREGION;
+}
+
+/* Mark start To end (Inclusive) With attr, Addresses Outside Every Region Stay Secure */
+pub fn sau_region(region: u32, start: u32, end: u32, attr: Attr) {
+    let sau = l552ze::SAU_BASE as *mut sau::SAUReg;
+    let mut rlar = (end & SAU_ADDR_MASK) | SAU_RLAR_ENABLE;
+    if attr == Attr::NonSecureCallable {
+        rlar |= SAU_RLAR_NSC;
+    }
+
+    unsafe {
+        ptr::write_volatile(ptr::addr_of_mut!((*sau).rnr), region);
+        ptr::write_volatile(ptr::addr_of_mut!((*sau).rbar), start & SAU_ADDR_MASK);
+        ptr::write_volatile(ptr::addr_of_mut!((*sau).rlar), rlar);
+    }
+}
+
+/* Non-Secure Callable Region Around The Veneers The Linker Generated For nsc_entry! */
+pub fn sau_region_veneers(region: u32) {
+    let start = ptr::addr_of!(__sg_start__) as u32;
+    let end = ptr::addr_of!(__sg_end__) as u32;
+    if end > start {
+        sau_region(region, start, end - 1, Attr::NonSecureCallable);
+    }
+}
+
+pub fn sau_enable() {
+    let sau = l552ze::SAU_BASE as *mut sau::SAUReg;
+    unsafe {
+        ptr::write_volatile(ptr::addr_of_mut!((*sau).ctrl), SAU_CTRL_ENABLE);
+        asm!("dsb", "isb");
+    }
+}
+
+/* SAU Off With ALLNS Set, Every Address Is Non-Secure Except The IDAU's Secure Aliases, Off Without It All Would Be Secure */
+pub fn sau_disable() {
+    let sau = l552ze::SAU_BASE as *mut sau::SAUReg;
+    unsafe {
+        ptr::write_volatile(ptr::addr_of_mut!((*sau).ctrl), SAU_CTRL_ALLNS);
+        asm!("dsb", "isb");
+    }
+}
+
+/* Deliver An Interrupt To The Non-Secure Vector Table */
+pub fn set_irq_non_secure(irq: l552ze::NvicIrq, non_secure: bool) {
+    nvic::Nvic::init(l552ze::NVIC_BASE, l552ze::SCB_BASE).set_target_ns(irq, non_secure);
+}
+
+pub fn get_irq_non_secure(irq: l552ze::NvicIrq) -> bool {
+    return nvic::Nvic::init(l552ze::NVIC_BASE, l552ze::SCB_BASE).get_target_ns(irq);
+}
+
+/* Secure Exceptions Keep Priority Over Non-Secure Ones, Faults And Reset Stay With The Secure World */
+/* The Non-Secure World Is Given The FPU */
+pub fn secure_exceptions() {
+    let scb = l552ze::SCB_BASE as *mut scb::SCBReg;
+    unsafe {
+        let aircr = ptr::read_volatile(ptr::addr_of!((*scb).aircr));
+        let aircr = (aircr & !(AIRCR_VECTKEY_MASK | AIRCR_BFHFNMINS)) | AIRCR_VECTKEY | AIRCR_PRIS | AIRCR_SYSRESETREQS;
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).aircr), aircr);
+        let nsacr = ptr::read_volatile(ptr::addr_of!((*scb).nsacr));
+        ptr::write_volatile(ptr::addr_of_mut!((*scb).nsacr), nsacr | NSACR_CP10 | NSACR_CP11);
+        asm!("dsb", "isb");
+    }
+}
+
+/* True If The Non-Secure World May Access addr To addr + len, For Checking Pointers Passed Into An Entry Point */
+pub fn check_non_secure(addr: u32, len: u32, write: bool) -> bool {
+    if len == 0 {
+        return true;
+    }
+    let last = match addr.checked_add(len - 1) {
+        Some(last) => last,
+        None => return false
+    };
+
+    let first = test_target(addr);
+    let end = test_target(last);
+    let access = if write { TT_NSRW } else { TT_NSR };
+
+    /* Both Ends Accessible And In The Same SAU Region, So Everything Between Is Too */
+    return (first & access) != 0 && (end & access) != 0 &&
+        (first & TT_SRVALID) != 0 && (first & (TT_SRVALID | TT_SREGION)) == (end & (TT_SRVALID | TT_SREGION));
+}
+
+/* Attributes Of addr As Seen By The Non-Secure World (TTA) */
+fn test_target(addr: u32) -> u32 {
+    let result: u32;
+    unsafe { asm!("tta {0}, {1}", out(reg) result, in(reg) addr, options(nomem, nostack, preserves_flags)) };
+    return result;
+}
+
+/* Start The Non-Secure Image Whose Vector Table Is At vtor, Never Returns */
+/* The Non-Secure World Then Only Gets Back In Through nsc_entry! Entry Points And Its Own Interrupts */
+pub fn boot_non_secure(vtor: u32) -> ! {
+    let scb_ns = l552ze::SCB_NS_BASE as *mut scb::SCBReg;
+    unsafe {
+        ptr::write_volatile(ptr::addr_of_mut!((*scb_ns).vtor), vtor);
+        let sp = ptr::read_volatile(vtor as *const u32);
+        let reset = ptr::read_volatile((vtor + 4) as *const u32) & !1;
+        asm!(
+            "msr msp_ns, {0}",
+            "dsb",
+            "isb",
+            "blxns {1}",
+            in(reg) sp,
+            in(reg) reset,
+            options(noreturn)
+        );
+    }
+}
+
+/* Non-Secure Callable Entry Point Named $name Calling The Secure extern "C" fn $func, Up To Four Word Arguments */
+/* The Linker Adds The SG Veneer (--cmse-implib), Scratch Registers And Flags Are Cleared Before Going Back */
+/* Pointers From The Non-Secure World Must Go Through check_non_secure Before Use */
+#[macro_export]
+macro_rules! nsc_entry {
+    ($name:ident => $func:path) => {
+        core::arch::global_asm!(
+            concat!(".section .text.", stringify!($name), ",\"ax\",%progbits"),
+            concat!(".global ", stringify!($name)),
+            concat!(".global __acle_se_", stringify!($name)),
+            concat!(".type ", stringify!($name), ", %function"),
+            concat!(".type __acle_se_", stringify!($name), ", %function"),
+            ".thumb_func",
+            concat!(stringify!($name), ":"),
+            concat!("__acle_se_", stringify!($name), ":"),
+            "push {{r4, lr}}",
+            "bl {func}",
+            "pop {{r4, lr}}",
+            "movs r1, #0",
+            "mov r2, r1",
+            "mov r3, r1",
+            "mov r12, r1",
+            "msr apsr_nzcvq, r1",
+            "bxns lr",
+            func = sym $func
+        );
+    };
+}